@@ -1,21 +1,21 @@
 use anyhow::Result;
 use std::sync::Arc;
 
-use anyhow::Context;
-
-use iwdrs::{adapter::Adapter as iwdAdapter, session::Session};
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Direction, Flex, Layout},
-    style::{Color, Style},
+    layout::{Alignment, Constraint},
+    style::Style,
     widgets::{Block, BorderType, Borders, Cell, Clear, Padding, Row, Table},
 };
 
-use crate::config::Config;
+use crate::layout::popup_area;
+use crate::theme::Theme;
+use crate::{backend::WifiBackend, config::Config};
 
 #[derive(Debug)]
 pub struct Adapter {
-    adapter: iwdAdapter,
+    backend: Arc<dyn WifiBackend>,
+    device_name: String,
     pub is_powered: bool,
     pub name: String,
     pub model: Option<String>,
@@ -25,80 +25,58 @@ pub struct Adapter {
 }
 
 impl Adapter {
-    pub async fn new(session: Arc<Session>, config: Arc<Config>) -> Result<Self> {
-        let adapter = session
-            .adapters()
-            .await?
-            .pop()
-            .context("No adapter found")?;
-
-        let is_powered = adapter.is_powered().await?;
-        let name = adapter.name().await?;
-        let model = adapter.model().await.ok();
-        let vendor = adapter.vendor().await.ok();
-        let supported_modes = adapter.supported_modes().await?;
+    pub async fn new(
+        backend: Arc<dyn WifiBackend>,
+        device_name: String,
+        config: Arc<Config>,
+    ) -> Result<Self> {
+        let adapter = backend.adapter(&device_name).await?;
 
         Ok(Self {
-            adapter,
-            is_powered,
-            name,
-            model,
-            vendor,
-            supported_modes,
+            backend,
+            device_name,
+            is_powered: adapter.is_powered,
+            name: adapter.name,
+            model: adapter.model,
+            vendor: adapter.vendor,
+            supported_modes: adapter.supported_modes,
             config,
         })
     }
 
     pub async fn refresh(&mut self) -> Result<()> {
-        self.is_powered = self.adapter.is_powered().await?;
+        self.is_powered = self.backend.adapter(&self.device_name).await?.is_powered;
         Ok(())
     }
 
-    pub fn render(&self, frame: &mut Frame, device_addr: String) {
-        let popup_layout = Layout::default()
-            .direction(ratatui::layout::Direction::Vertical)
-            .constraints([
-                Constraint::Fill(1),
-                Constraint::Length(9),
-                Constraint::Fill(1),
-            ])
-            .flex(Flex::Start)
-            .split(frame.area());
-
-        let area = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Fill(1),
-                Constraint::Min(80),
-                Constraint::Fill(1),
-            ])
-            .split(popup_layout[1])[1];
+    pub fn render(&self, frame: &mut Frame, device_addr: String, theme: &Theme) {
+        let area = popup_area(frame.area(), 80, 9);
 
         let mut rows = vec![
             Row::new(vec![
-                Cell::from("name").style(Style::default().bold().yellow()),
+                Cell::from("name").style(Style::default().bold().fg(theme.header)),
                 Cell::from(self.name.clone()),
             ]),
             Row::new(vec![
-                Cell::from("address").style(Style::default().bold().yellow()),
+                Cell::from("address").style(Style::default().bold().fg(theme.header)),
                 Cell::from(device_addr),
             ]),
             Row::new(vec![
-                Cell::from("Supported modes").style(Style::default().bold().yellow()),
+                Cell::from("Supported modes").style(Style::default().bold().fg(theme.header)),
                 Cell::from(self.supported_modes.clone().join(" ")),
             ]),
         ];
 
         if let Some(model) = &self.model {
             rows.push(Row::new(vec![
-                Cell::from("model").style(Style::default().bold().yellow()),
+                Cell::from("model").style(Style::default().bold().fg(theme.header)),
                 Cell::from(model.clone()),
             ]));
         }
 
         if let Some(vendor) = &self.vendor {
             rows.push(Row::new(vec![
-                Cell::from("vendor").style(Style::default().bold().yellow()),
+                Cell::from("vendor").style(Style::default().bold().fg(theme.header)),
                 Cell::from(vendor.clone()),
             ]));
         }
@@ -113,11 +91,11 @@ impl Adapter {
                     .title_alignment(Alignment::Center)
                     .padding(Padding::uniform(1))
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Green))
+                    .border_style(theme.border(true))
                     .border_type(BorderType::Thick),
             )
             .column_spacing(3)
-            .row_highlight_style(Style::default().bg(Color::DarkGray).fg(Color::White));
+            .row_highlight_style(theme.highlight());
 
         frame.render_widget(Clear, area);
         frame.render_widget(device_infos_table, area);
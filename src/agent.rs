@@ -2,10 +2,13 @@ use async_channel::{Receiver, Sender};
 use std::sync::{Arc, atomic::AtomicBool};
 use tokio::sync::mpsc::UnboundedSender;
 
+use async_trait::async_trait;
 use iwdrs::error::agent::Canceled;
-use iwdrs::{agent::Agent, network::Network};
 
-use crate::event::Event;
+use crate::{
+    backend::{Agent, SignalLevel, SignalLevelAgent},
+    event::Event,
+};
 
 #[derive(Debug, Clone)]
 pub struct AuthAgent {
@@ -44,12 +47,12 @@ impl AuthAgent {
     }
 }
 
+#[async_trait]
 impl Agent for AuthAgent {
-    async fn request_passphrase(&self, network: &Network) -> Result<String, Canceled> {
+    async fn request_passphrase(&self, network_name: String) -> Result<String, Canceled> {
         self.psk_required
             .store(true, std::sync::atomic::Ordering::Relaxed);
 
-        let network_name = network.name().await.map_err(|_| Canceled())?;
         self.event_sender
             .send(Event::Auth(network_name))
             .map_err(|_| Canceled())?;
@@ -71,12 +74,11 @@ impl Agent for AuthAgent {
 
     async fn request_private_key_passphrase(
         &self,
-        network: &Network,
+        network_name: String,
     ) -> Result<String, iwdrs::error::agent::Canceled> {
         self.private_key_passphrase_required
             .store(true, std::sync::atomic::Ordering::Relaxed);
 
-        let network_name = network.name().await.map_err(|_| Canceled())?;
         self.event_sender
             .send(Event::AuthReqKeyPassphrase(network_name))
             .map_err(|_| Canceled())?;
@@ -98,11 +100,10 @@ impl Agent for AuthAgent {
 
     async fn request_user_name_and_passphrase(
         &self,
-        network: &Network,
+        network_name: String,
     ) -> Result<(String, String), iwdrs::error::agent::Canceled> {
         self.username_and_password_required
             .store(true, std::sync::atomic::Ordering::Relaxed);
-        let network_name = network.name().await.map_err(|_| Canceled())?;
         self.event_sender
             .send(Event::AuthReqUsernameAndPassword(network_name))
             .map_err(|_| Canceled())?;
@@ -124,17 +125,13 @@ impl Agent for AuthAgent {
 
     async fn request_user_password(
         &self,
-        network: &Network,
-        user_name: Option<&String>,
+        network_name: String,
+        user_name: Option<String>,
     ) -> Result<String, iwdrs::error::agent::Canceled> {
         self.password_required
             .store(true, std::sync::atomic::Ordering::Relaxed);
-        let network_name = network.name().await.map_err(|_| Canceled())?;
         self.event_sender
-            .send(Event::AuthRequestPassword((
-                network_name,
-                user_name.cloned(),
-            )))
+            .send(Event::AuthRequestPassword((network_name, user_name)))
             .map_err(|_| Canceled())?;
 
         tokio::select! {
@@ -152,3 +149,59 @@ impl Agent for AuthAgent {
         }
     }
 }
+
+/// Agent used by the non-interactive commands: it answers passphrase requests with
+/// the passphrase given on the command line and cancels every other request.
+#[derive(Debug, Clone, Default)]
+pub struct PassphraseAgent {
+    passphrase: Option<String>,
+}
+
+impl PassphraseAgent {
+    pub fn new(passphrase: Option<String>) -> Self {
+        Self { passphrase }
+    }
+}
+
+#[async_trait]
+impl Agent for PassphraseAgent {
+    async fn request_passphrase(&self, _network_name: String) -> Result<String, Canceled> {
+        self.passphrase.clone().ok_or(Canceled())
+    }
+
+    async fn request_private_key_passphrase(
+        &self,
+        _network_name: String,
+    ) -> Result<String, Canceled> {
+        Err(Canceled())
+    }
+
+    async fn request_user_name_and_passphrase(
+        &self,
+        _network_name: String,
+    ) -> Result<(String, String), Canceled> {
+        Err(Canceled())
+    }
+
+    async fn request_user_password(
+        &self,
+        _network_name: String,
+        _user_name: Option<String>,
+    ) -> Result<String, Canceled> {
+        Err(Canceled())
+    }
+}
+
+/// Forwards the signal level changes of the connection to the event loop.
+#[derive(Debug, Clone)]
+pub struct SignalAgent {
+    pub event_sender: UnboundedSender<Event>,
+}
+
+impl SignalLevelAgent for SignalAgent {
+    fn changed(&self, device: &str, level: SignalLevel) {
+        let _ = self
+            .event_sender
+            .send(Event::SignalLevel(device.to_string(), level));
+    }
+}
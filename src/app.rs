@@ -1,12 +1,24 @@
-use anyhow::{Result, anyhow};
+use anyhow::{Context, Result, anyhow};
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::mpsc::UnboundedSender;
 
-use iwdrs::{modes::Mode, session::Session};
+use iwdrs::station::State;
+use ratatui::layout::Position;
 
 use crate::{
-    adapter::Adapter, agent::AuthAgent, config::Config, device::Device, event::Event,
-    mode::station::auth::Auth, notification::Notification, reset::Reset,
+    adapter::Adapter,
+    agent::{AuthAgent, SignalAgent},
+    backend::{DppRole, SignalLevel, WifiBackend},
+    config::Config,
+    device::{Device, selector::DeviceSelector, sorted_devices},
+    event::Event,
+    help::Help,
+    keymap::KeyBinding,
+    mode::{ViewMode, p2p::P2p, station::auth::Auth},
+    monitor::Changes,
+    notification::{Notification, NotificationLevel},
+    reset::Reset,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -20,67 +32,92 @@ pub enum FocusedBlock {
     AdapterInfos,
     AccessPointInput,
     AccessPointConnectedDevices,
+    P2pPeers,
     RequestKeyPasshphrase,
     RequestPassword,
     RequestUsernameAndPassword,
     ShareNetwork,
+    Dpp,
+    Wps,
+    NetworkDetails,
+    LinkQuality,
+    Traffic,
     ConnectHiddenNetwork,
+    DeviceSelector,
+}
+
+impl FocusedBlock {
+    /// The block to focus when the device is shown for the first time.
+    pub fn default_for(device: &Device) -> Self {
+        if device.is_powered {
+            match device.view_mode() {
+                ViewMode::Station => FocusedBlock::KnownNetworks,
+                ViewMode::Ap => FocusedBlock::AccessPoint,
+                ViewMode::P2p => FocusedBlock::P2pPeers,
+            }
+        } else {
+            FocusedBlock::Device
+        }
+    }
 }
 
 pub struct App {
     pub running: bool,
     pub focused_block: FocusedBlock,
     pub notifications: Vec<Notification>,
-    pub session: Arc<Session>,
+    pub backend: Arc<dyn WifiBackend>,
     pub adapter: Adapter,
     pub device: Device,
+    pub other_devices: Vec<Device>,
+    pub device_selector: Option<DeviceSelector>,
+    /// The overlay listing the keys of the focused block
+    pub help: Option<Help>,
     pub agent: AuthAgent,
     pub reset: Reset,
     pub config: Arc<Config>,
     pub auth: Auth,
     pub network_name_requiring_auth: Option<String>,
+    /// The keys of a sequence that is being typed
+    pub pending_keys: Vec<KeyBinding>,
+    /// When and where the last left click happened, to detect double clicks
+    pub last_click: Option<(Instant, Position)>,
 }
 
 impl App {
     pub async fn new(
+        backend: Arc<dyn WifiBackend>,
         sender: UnboundedSender<Event>,
         config: Arc<Config>,
-        mode: Mode,
+        mode: ViewMode,
+        device_name: Option<String>,
     ) -> Result<Self> {
-        let session = {
-            match iwdrs::session::Session::new().await {
-                Ok(session) => Arc::new(session),
-                Err(e) => {
-                    return Err(anyhow!(
-                        "Can not access the iwd service.
-Error: {}",
-                        e
-                    ));
-                }
-            }
+        let mut devices = Device::all(backend.clone(), config.clone()).await?;
+        let index = match &device_name {
+            Some(name) => devices
+                .iter()
+                .position(|device| &device.name == name)
+                .with_context(|| format!("No device named {} found", name))?,
+            None if devices.is_empty() => return Err(anyhow!("No device found")),
+            None => 0,
         };
+        let mut device = devices.remove(index);
+        device.set_mode(mode.iwd_mode()).await?;
+        if mode == ViewMode::P2p {
+            device.p2p = Some(P2p::new(backend.clone(), device.name.clone()).await?);
+        }
 
-        let adapter = match Adapter::new(session.clone(), config.clone()).await {
+        let adapter = match Adapter::new(backend.clone(), device.name.clone(), config.clone()).await
+        {
             Ok(v) => v,
             Err(e) => {
                 return Err(anyhow!("Can not access the iwd service: {}", e));
             }
         };
 
-        let device = Device::new(session.clone()).await?;
-        device.set_mode(mode).await?;
-
         let agent = AuthAgent::new(sender);
-        let _ = session.register_agent(agent.clone()).await?;
+        backend.register_agent(Arc::new(agent.clone())).await?;
 
-        let focused_block = if device.is_powered {
-            match device.mode {
-                Mode::Station => FocusedBlock::KnownNetworks,
-                _ => FocusedBlock::AccessPoint,
-            }
-        } else {
-            FocusedBlock::Device
-        };
+        let focused_block = FocusedBlock::default_for(&device);
 
         let reset = Reset::new(mode);
 
@@ -88,31 +125,34 @@ Error: {}",
             running: true,
             focused_block,
             notifications: Vec::new(),
-            session,
+            backend,
             adapter,
             agent,
             reset,
             device,
+            other_devices: devices,
+            device_selector: None,
+            help: None,
             config,
             auth: Auth::default(),
             network_name_requiring_auth: None,
+            pending_keys: Vec::new(),
+            last_click: None,
         })
     }
 
-    pub async fn reset(mode: Mode) -> Result<()> {
-        let session = {
-            match iwdrs::session::Session::new().await {
-                Ok(session) => Arc::new(session),
-                Err(e) => return Err(anyhow!("Can not access the iwd service: {}", e)),
-            }
-        };
-
-        let device = match Device::new(session.clone()).await {
+    pub async fn reset(
+        backend: Arc<dyn WifiBackend>,
+        config: Arc<Config>,
+        mode: ViewMode,
+        device_name: &str,
+    ) -> Result<()> {
+        let device = match Device::find(backend, config, Some(device_name)).await {
             Ok(v) => v,
             Err(e) => return Err(anyhow!("Can not access the iwd service: {}", e)),
         };
 
-        device.set_mode(mode).await?;
+        device.set_mode(mode.iwd_mode()).await?;
         Ok(())
     }
 
@@ -120,8 +160,196 @@ Error: {}",
         self.notifications.retain(|n| n.ttl > 0);
         self.notifications.iter_mut().for_each(|n| n.ttl -= 1);
 
-        self.device.refresh().await?;
-        self.adapter.refresh().await?;
+        self.device.sample_traffic().await;
+
+        if self.device.is_powered
+            && let Some(station) = &mut self.device.station
+        {
+            station.sample_link_quality().await;
+
+            if let Some(dpp) = station.refresh_dpp().await {
+                if self.focused_block == FocusedBlock::Dpp {
+                    self.focused_block = dpp.parent_block();
+                }
+                let message = match dpp.role {
+                    DppRole::Enrollee if station.state == State::Connected => {
+                        "The device has been provisioned"
+                    }
+                    _ => "DPP has stopped",
+                };
+                self.notifications.push(Notification {
+                    message: message.to_string(),
+                    level: NotificationLevel::Info,
+                    ttl: 3,
+                });
+            }
+
+            // A new station, after a power cycle or a mode switch, needs a new agent
+            let thresholds = &self.config.station.signal_thresholds;
+            if !station.has_signal_level_agent && !thresholds.is_empty() {
+                let agent = SignalAgent {
+                    event_sender: self.agent.event_sender.clone(),
+                };
+                match self
+                    .backend
+                    .register_signal_level_agent(&self.device.name, thresholds, Arc::new(agent))
+                    .await
+                {
+                    Ok(()) => {
+                        station.has_signal_level_agent = true;
+                        station.signal_level_agent_error = None;
+                    }
+                    // Tried again on the next tick
+                    Err(e) => {
+                        let message = e.to_string();
+                        if station.signal_level_agent_error.as_ref() != Some(&message) {
+                            self.notifications.push(Notification {
+                                message: format!("Can not receive the signal levels\n{}", message),
+                                level: NotificationLevel::Warning,
+                                ttl: 3,
+                            });
+                            station.signal_level_agent_error = Some(message);
+                        }
+                    }
+                }
+            }
+        }
+
+        if self.device.is_powered
+            && let Some(ap) = &mut self.device.ap
+            && ap.has_started
+        {
+            ap.refresh_connected_devices().await?;
+        }
+
+        if self.device.is_powered
+            && let Some(p2p) = &mut self.device.p2p
+        {
+            p2p.refresh().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Warns when the signal of the connection gets weaker than a threshold, and updates the
+    /// signal of the connected network right away.
+    pub async fn signal_level_changed(&mut self, device_name: &str, level: SignalLevel) {
+        if self.device.name != device_name {
+            return;
+        }
+        let Some(station) = &mut self.device.station else {
+            return;
+        };
+
+        let previous = station.signal_level.replace(level);
+        let notification = match (previous, level.min, level.max) {
+            // The first level is only worth a warning below the lowest threshold
+            (None, None, Some(max)) => Some((
+                format!("The signal is below {} dBm", max),
+                NotificationLevel::Warning,
+            )),
+            (Some(previous), _, Some(max)) if previous.min.is_some_and(|min| max <= min) => Some((
+                format!("The signal dropped below {} dBm", max),
+                NotificationLevel::Warning,
+            )),
+            (Some(previous), Some(min), _) if previous.max.is_some_and(|max| min >= max) => Some((
+                format!("The signal is back above {} dBm", min),
+                NotificationLevel::Info,
+            )),
+            _ => None,
+        };
+
+        if let Some((message, level)) = notification {
+            self.notifications.push(Notification {
+                message,
+                level,
+                ttl: 5,
+            });
+        }
+
+        station.refresh_connected_signal().await;
+    }
+
+    pub async fn refresh(&mut self, changes: Changes) -> Result<()> {
+        if changes.device {
+            self.refresh_devices().await?;
+        }
+        if changes.adapter {
+            self.adapter.refresh().await?;
+        }
+        self.device.refresh(changes).await?;
+
+        // The other devices are only shown in the selector, they should not bring the app down
+        for device in &mut self.other_devices {
+            let _ = device.refresh(changes).await;
+        }
+
+        Ok(())
+    }
+
+    /// Keeps track of the devices that are plugged in or removed.
+    async fn refresh_devices(&mut self) -> Result<()> {
+        let mut names = Vec::new();
+        for info in self.backend.devices().await? {
+            let name = info.name.clone();
+            if name != self.device.name
+                && !self.other_devices.iter().any(|device| device.name == name)
+                && let Ok(device) =
+                    Device::new(self.backend.clone(), info, self.config.clone()).await
+            {
+                self.other_devices.push(device);
+            }
+            names.push(name);
+        }
+
+        self.other_devices
+            .retain(|device| names.contains(&device.name));
+        self.other_devices.sort_by(|a, b| a.name.cmp(&b.name));
+
+        if !names.contains(&self.device.name) && !self.other_devices.is_empty() {
+            let name = self.other_devices[0].name.clone();
+            self.switch_device(&name).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns all the devices, sorted by name.
+    pub fn devices(&self) -> Vec<&Device> {
+        sorted_devices(&self.device, &self.other_devices)
+    }
+
+    pub fn open_device_selector(&mut self) {
+        let index = self
+            .devices()
+            .iter()
+            .position(|device| device.name == self.device.name)
+            .unwrap_or(0);
+        self.device_selector = Some(DeviceSelector::new(index));
+        self.focused_block = FocusedBlock::DeviceSelector;
+    }
+
+    /// Makes the device with the given name the one managed by the app.
+    pub async fn switch_device(&mut self, name: &str) -> Result<()> {
+        self.device_selector = None;
+
+        if name != self.device.name {
+            let index = self
+                .other_devices
+                .iter()
+                .position(|device| device.name == name)
+                .with_context(|| format!("No device named {} found", name))?;
+
+            let adapter =
+                Adapter::new(self.backend.clone(), name.to_string(), self.config.clone()).await?;
+
+            std::mem::swap(&mut self.device, &mut self.other_devices[index]);
+            self.other_devices.sort_by(|a, b| a.name.cmp(&b.name));
+            self.adapter = adapter;
+            self.reset = Reset::new(self.device.view_mode());
+        }
+
+        self.focused_block = FocusedBlock::default_for(&self.device);
 
         Ok(())
     }
@@ -0,0 +1,327 @@
+use anyhow::Result;
+pub mod fake;
+pub mod iwd;
+
+use std::{
+    fmt::{self, Debug},
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset};
+use iwdrs::{
+    error::agent::Canceled, hidden_network::HiddenNetwork, modes::Mode, network::NetworkType,
+    station::State, station::diagnostics::ActiveStationDiagnostics,
+};
+
+use crate::rfkill::RfkillState;
+
+pub use fake::FakeBackend;
+pub use iwd::IwdBackend;
+
+#[derive(Debug, Clone)]
+pub struct DeviceInfo {
+    pub name: String,
+    pub address: String,
+    pub mode: Mode,
+    pub is_powered: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct AdapterInfo {
+    pub name: String,
+    pub model: Option<String>,
+    pub vendor: Option<String>,
+    pub is_powered: bool,
+    pub supported_modes: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StationInfo {
+    pub state: State,
+    pub is_scanning: bool,
+    pub connected_network: Option<NetworkInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NetworkInfo {
+    pub name: String,
+    pub network_type: NetworkType,
+    pub is_connected: bool,
+    pub known_network: Option<KnownNetworkInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct KnownNetworkInfo {
+    pub name: String,
+    pub network_type: NetworkType,
+    pub is_autoconnect: bool,
+    pub is_hidden: bool,
+    pub last_connected: Option<DateTime<FixedOffset>>,
+}
+
+/// An access point of a network. iwd only reports the signal and the frequency of every access
+/// point in developer mode (`iwd -E`), otherwise they are `None`.
+#[derive(Debug, Clone)]
+pub struct BssInfo {
+    pub address: String,
+    /// Signal strength in 100 * dBm
+    pub signal: Option<i16>,
+    pub frequency_mhz: Option<u32>,
+}
+
+/// The kernel counters of a network interface, since it was brought up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InterfaceStatistics {
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_packets: u64,
+    pub tx_packets: u64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+}
+
+/// The side of a station in a Device Provisioning Protocol (Wi-Fi Easy Connect) exchange.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DppRole {
+    /// Receives a network from a configurator
+    Enrollee,
+    /// Hands the connected network to an enrollee
+    Configurator,
+}
+
+/// A running DPP exchange, `uri` is scanned by the other side as a QR code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DppInfo {
+    pub role: DppRole,
+    pub uri: String,
+}
+
+/// How long the access points stay in WPS mode, iwd gives up on a WPS connection after it.
+pub const WPS_WALK_TIME: Duration = Duration::from_secs(120);
+
+/// Why a WPS connection ended without a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WpsError {
+    Canceled,
+    Timeout,
+    /// Several access points are in push button mode
+    SessionOverlap,
+    InvalidPin,
+    Busy,
+    Failed,
+}
+
+impl fmt::Display for WpsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            WpsError::Canceled => "WPS canceled",
+            WpsError::Timeout => "No access point answered WPS in time",
+            WpsError::SessionOverlap => {
+                "Several access points are in push button mode, try again later"
+            }
+            WpsError::InvalidPin => "Invalid WPS PIN",
+            WpsError::Busy => "A WPS connection is already running",
+            WpsError::Failed => "WPS failed",
+        })
+    }
+}
+
+impl std::error::Error for WpsError {}
+
+/// The access point of a connection can only be picked when iwd runs in developer mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeveloperModeError;
+
+impl fmt::Display for DeveloperModeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(
+            "iwd does not run in developer mode, restart it with -E to pick an access point",
+        )
+    }
+}
+
+impl std::error::Error for DeveloperModeError {}
+
+#[derive(Debug, Clone, Default)]
+pub struct AccessPointInfo {
+    pub has_started: bool,
+    pub name: Option<String>,
+    pub frequency: Option<u32>,
+    pub is_scanning: Option<bool>,
+    pub supported_ciphers: Option<Vec<String>>,
+    pub used_cipher: Option<String>,
+}
+
+/// The Wi-Fi Direct (P2P) device of an adapter, it runs beside the station of the adapter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct P2pDeviceInfo {
+    /// The name shown to the peers
+    pub name: String,
+    pub is_enabled: bool,
+    /// How many more peers can be connected to
+    pub available_connections: u16,
+}
+
+/// A device found by the Wi-Fi Direct discovery.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct P2pPeerInfo {
+    pub address: String,
+    pub name: String,
+    /// The WSC category of the device, e.g. `display` or `telephone`
+    pub category: String,
+    pub subcategory: Option<String>,
+    pub is_connected: bool,
+    /// The interface of the P2P group once connected, e.g. `p2p-wlan0-0`
+    pub interface: Option<String>,
+    /// The address of the peer in the group
+    pub ip: Option<String>,
+}
+
+/// Everything impala needs from the wifi daemon. Devices are referred to by their interface
+/// name and networks by their SSID.
+///
+/// The operations return the iwdrs errors (e.g. `IWDError<ScanError>`) wrapped in
+/// `anyhow::Error`, so callers can downcast them to react to a specific failure.
+#[async_trait]
+pub trait WifiBackend: Debug + Send + Sync {
+    // Devices
+    async fn devices(&self) -> Result<Vec<DeviceInfo>>;
+    async fn set_mode(&self, device: &str, mode: Mode) -> Result<()>;
+    async fn set_power(&self, device: &str, is_powered: bool) -> Result<()>;
+    async fn adapter(&self, device: &str) -> Result<AdapterInfo>;
+    /// The traffic counters of the interface, read from the kernel rather than from iwd.
+    async fn statistics(&self, device: &str) -> Result<InterfaceStatistics>;
+    /// Whether the wifi radio is turned off by rfkill, read from the kernel as well.
+    async fn rfkill(&self) -> Result<RfkillState>;
+
+    // Station
+    async fn station(&self, device: &str) -> Result<StationInfo>;
+    /// The discovered networks with their signal strength (in 100 * dBm), strongest first.
+    async fn networks(&self, device: &str) -> Result<Vec<(NetworkInfo, i16)>>;
+    async fn hidden_networks(&self, device: &str) -> Result<Vec<HiddenNetwork>>;
+    /// The access points of a discovered network, strongest first when the signals are known.
+    async fn basic_service_sets(&self, device: &str, network: &str) -> Result<Vec<BssInfo>>;
+    async fn diagnostic(&self, device: &str) -> Result<Option<ActiveStationDiagnostics>>;
+    async fn scan(&self, device: &str) -> Result<()>;
+    async fn wait_for_scan_complete(&self, device: &str) -> Result<()>;
+    async fn connect(&self, device: &str, network: &str) -> Result<()>;
+    async fn connect_hidden_network(&self, device: &str, ssid: &str) -> Result<()>;
+    async fn disconnect(&self, device: &str) -> Result<()>;
+    /// Whether iwd runs in developer mode (`iwd -E`), the two methods below fail with a
+    /// `DeveloperModeError` otherwise.
+    async fn is_developer_mode(&self, device: &str) -> Result<bool>;
+    /// Connects to the network of an access point through that access point only.
+    async fn connect_bss(&self, device: &str, address: &str) -> Result<()>;
+    /// Moves the connection to another access point of the connected network.
+    async fn roam(&self, device: &str, address: &str) -> Result<()>;
+
+    // WPS
+    /// Connects to the access point whose WPS button is pressed. Returns once connected, or
+    /// with a `WpsError` after the walk time.
+    async fn wps_push_button(&self, device: &str) -> Result<()>;
+    /// A PIN to enter on the access point, for `wps_start_pin`.
+    async fn wps_generate_pin(&self, device: &str) -> Result<String>;
+    /// Connects to the access point the PIN is entered on, returns like `wps_push_button`.
+    async fn wps_start_pin(&self, device: &str, pin: &str) -> Result<()>;
+    async fn wps_cancel(&self, device: &str) -> Result<()>;
+
+    // Device provisioning (DPP)
+    /// Starts a DPP exchange of the station and returns its URI. A configurator needs the
+    /// station to be connected.
+    async fn start_dpp(&self, device: &str, role: DppRole) -> Result<String>;
+    async fn stop_dpp(&self, device: &str) -> Result<()>;
+    /// The running DPP exchange, iwd stops it once the network is handed over or on timeout.
+    async fn dpp(&self, device: &str) -> Result<Option<DppInfo>>;
+
+    // Known networks
+    async fn known_networks(&self) -> Result<Vec<KnownNetworkInfo>>;
+    async fn forget(&self, network: &str) -> Result<()>;
+    async fn set_autoconnect(&self, network: &str, is_autoconnect: bool) -> Result<()>;
+
+    // Access point
+    async fn access_point(&self, device: &str) -> Result<AccessPointInfo>;
+    /// The MAC addresses of the devices connected to the access point.
+    async fn access_point_clients(&self, device: &str) -> Result<Vec<String>>;
+    async fn start_access_point(&self, device: &str, ssid: &str, psk: &str) -> Result<()>;
+    async fn stop_access_point(&self, device: &str) -> Result<()>;
+    async fn scan_access_point(&self, device: &str) -> Result<()>;
+
+    // Wi-Fi Direct (P2P), peers are referred to by their address
+    /// The P2P device of the adapter of the device, `None` when the adapter has none.
+    async fn p2p_device(&self, device: &str) -> Result<Option<P2pDeviceInfo>>;
+    async fn set_p2p_enabled(&self, device: &str, is_enabled: bool) -> Result<()>;
+    /// Looks for peers until `stop_p2p_discovery` is called, or until impala exits.
+    async fn start_p2p_discovery(&self, device: &str) -> Result<()>;
+    async fn stop_p2p_discovery(&self, device: &str) -> Result<()>;
+    /// The discovered peers with their signal strength (in 100 * dBm), strongest first.
+    async fn p2p_peers(&self, device: &str) -> Result<Vec<(P2pPeerInfo, i16)>>;
+    /// Connects to the peer once the connection is accepted on it. Returns once connected, or
+    /// with a `WpsError` like `wps_push_button`.
+    async fn p2p_push_button(&self, device: &str, peer: &str) -> Result<()>;
+    /// A PIN to enter on the peer, for `p2p_start_pin`.
+    async fn p2p_generate_pin(&self, device: &str, peer: &str) -> Result<String>;
+    async fn p2p_start_pin(&self, device: &str, peer: &str, pin: &str) -> Result<()>;
+    async fn p2p_cancel(&self, device: &str, peer: &str) -> Result<()>;
+    async fn p2p_disconnect(&self, device: &str, peer: &str) -> Result<()>;
+
+    async fn register_agent(&self, agent: Arc<dyn Agent>) -> Result<()>;
+    /// Calls the agent right away with the signal level of the station, then every time the
+    /// signal of its connection crosses one of the thresholds (in dBm).
+    async fn register_signal_level_agent(
+        &self,
+        device: &str,
+        thresholds: &[i16],
+        agent: Arc<dyn SignalLevelAgent>,
+    ) -> Result<()>;
+}
+
+/// Answers the credentials requests made while connecting to a network.
+#[async_trait]
+pub trait Agent: Send + Sync {
+    async fn request_passphrase(&self, network_name: String) -> Result<String, Canceled>;
+
+    async fn request_private_key_passphrase(
+        &self,
+        network_name: String,
+    ) -> Result<String, Canceled>;
+
+    async fn request_user_name_and_passphrase(
+        &self,
+        network_name: String,
+    ) -> Result<(String, String), Canceled>;
+
+    async fn request_user_password(
+        &self,
+        network_name: String,
+        user_name: Option<String>,
+    ) -> Result<String, Canceled>;
+}
+
+/// The range the signal of a connection is in, between two thresholds. `None` is unbounded,
+/// e.g. `min` is `None` below the lowest threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignalLevel {
+    /// In dBm, included
+    pub min: Option<i16>,
+    /// In dBm, excluded
+    pub max: Option<i16>,
+}
+
+impl SignalLevel {
+    /// The level a signal (in dBm) is in.
+    pub fn new(signal: i16, thresholds: &[i16]) -> Self {
+        Self {
+            min: thresholds.iter().copied().filter(|t| *t <= signal).max(),
+            max: thresholds.iter().copied().filter(|t| *t > signal).min(),
+        }
+    }
+}
+
+/// Told when the signal of a connection moves to another level.
+pub trait SignalLevelAgent: Send + Sync {
+    fn changed(&self, device: &str, level: SignalLevel);
+}
@@ -0,0 +1,984 @@
+use anyhow::{Context, Result, anyhow};
+pub mod scenario;
+use std::{
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use iwdrs::{
+    error::{
+        IWDError,
+        access_point::{AccessPointStartError, AccessPointStopError},
+        network::ConnectError,
+        station::{DisconnectError, ScanError},
+    },
+    hidden_network::HiddenNetwork,
+    modes::Mode,
+    network::NetworkType,
+    station::{
+        State,
+        diagnostics::{
+            ActiveStationDiagnostics, Mode as DiagnosticMode, PairwiseCipher, StationSecurity,
+        },
+    },
+};
+use serde::Deserialize;
+
+use crate::backend::{
+    AccessPointInfo, AdapterInfo, Agent, BssInfo, DeveloperModeError, DeviceInfo, DppInfo, DppRole,
+    InterfaceStatistics, KnownNetworkInfo, NetworkInfo, P2pDeviceInfo, P2pPeerInfo, SignalLevel,
+    SignalLevelAgent, StationInfo, WPS_WALK_TIME, WifiBackend, WpsError,
+};
+use crate::rfkill::RfkillState;
+
+/// The whole state of the fake backend.
+#[derive(Debug, Clone, Default)]
+pub struct FakeState {
+    pub devices: Vec<FakeDevice>,
+    pub known_networks: Vec<KnownNetworkInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeDevice {
+    pub info: DeviceInfo,
+    pub adapter: AdapterInfo,
+    pub state: State,
+    pub is_scanning: bool,
+    pub connected_network: Option<String>,
+    /// The access point the station is connected through, the first one of the network when
+    /// `None`
+    pub connected_bss: Option<String>,
+    pub networks: Vec<FakeNetwork>,
+    pub hidden_networks: Vec<HiddenNetwork>,
+    pub access_point: AccessPointInfo,
+    pub access_point_clients: Vec<String>,
+    pub statistics: InterfaceStatistics,
+    /// The running DPP exchange, no device ever answers it
+    pub dpp: Option<DppInfo>,
+    pub is_wps_running: bool,
+    /// The Wi-Fi Direct device of the adapter, if it has one
+    pub p2p: Option<FakeP2p>,
+    /// iwd runs with `-E`, the access points can be picked
+    pub is_developer_mode: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeP2p {
+    pub info: P2pDeviceInfo,
+    pub is_discovering: bool,
+    pub peers: Vec<FakePeer>,
+    /// The address of the peer being connected to
+    pub connecting_peer: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FakePeer {
+    pub info: P2pPeerInfo,
+    /// Signal strength in 100 * dBm
+    pub signal: i16,
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeNetwork {
+    pub name: String,
+    pub network_type: NetworkType,
+    /// Signal strength in 100 * dBm
+    pub signal: i16,
+    pub frequency_mhz: u32,
+    /// The access points of the network, a single one with the signal and the frequency above
+    /// when empty
+    pub basic_service_sets: Vec<BssInfo>,
+    /// The passphrase expected by the network, any passphrase is accepted when `None`
+    pub passphrase: Option<String>,
+    /// The credentials asked for when connecting to a known 802.1x network
+    pub eap_prompt: Option<EapPrompt>,
+    /// The iwd error returned by every connection attempt, e.g. `Failed`
+    pub error: Option<String>,
+    /// The access point answers the WPS connections, with the push button or a PIN
+    pub wps: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EapPrompt {
+    PrivateKeyPassphrase,
+    UsernameAndPassphrase,
+    Password,
+}
+
+impl FakeDevice {
+    /// A powered station without any network around.
+    pub fn new(name: &str) -> Self {
+        Self {
+            info: DeviceInfo {
+                name: name.to_string(),
+                address: "02:00:00:00:00:00".to_string(),
+                mode: Mode::Station,
+                is_powered: true,
+            },
+            adapter: AdapterInfo {
+                name: "phy0".to_string(),
+                model: None,
+                vendor: None,
+                is_powered: true,
+                supported_modes: vec!["station".to_string(), "ap".to_string()],
+            },
+            state: State::Disconnected,
+            is_scanning: false,
+            connected_network: None,
+            connected_bss: None,
+            networks: Vec::new(),
+            hidden_networks: Vec::new(),
+            access_point: AccessPointInfo::default(),
+            access_point_clients: Vec::new(),
+            statistics: InterfaceStatistics::default(),
+            dpp: None,
+            is_wps_running: false,
+            p2p: Some(FakeP2p::new("impala")),
+            is_developer_mode: false,
+        }
+    }
+}
+
+impl FakeP2p {
+    /// A disabled P2P device without any peer around.
+    pub fn new(name: &str) -> Self {
+        Self {
+            info: P2pDeviceInfo {
+                name: name.to_string(),
+                is_enabled: false,
+                available_connections: 1,
+            },
+            is_discovering: false,
+            peers: Vec::new(),
+            connecting_peer: None,
+        }
+    }
+}
+
+impl FakeNetwork {
+    pub fn new(name: &str, network_type: NetworkType, signal: i16) -> Self {
+        Self {
+            name: name.to_string(),
+            network_type,
+            signal,
+            frequency_mhz: 2412,
+            basic_service_sets: Vec::new(),
+            passphrase: None,
+            eap_prompt: None,
+            error: None,
+            wps: false,
+        }
+    }
+}
+
+impl FakeNetwork {
+    /// The access points of the network, the one connected to comes first.
+    pub fn basic_service_sets(&self) -> Vec<BssInfo> {
+        if self.basic_service_sets.is_empty() {
+            // Made from the name, so that every network has its own access point
+            let hash = self.name.bytes().fold(0u16, |hash, byte| {
+                hash.wrapping_mul(31).wrapping_add(byte.into())
+            });
+            vec![BssInfo {
+                address: format!("02:00:00:00:{:02x}:{:02x}", hash >> 8, hash & 0xff),
+                signal: Some(self.signal),
+                frequency_mhz: Some(self.frequency_mhz),
+            }]
+        } else {
+            self.basic_service_sets.clone()
+        }
+    }
+}
+
+/// An in-memory backend, used to run impala without iwd or a wifi card.
+#[derive(Default)]
+pub struct FakeBackend {
+    state: Mutex<FakeState>,
+    agent: Mutex<Option<Arc<dyn Agent>>>,
+}
+
+impl std::fmt::Debug for FakeBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FakeBackend")
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FakeBackend {
+    pub fn new(state: FakeState) -> Self {
+        Self {
+            state: Mutex::new(state),
+            agent: Mutex::new(None),
+        }
+    }
+
+    /// A copy of the current state.
+    pub fn state(&self) -> FakeState {
+        self.state.lock().unwrap().clone()
+    }
+
+    fn with_device<T>(
+        &self,
+        device: &str,
+        f: impl FnOnce(&mut FakeDevice, &mut Vec<KnownNetworkInfo>) -> Result<T>,
+    ) -> Result<T> {
+        let mut state = self.state.lock().unwrap();
+        let FakeState {
+            devices,
+            known_networks,
+        } = &mut *state;
+
+        let device = devices
+            .iter_mut()
+            .find(|d| d.info.name == device)
+            .with_context(|| format!("No device named {} found", device))?;
+        f(device, known_networks)
+    }
+
+    fn with_station<T>(
+        &self,
+        device: &str,
+        f: impl FnOnce(&mut FakeDevice, &mut Vec<KnownNetworkInfo>) -> Result<T>,
+    ) -> Result<T> {
+        self.with_device(device, |d, known_networks| {
+            if !d.info.is_powered || d.info.mode != Mode::Station {
+                return Err(anyhow!("No station found for {}", d.info.name));
+            }
+            f(d, known_networks)
+        })
+    }
+
+    fn with_access_point<T>(
+        &self,
+        device: &str,
+        f: impl FnOnce(&mut FakeDevice) -> Result<T>,
+    ) -> Result<T> {
+        self.with_device(device, |d, _| {
+            if !d.info.is_powered || d.info.mode != Mode::Ap {
+                return Err(anyhow!("No AP found for {}", d.info.name));
+            }
+            f(d)
+        })
+    }
+
+    fn with_p2p<T>(&self, device: &str, f: impl FnOnce(&mut FakeP2p) -> Result<T>) -> Result<T> {
+        self.with_device(device, |d, _| {
+            let Some(p2p) = &mut d.p2p else {
+                return Err(anyhow!(
+                    "The adapter of {} does not support Wi-Fi Direct",
+                    d.info.name
+                ));
+            };
+            f(p2p)
+        })
+    }
+
+    fn agent(&self) -> Result<Arc<dyn Agent>> {
+        self.agent
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or(IWDError::<ConnectError>::OperationError(ConnectError::NoAgent).into())
+    }
+
+    /// Waits for an access point in WPS mode, as if its button was pressed or the PIN entered
+    /// after a few seconds.
+    async fn wps_connect(&self, device: &str) -> Result<()> {
+        self.with_station(device, |d, _| {
+            if d.is_wps_running {
+                return Err(WpsError::Busy.into());
+            }
+            d.is_wps_running = true;
+            Ok(())
+        })?;
+
+        let started_at = Instant::now();
+        loop {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            let elapsed = started_at.elapsed();
+
+            let network = self.with_station(device, |d, _| {
+                if !d.is_wps_running {
+                    return Err(WpsError::Canceled.into());
+                }
+                let network = if elapsed >= Duration::from_secs(3) {
+                    d.networks.iter().find(|net| net.wps).cloned()
+                } else {
+                    None
+                };
+                if network.is_some() || elapsed >= WPS_WALK_TIME {
+                    d.is_wps_running = false;
+                }
+                Ok(network)
+            })?;
+
+            match network {
+                Some(network) => return self.mark_connected(device, &network),
+                None if elapsed >= WPS_WALK_TIME => return Err(WpsError::Timeout.into()),
+                None => {}
+            }
+        }
+    }
+
+    /// Connects to a peer, as if the connection was accepted on it after a few seconds.
+    async fn p2p_connect(&self, device: &str, peer: &str) -> Result<()> {
+        self.with_p2p(device, |p2p| {
+            if p2p.connecting_peer.is_some() {
+                return Err(WpsError::Busy.into());
+            }
+            if p2p.info.available_connections == 0 {
+                return Err(anyhow!("No connection available"));
+            }
+            if !p2p.peers.iter().any(|p| p.info.address == peer) {
+                return Err(anyhow!("Peer {} not found", peer));
+            }
+            p2p.connecting_peer = Some(peer.to_string());
+            Ok(())
+        })?;
+
+        for _ in 0..3 {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            self.with_p2p(device, |p2p| match p2p.connecting_peer {
+                Some(_) => Ok(()),
+                None => Err(WpsError::Canceled.into()),
+            })?;
+        }
+
+        let interface = format!("p2p-{}-0", device);
+        self.with_p2p(device, |p2p| {
+            p2p.connecting_peer = None;
+            p2p.info.available_connections -= 1;
+            if let Some(p) = p2p.peers.iter_mut().find(|p| p.info.address == peer) {
+                p.info.is_connected = true;
+                p.info.interface = Some(interface);
+                p.info.ip = Some("192.168.49.1".to_string());
+            }
+            Ok(())
+        })
+    }
+
+    fn mark_connected(&self, device: &str, network: &FakeNetwork) -> Result<()> {
+        self.with_station(device, |d, known_networks| {
+            d.state = State::Connected;
+            d.connected_network = Some(network.name.clone());
+            d.connected_bss = None;
+
+            if !known_networks.iter().any(|net| net.name == network.name) {
+                known_networks.push(KnownNetworkInfo {
+                    name: network.name.clone(),
+                    network_type: network.network_type,
+                    is_autoconnect: true,
+                    is_hidden: false,
+                    last_connected: None,
+                });
+            }
+            if let Some(known_network) = known_networks
+                .iter_mut()
+                .find(|net| net.name == network.name)
+            {
+                known_network.last_connected = Some(chrono::Local::now().fixed_offset());
+            }
+            Ok(())
+        })
+    }
+}
+
+fn network_info(
+    network: &FakeNetwork,
+    device: &FakeDevice,
+    known_networks: &[KnownNetworkInfo],
+) -> NetworkInfo {
+    NetworkInfo {
+        name: network.name.clone(),
+        network_type: network.network_type,
+        is_connected: device.connected_network.as_ref() == Some(&network.name),
+        known_network: known_networks
+            .iter()
+            .find(|net| net.name == network.name)
+            .cloned(),
+    }
+}
+
+#[async_trait]
+impl WifiBackend for FakeBackend {
+    async fn devices(&self) -> Result<Vec<DeviceInfo>> {
+        let state = self.state.lock().unwrap();
+        Ok(state.devices.iter().map(|d| d.info.clone()).collect())
+    }
+
+    async fn set_mode(&self, device: &str, mode: Mode) -> Result<()> {
+        self.with_device(device, |d, _| {
+            if d.info.mode != mode {
+                d.info.mode = mode;
+                d.state = State::Disconnected;
+                d.connected_network = None;
+                d.access_point = AccessPointInfo::default();
+                d.access_point_clients.clear();
+            }
+            Ok(())
+        })
+    }
+
+    async fn set_power(&self, device: &str, is_powered: bool) -> Result<()> {
+        self.with_device(device, |d, _| {
+            d.info.is_powered = is_powered;
+            if !is_powered {
+                d.state = State::Disconnected;
+                d.connected_network = None;
+                d.access_point.has_started = false;
+            }
+            Ok(())
+        })
+    }
+
+    async fn adapter(&self, device: &str) -> Result<AdapterInfo> {
+        self.with_device(device, |d, _| Ok(d.adapter.clone()))
+    }
+
+    async fn statistics(&self, device: &str) -> Result<InterfaceStatistics> {
+        self.with_device(device, |d, _| {
+            // Some traffic flows between two reads while the device is connected
+            if d.connected_network.is_some() || d.access_point.has_started {
+                let statistics = &mut d.statistics;
+                statistics.rx_bytes += 1_250_000;
+                statistics.tx_bytes += 180_000;
+                statistics.rx_packets += 900;
+                statistics.tx_packets += 450;
+                if statistics.rx_packets % 9_000 == 0 {
+                    statistics.rx_dropped += 1;
+                }
+            }
+            Ok(d.statistics)
+        })
+    }
+
+    // The fake devices are never blocked
+    async fn rfkill(&self) -> Result<RfkillState> {
+        Ok(RfkillState::Unblocked)
+    }
+
+    async fn station(&self, device: &str) -> Result<StationInfo> {
+        self.with_station(device, |d, known_networks| {
+            let connected_network = d
+                .networks
+                .iter()
+                .find(|net| d.connected_network.as_ref() == Some(&net.name))
+                .map(|net| network_info(net, d, known_networks));
+
+            Ok(StationInfo {
+                state: d.state,
+                is_scanning: d.is_scanning,
+                connected_network,
+            })
+        })
+    }
+
+    async fn networks(&self, device: &str) -> Result<Vec<(NetworkInfo, i16)>> {
+        self.with_station(device, |d, known_networks| {
+            let mut networks: Vec<(NetworkInfo, i16)> = d
+                .networks
+                .iter()
+                .map(|net| (network_info(net, d, known_networks), net.signal))
+                .collect();
+            networks.sort_by_key(|(_, signal)| -signal);
+            Ok(networks)
+        })
+    }
+
+    async fn hidden_networks(&self, device: &str) -> Result<Vec<HiddenNetwork>> {
+        self.with_station(device, |d, _| Ok(d.hidden_networks.clone()))
+    }
+
+    async fn basic_service_sets(&self, device: &str, network: &str) -> Result<Vec<BssInfo>> {
+        self.with_station(device, |d, _| {
+            d.networks
+                .iter()
+                .find(|net| net.name == network)
+                .map(FakeNetwork::basic_service_sets)
+                .with_context(|| format!("Network {} not found", network))
+        })
+    }
+
+    async fn diagnostic(&self, device: &str) -> Result<Option<ActiveStationDiagnostics>> {
+        self.with_station(device, |d, _| {
+            let Some(network) = d
+                .networks
+                .iter()
+                .find(|net| d.connected_network.as_ref() == Some(&net.name))
+            else {
+                return Ok(None);
+            };
+
+            let security = match network.network_type {
+                NetworkType::Open => StationSecurity::Open,
+                NetworkType::Eap => StationSecurity::WPA2Enterprise,
+                _ => StationSecurity::WPA2Personal,
+            };
+
+            let mut basic_service_sets = network.basic_service_sets();
+            let index = basic_service_sets
+                .iter()
+                .position(|bss| d.connected_bss.as_ref() == Some(&bss.address))
+                .unwrap_or(0);
+            let bss = basic_service_sets.remove(index);
+            let frequency_mhz = bss.frequency_mhz.unwrap_or(network.frequency_mhz);
+            let rssi = bss.signal.unwrap_or(network.signal) / 100;
+
+            // A rough 802.11ax link on 80 MHz, which gets faster with the signal
+            let mcs = ((rssi + 90) / 4).clamp(0, 11) as u8;
+            let rate_kbps = (u64::from(mcs) + 1) * 100_000;
+            let pairwise_cipher = match network.network_type {
+                NetworkType::Open => None,
+                _ => Some(PairwiseCipher::Ccmp128),
+            };
+
+            Ok(Some(ActiveStationDiagnostics {
+                connected_bss: bss.address,
+                frequency_mhz,
+                channel: None,
+                security,
+                rssi: Some(rssi),
+                average_rssi: Some(rssi),
+                rx_mode: Some(DiagnosticMode::AX),
+                rx_rate_kbps: Some(rate_kbps),
+                rx_mcs: Some(mcs),
+                tx_mode: Some(DiagnosticMode::AX),
+                tx_rate_kbps: Some(rate_kbps * 9 / 10),
+                tx_mcs: Some(mcs.saturating_sub(1)),
+                pairwise_cipher,
+                inactive_time: None,
+                connected_time: None,
+            }))
+        })
+    }
+
+    async fn scan(&self, device: &str) -> Result<()> {
+        self.with_station(device, |d, _| {
+            if d.is_scanning {
+                return Err(IWDError::<ScanError>::OperationError(ScanError::Busy).into());
+            }
+            Ok(())
+        })
+    }
+
+    async fn wait_for_scan_complete(&self, device: &str) -> Result<()> {
+        self.with_station(device, |d, _| {
+            d.is_scanning = false;
+            Ok(())
+        })
+    }
+
+    async fn connect(&self, device: &str, network: &str) -> Result<()> {
+        let (network, is_known) = self.with_station(device, |d, known_networks| {
+            let network = d
+                .networks
+                .iter()
+                .find(|net| net.name == network)
+                .cloned()
+                .with_context(|| format!("Network {} not found", network))?;
+            let is_known = known_networks.iter().any(|net| net.name == network.name);
+            Ok((network, is_known))
+        })?;
+
+        if let Some(error) = &network.error {
+            let error = ConnectError::from_str(&format!("net.connman.iwd.{}", error))
+                .unwrap_or(ConnectError::Failed);
+            return Err(IWDError::<ConnectError>::OperationError(error).into());
+        }
+
+        let passphrase = match (network.network_type, is_known) {
+            (NetworkType::Open, _) => None,
+            (NetworkType::Eap, false) => {
+                return Err(
+                    IWDError::<ConnectError>::OperationError(ConnectError::NotConfigured).into(),
+                );
+            }
+            (NetworkType::Eap, true) => match network.eap_prompt {
+                Some(prompt) => {
+                    let agent = self.agent()?;
+                    let name = network.name.clone();
+                    let passphrase = match prompt {
+                        EapPrompt::PrivateKeyPassphrase => {
+                            agent.request_private_key_passphrase(name).await
+                        }
+                        EapPrompt::UsernameAndPassphrase => agent
+                            .request_user_name_and_passphrase(name)
+                            .await
+                            .map(|(_, passphrase)| passphrase),
+                        EapPrompt::Password => agent.request_user_password(name, None).await,
+                    };
+                    Some(passphrase)
+                }
+                None => None,
+            },
+            (NetworkType::Psk | NetworkType::Wep, true) => None,
+            (NetworkType::Psk | NetworkType::Wep, false) => {
+                Some(self.agent()?.request_passphrase(network.name.clone()).await)
+            }
+        };
+
+        if let Some(passphrase) = passphrase {
+            let passphrase = passphrase
+                .map_err(|_| IWDError::<ConnectError>::OperationError(ConnectError::Aborted))?;
+
+            if network
+                .passphrase
+                .as_ref()
+                .is_some_and(|expected| *expected != passphrase)
+            {
+                return Err(IWDError::<ConnectError>::OperationError(ConnectError::Failed).into());
+            }
+        }
+
+        self.mark_connected(device, &network)
+    }
+
+    async fn connect_hidden_network(&self, device: &str, ssid: &str) -> Result<()> {
+        self.with_station(device, |d, _| {
+            if d.networks.iter().any(|net| net.name == ssid) {
+                return Ok(());
+            }
+            if d.hidden_networks.is_empty() {
+                return Err(anyhow!("Network {} not found", ssid));
+            }
+
+            let hidden_network = d.hidden_networks.remove(0);
+            d.networks.push(FakeNetwork::new(
+                ssid,
+                hidden_network.network_type,
+                hidden_network.signal_strength,
+            ));
+            Ok(())
+        })?;
+
+        self.connect(device, ssid).await
+    }
+
+    async fn disconnect(&self, device: &str) -> Result<()> {
+        self.with_station(device, |d, _| {
+            if d.connected_network.is_none() {
+                return Err(IWDError::<DisconnectError>::OperationError(
+                    DisconnectError::NotConnected,
+                )
+                .into());
+            }
+            d.state = State::Disconnected;
+            d.connected_network = None;
+            d.connected_bss = None;
+            // A configurator has nothing left to hand over
+            if d.dpp.as_ref().map(|dpp| dpp.role) == Some(DppRole::Configurator) {
+                d.dpp = None;
+            }
+            Ok(())
+        })
+    }
+
+    async fn is_developer_mode(&self, device: &str) -> Result<bool> {
+        self.with_device(device, |d, _| Ok(d.is_developer_mode))
+    }
+
+    async fn connect_bss(&self, device: &str, address: &str) -> Result<()> {
+        let (network, address) = self.with_station(device, |d, _| {
+            if !d.is_developer_mode {
+                return Err(DeveloperModeError.into());
+            }
+            d.networks
+                .iter()
+                .find_map(|net| {
+                    net.basic_service_sets()
+                        .into_iter()
+                        .find(|bss| bss.address.eq_ignore_ascii_case(address))
+                        .map(|bss| (net.name.clone(), bss.address))
+                })
+                .with_context(|| format!("Access point {} not found", address))
+        })?;
+
+        self.connect(device, &network).await?;
+        self.with_station(device, |d, _| {
+            d.connected_bss = Some(address);
+            Ok(())
+        })
+    }
+
+    async fn roam(&self, device: &str, address: &str) -> Result<()> {
+        self.with_station(device, |d, _| {
+            if !d.is_developer_mode {
+                return Err(DeveloperModeError.into());
+            }
+            let network = d
+                .networks
+                .iter()
+                .find(|net| d.connected_network.as_ref() == Some(&net.name))
+                .context("Not connected")?;
+            let bss = network
+                .basic_service_sets()
+                .into_iter()
+                .find(|bss| bss.address.eq_ignore_ascii_case(address))
+                .with_context(|| {
+                    format!("Access point {} not found in {}", address, network.name)
+                })?;
+            d.connected_bss = Some(bss.address);
+            Ok(())
+        })
+    }
+
+    async fn wps_push_button(&self, device: &str) -> Result<()> {
+        self.wps_connect(device).await
+    }
+
+    async fn wps_generate_pin(&self, device: &str) -> Result<String> {
+        // A valid PIN, its last digit is the checksum of the others
+        self.with_station(device, |_, _| Ok("12345670".to_string()))
+    }
+
+    async fn wps_start_pin(&self, device: &str, pin: &str) -> Result<()> {
+        if pin.len() != 8 || !pin.chars().all(|c| c.is_ascii_digit()) {
+            return Err(WpsError::InvalidPin.into());
+        }
+        self.wps_connect(device).await
+    }
+
+    async fn wps_cancel(&self, device: &str) -> Result<()> {
+        self.with_station(device, |d, _| {
+            d.is_wps_running = false;
+            Ok(())
+        })
+    }
+
+    async fn start_dpp(&self, device: &str, role: DppRole) -> Result<String> {
+        self.with_station(device, |d, _| {
+            if d.dpp.is_some() {
+                return Err(anyhow!("Operation already in progress"));
+            }
+            if role == DppRole::Configurator && d.connected_network.is_none() {
+                return Err(anyhow!("Not connected"));
+            }
+
+            // A made up public key, on channel 1 like iwd without a connection
+            let uri = format!(
+                "DPP:C:81/1;M:{};V:2;K:{};;",
+                d.info.address.replace(':', ""),
+                "MDkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDIgAC3ly6R0NjPBLAdXuJT1lQXmM0tVeaxL9hz7qqbX4O0sU="
+            );
+            d.dpp = Some(DppInfo {
+                role,
+                uri: uri.clone(),
+            });
+            Ok(uri)
+        })
+    }
+
+    async fn stop_dpp(&self, device: &str) -> Result<()> {
+        self.with_station(device, |d, _| {
+            d.dpp = None;
+            Ok(())
+        })
+    }
+
+    async fn dpp(&self, device: &str) -> Result<Option<DppInfo>> {
+        self.with_station(device, |d, _| Ok(d.dpp.clone()))
+    }
+
+    async fn known_networks(&self) -> Result<Vec<KnownNetworkInfo>> {
+        Ok(self.state.lock().unwrap().known_networks.clone())
+    }
+
+    async fn forget(&self, network: &str) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if !state.known_networks.iter().any(|net| net.name == network) {
+            return Err(anyhow!("Known network {} not found", network));
+        }
+        state.known_networks.retain(|net| net.name != network);
+
+        for device in &mut state.devices {
+            if device.connected_network.as_deref() == Some(network) {
+                device.state = State::Disconnected;
+                device.connected_network = None;
+            }
+        }
+        Ok(())
+    }
+
+    async fn set_autoconnect(&self, network: &str, is_autoconnect: bool) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let known_network = state
+            .known_networks
+            .iter_mut()
+            .find(|net| net.name == network)
+            .with_context(|| format!("Known network {} not found", network))?;
+        known_network.is_autoconnect = is_autoconnect;
+        Ok(())
+    }
+
+    async fn access_point(&self, device: &str) -> Result<AccessPointInfo> {
+        self.with_access_point(device, |d| Ok(d.access_point.clone()))
+    }
+
+    async fn access_point_clients(&self, device: &str) -> Result<Vec<String>> {
+        self.with_access_point(device, |d| {
+            if d.access_point.has_started {
+                Ok(d.access_point_clients.clone())
+            } else {
+                Ok(Vec::new())
+            }
+        })
+    }
+
+    async fn start_access_point(&self, device: &str, ssid: &str, psk: &str) -> Result<()> {
+        self.with_access_point(device, |d| {
+            if d.access_point.has_started {
+                return Err(IWDError::<AccessPointStartError>::OperationError(
+                    AccessPointStartError::AlreadyExists,
+                )
+                .into());
+            }
+            if ssid.is_empty() || psk.len() < 8 {
+                return Err(IWDError::<AccessPointStartError>::OperationError(
+                    AccessPointStartError::InvalidArguments,
+                )
+                .into());
+            }
+
+            d.access_point = AccessPointInfo {
+                has_started: true,
+                name: Some(ssid.to_string()),
+                frequency: Some(2412),
+                is_scanning: Some(false),
+                supported_ciphers: Some(vec!["CCMP".to_string()]),
+                used_cipher: Some("CCMP".to_string()),
+            };
+            Ok(())
+        })
+    }
+
+    async fn stop_access_point(&self, device: &str) -> Result<()> {
+        self.with_access_point(device, |d| {
+            if !d.access_point.has_started {
+                return Err(IWDError::<AccessPointStopError>::OperationError(
+                    AccessPointStopError::Failed,
+                )
+                .into());
+            }
+            d.access_point = AccessPointInfo::default();
+            d.access_point_clients.clear();
+            Ok(())
+        })
+    }
+
+    async fn scan_access_point(&self, device: &str) -> Result<()> {
+        self.with_access_point(device, |_| Ok(()))
+    }
+
+    async fn p2p_device(&self, device: &str) -> Result<Option<P2pDeviceInfo>> {
+        self.with_device(device, |d, _| {
+            Ok(d.p2p.as_ref().map(|p2p| p2p.info.clone()))
+        })
+    }
+
+    async fn set_p2p_enabled(&self, device: &str, is_enabled: bool) -> Result<()> {
+        self.with_p2p(device, |p2p| {
+            p2p.info.is_enabled = is_enabled;
+            if !is_enabled {
+                p2p.is_discovering = false;
+            }
+            Ok(())
+        })
+    }
+
+    async fn start_p2p_discovery(&self, device: &str) -> Result<()> {
+        self.with_p2p(device, |p2p| {
+            if !p2p.info.is_enabled {
+                return Err(anyhow!("Not available"));
+            }
+            p2p.is_discovering = true;
+            Ok(())
+        })
+    }
+
+    async fn stop_p2p_discovery(&self, device: &str) -> Result<()> {
+        self.with_p2p(device, |p2p| {
+            p2p.is_discovering = false;
+            Ok(())
+        })
+    }
+
+    async fn p2p_peers(&self, device: &str) -> Result<Vec<(P2pPeerInfo, i16)>> {
+        self.with_p2p(device, |p2p| {
+            // Without a discovery, only the connected peers are still known
+            let mut peers: Vec<(P2pPeerInfo, i16)> = p2p
+                .peers
+                .iter()
+                .filter(|p| p2p.is_discovering || p.info.is_connected)
+                .map(|p| (p.info.clone(), p.signal))
+                .collect();
+            peers.sort_by_key(|(_, signal)| -signal);
+            Ok(peers)
+        })
+    }
+
+    async fn p2p_push_button(&self, device: &str, peer: &str) -> Result<()> {
+        self.p2p_connect(device, peer).await
+    }
+
+    async fn p2p_generate_pin(&self, device: &str, _peer: &str) -> Result<String> {
+        self.with_p2p(device, |_| Ok("12345670".to_string()))
+    }
+
+    async fn p2p_start_pin(&self, device: &str, peer: &str, pin: &str) -> Result<()> {
+        if pin.len() != 8 || !pin.chars().all(|c| c.is_ascii_digit()) {
+            return Err(WpsError::InvalidPin.into());
+        }
+        self.p2p_connect(device, peer).await
+    }
+
+    async fn p2p_cancel(&self, device: &str, _peer: &str) -> Result<()> {
+        self.with_p2p(device, |p2p| {
+            p2p.connecting_peer = None;
+            Ok(())
+        })
+    }
+
+    async fn p2p_disconnect(&self, device: &str, peer: &str) -> Result<()> {
+        self.with_p2p(device, |p2p| {
+            let p = p2p
+                .peers
+                .iter_mut()
+                .find(|p| p.info.address == peer && p.info.is_connected)
+                .ok_or(anyhow!("Not connected"))?;
+            p.info.is_connected = false;
+            p.info.interface = None;
+            p.info.ip = None;
+            p2p.info.available_connections += 1;
+            Ok(())
+        })
+    }
+
+    async fn register_agent(&self, agent: Arc<dyn Agent>) -> Result<()> {
+        *self.agent.lock().unwrap() = Some(agent);
+        Ok(())
+    }
+
+    async fn register_signal_level_agent(
+        &self,
+        device: &str,
+        thresholds: &[i16],
+        agent: Arc<dyn SignalLevelAgent>,
+    ) -> Result<()> {
+        // The signals never change, so the agent is only called with the current level
+        let signal = self.with_station(device, |d, _| {
+            Ok(d.networks
+                .iter()
+                .find(|net| d.connected_network.as_ref() == Some(&net.name))
+                .map(|net| net.signal / 100))
+        })?;
+
+        if let Some(signal) = signal {
+            agent.changed(device, SignalLevel::new(signal, thresholds));
+        }
+        Ok(())
+    }
+}
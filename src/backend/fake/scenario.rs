@@ -0,0 +1,347 @@
+use anyhow::{Context, Result, anyhow};
+use std::{fs, path::Path, str::FromStr};
+
+use iwdrs::{hidden_network::HiddenNetwork, modes::Mode, network::NetworkType, station::State};
+use serde::Deserialize;
+
+use crate::backend::{
+    AccessPointInfo, AdapterInfo, BssInfo, DeviceInfo, InterfaceStatistics, KnownNetworkInfo,
+    P2pPeerInfo,
+    fake::{EapPrompt, FakeDevice, FakeNetwork, FakeP2p, FakePeer, FakeState},
+};
+
+/// Description of the fake devices and networks used by `impala --simulate`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Scenario {
+    #[serde(default)]
+    devices: Vec<ScenarioDevice>,
+    #[serde(default)]
+    known_networks: Vec<ScenarioKnownNetwork>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScenarioDevice {
+    name: String,
+    #[serde(default = "default_address")]
+    address: String,
+    #[serde(default = "default_mode")]
+    mode: String,
+    #[serde(default = "default_true")]
+    powered: bool,
+    #[serde(default)]
+    adapter: ScenarioAdapter,
+    connected_network: Option<String>,
+    #[serde(default)]
+    networks: Vec<ScenarioNetwork>,
+    #[serde(default)]
+    hidden_networks: Vec<ScenarioHiddenNetwork>,
+    access_point: Option<String>,
+    #[serde(default)]
+    access_point_clients: Vec<String>,
+    /// The adapter has a Wi-Fi Direct device
+    #[serde(default = "default_true")]
+    p2p: bool,
+    /// The devices found by the Wi-Fi Direct discovery
+    #[serde(default)]
+    p2p_peers: Vec<ScenarioPeer>,
+    /// iwd runs with `-E`, the access points of a network can be picked
+    #[serde(default)]
+    developer_mode: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScenarioAdapter {
+    #[serde(default = "default_adapter_name")]
+    name: String,
+    model: Option<String>,
+    vendor: Option<String>,
+    #[serde(default = "default_true")]
+    powered: bool,
+}
+
+impl Default for ScenarioAdapter {
+    fn default() -> Self {
+        Self {
+            name: default_adapter_name(),
+            model: None,
+            vendor: None,
+            powered: true,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScenarioNetwork {
+    name: String,
+    #[serde(default = "default_security")]
+    security: String,
+    /// Signal strength in dBm
+    #[serde(default = "default_signal")]
+    signal: i16,
+    #[serde(default = "default_frequency")]
+    frequency: u32,
+    /// The access points of the network, one with the signal and frequency above by default
+    #[serde(default)]
+    bss: Vec<ScenarioBss>,
+    passphrase: Option<String>,
+    eap_prompt: Option<EapPrompt>,
+    error: Option<String>,
+    /// The access point answers the WPS connections after a few seconds
+    #[serde(default)]
+    wps: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScenarioBss {
+    address: String,
+    #[serde(default = "default_signal")]
+    signal: i16,
+    #[serde(default = "default_frequency")]
+    frequency: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScenarioHiddenNetwork {
+    address: String,
+    #[serde(default = "default_security")]
+    security: String,
+    #[serde(default = "default_signal")]
+    signal: i16,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScenarioPeer {
+    name: String,
+    address: String,
+    #[serde(default = "default_category")]
+    category: String,
+    subcategory: Option<String>,
+    /// Signal strength in dBm
+    #[serde(default = "default_signal")]
+    signal: i16,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScenarioKnownNetwork {
+    name: String,
+    #[serde(default = "default_security")]
+    security: String,
+    #[serde(default = "default_true")]
+    autoconnect: bool,
+    #[serde(default)]
+    hidden: bool,
+}
+
+fn default_address() -> String {
+    "02:00:00:00:00:00".to_string()
+}
+
+fn default_mode() -> String {
+    "station".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_adapter_name() -> String {
+    "phy0".to_string()
+}
+
+fn default_security() -> String {
+    "psk".to_string()
+}
+
+fn default_signal() -> i16 {
+    -60
+}
+
+fn default_frequency() -> u32 {
+    2412
+}
+
+fn default_category() -> String {
+    "computer".to_string()
+}
+
+fn network_type(security: &str) -> Result<NetworkType> {
+    NetworkType::from_str(security).map_err(|_| {
+        anyhow!(
+            "Unknown security {}, expected one of open, wep, psk or 8021x",
+            security
+        )
+    })
+}
+
+/// Reads a scenario file and builds the state of the fake backend from it.
+pub fn load(path: impl AsRef<Path>) -> Result<FakeState> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Can not read the scenario {}", path.display()))?;
+    let scenario: Scenario =
+        toml::from_str(&content).with_context(|| format!("Invalid scenario {}", path.display()))?;
+
+    if scenario.devices.is_empty() {
+        return Err(anyhow!("The scenario {} has no device", path.display()));
+    }
+
+    let mut known_networks = scenario
+        .known_networks
+        .into_iter()
+        .map(|net| {
+            Ok(KnownNetworkInfo {
+                network_type: network_type(&net.security)?,
+                name: net.name,
+                is_autoconnect: net.autoconnect,
+                is_hidden: net.hidden,
+                last_connected: None,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let devices = scenario
+        .devices
+        .into_iter()
+        .map(|device| {
+            let mode = Mode::from_str(&device.mode)
+                .map_err(|_| anyhow!("Unknown mode {}, expected station or ap", device.mode))?;
+
+            let networks = device
+                .networks
+                .into_iter()
+                .map(|net| {
+                    Ok(FakeNetwork {
+                        network_type: network_type(&net.security)?,
+                        name: net.name,
+                        signal: net.signal.saturating_mul(100),
+                        frequency_mhz: net.frequency,
+                        basic_service_sets: net
+                            .bss
+                            .into_iter()
+                            .map(|bss| BssInfo {
+                                address: bss.address,
+                                signal: Some(bss.signal.saturating_mul(100)),
+                                frequency_mhz: Some(bss.frequency),
+                            })
+                            .collect(),
+                        passphrase: net.passphrase,
+                        eap_prompt: net.eap_prompt,
+                        error: net.error,
+                        wps: net.wps,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            if let Some(name) = &device.connected_network {
+                let Some(network) = networks.iter().find(|net| &net.name == name) else {
+                    return Err(anyhow!(
+                        "The connected network {} is not one of the networks of {}",
+                        name,
+                        device.name
+                    ));
+                };
+
+                // iwd only connects to the networks it knows
+                if !known_networks.iter().any(|net| &net.name == name) {
+                    known_networks.push(KnownNetworkInfo {
+                        name: name.clone(),
+                        network_type: network.network_type,
+                        is_autoconnect: true,
+                        is_hidden: false,
+                        last_connected: None,
+                    });
+                }
+            }
+
+            let hidden_networks = device
+                .hidden_networks
+                .into_iter()
+                .map(|net| {
+                    Ok(HiddenNetwork {
+                        network_type: network_type(&net.security)?,
+                        address: net.address,
+                        signal_strength: net.signal.saturating_mul(100),
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let access_point = match device.access_point {
+                Some(name) => AccessPointInfo {
+                    has_started: true,
+                    name: Some(name),
+                    frequency: Some(2412),
+                    is_scanning: Some(false),
+                    supported_ciphers: Some(vec!["CCMP".to_string()]),
+                    used_cipher: Some("CCMP".to_string()),
+                },
+                None => AccessPointInfo::default(),
+            };
+
+            let p2p = device.p2p.then(|| FakeP2p {
+                peers: device
+                    .p2p_peers
+                    .into_iter()
+                    .map(|peer| FakePeer {
+                        info: P2pPeerInfo {
+                            address: peer.address,
+                            name: peer.name,
+                            category: peer.category,
+                            subcategory: peer.subcategory,
+                            is_connected: false,
+                            interface: None,
+                            ip: None,
+                        },
+                        signal: peer.signal.saturating_mul(100),
+                    })
+                    .collect(),
+                ..FakeP2p::new("impala")
+            });
+
+            Ok(FakeDevice {
+                info: DeviceInfo {
+                    name: device.name,
+                    address: device.address,
+                    mode,
+                    is_powered: device.powered,
+                },
+                adapter: AdapterInfo {
+                    name: device.adapter.name,
+                    model: device.adapter.model,
+                    vendor: device.adapter.vendor,
+                    is_powered: device.adapter.powered,
+                    supported_modes: vec!["station".to_string(), "ap".to_string()],
+                },
+                state: if device.connected_network.is_some() {
+                    State::Connected
+                } else {
+                    State::Disconnected
+                },
+                is_scanning: false,
+                connected_network: device.connected_network,
+                connected_bss: None,
+                networks,
+                hidden_networks,
+                access_point,
+                access_point_clients: device.access_point_clients,
+                statistics: InterfaceStatistics::default(),
+                dpp: None,
+                is_wps_running: false,
+                p2p,
+                is_developer_mode: device.developer_mode,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(FakeState {
+        devices,
+        known_networks,
+    })
+}
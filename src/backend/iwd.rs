@@ -0,0 +1,1027 @@
+use anyhow::{Context, Result, anyhow};
+use std::{collections::HashMap, fs, path::Path, str::FromStr, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use chrono::DateTime;
+use futures::StreamExt;
+use iwdrs::{
+    agent::Agent as iwdAgent,
+    device::Device as iwdDevice,
+    error::{
+        IWDError,
+        access_point::{
+            AccessPointStartError, AccessPointStopError, ScanError as AccessPointScanError,
+        },
+        agent::Canceled,
+        network::ConnectError,
+        station::{DisconnectError, ScanError},
+    },
+    hidden_network::HiddenNetwork,
+    known_network::KnownNetwork as iwdKnownNetwork,
+    modes::Mode,
+    network::Network as iwdNetwork,
+    session::Session,
+    station::{
+        State,
+        diagnostics::{ActiveStationDiagnostics, StationSecurity},
+    },
+};
+use zbus::{
+    Connection, Proxy,
+    fdo::ObjectManagerProxy,
+    names::OwnedInterfaceName,
+    zvariant::{OwnedObjectPath, OwnedValue},
+};
+
+use crate::backend::{
+    AccessPointInfo, AdapterInfo, Agent, BssInfo, DeveloperModeError, DeviceInfo, DppInfo, DppRole,
+    InterfaceStatistics, KnownNetworkInfo, NetworkInfo, P2pDeviceInfo, P2pPeerInfo, SignalLevel,
+    SignalLevelAgent, StationInfo, WifiBackend, WpsError,
+};
+use crate::rfkill::{self, RfkillState};
+
+const IWD_SERVICE: &str = "net.connman.iwd";
+const DPP_INTERFACE: &str = "net.connman.iwd.DeviceProvisioning";
+const WPS_INTERFACE: &str = "net.connman.iwd.SimpleConfiguration";
+const P2P_DEVICE_INTERFACE: &str = "net.connman.iwd.p2p.Device";
+const P2P_PEER_INTERFACE: &str = "net.connman.iwd.p2p.Peer";
+const NETWORK_INTERFACE: &str = "net.connman.iwd.Network";
+const KNOWN_NETWORK_INTERFACE: &str = "net.connman.iwd.KnownNetwork";
+const STATION_INTERFACE: &str = "net.connman.iwd.Station";
+/// Only there while the station is connected
+const STATION_DIAGNOSTIC_INTERFACE: &str = "net.connman.iwd.StationDiagnostic";
+const ACCESS_POINT_INTERFACE: &str = "net.connman.iwd.AccessPoint";
+/// Only there while the access point is started
+const ACCESS_POINT_DIAGNOSTIC_INTERFACE: &str = "net.connman.iwd.AccessPointDiagnostic";
+/// Only there in developer mode
+const STATION_DEBUG_INTERFACE: &str = "net.connman.iwd.StationDebug";
+
+type Properties = HashMap<String, OwnedValue>;
+type Objects = HashMap<OwnedObjectPath, HashMap<OwnedInterfaceName, Properties>>;
+
+/// Talks to iwd over D-Bus.
+#[derive(Debug)]
+pub struct IwdBackend {
+    session: Session,
+    /// For what iwdrs does not expose, like the access points of a network
+    connection: Connection,
+}
+
+impl IwdBackend {
+    pub async fn new() -> Result<Self> {
+        let backend = async {
+            Ok::<_, zbus::Error>(Self {
+                session: Session::new().await?,
+                connection: Connection::system().await?,
+            })
+        };
+
+        match backend.await {
+            Ok(backend) => Ok(backend),
+            Err(e) => Err(anyhow!(
+                "Can not access the iwd service.
+Error: {}",
+                e
+            )),
+        }
+    }
+
+    /// An interface of a device that iwdrs does not expose.
+    async fn device_proxy(&self, device: &str, interface: &'static str) -> Result<Proxy<'static>> {
+        let objects = self.objects().await?;
+        let device_path = device_path(&objects, device)?;
+
+        Ok(Proxy::new(&self.connection, IWD_SERVICE, device_path, interface).await?)
+    }
+
+    /// Every object of iwd with the properties of its interfaces.
+    async fn objects(&self) -> Result<Objects> {
+        let object_manager = ObjectManagerProxy::builder(&self.connection)
+            .destination(IWD_SERVICE)?
+            .path("/")?
+            .build()
+            .await?;
+
+        Ok(object_manager.get_managed_objects().await?)
+    }
+
+    async fn station_debug_proxy(&self, device: &str) -> Result<Proxy<'static>> {
+        if !self.is_developer_mode(device).await? {
+            return Err(DeveloperModeError.into());
+        }
+        self.device_proxy(device, STATION_DEBUG_INTERFACE).await
+    }
+
+    async fn p2p_proxy(&self, device: &str) -> Result<Proxy<'static>> {
+        let objects = self.objects().await?;
+        let (path, _) = find_p2p_device(&objects, device)?
+            .with_context(|| format!("The adapter of {} does not support Wi-Fi Direct", device))?;
+
+        Ok(Proxy::new(
+            &self.connection,
+            IWD_SERVICE,
+            path.clone(),
+            P2P_DEVICE_INTERFACE,
+        )
+        .await?)
+    }
+
+    /// An interface of a discovered peer, the connections go through its WPS interface.
+    async fn peer_proxy(
+        &self,
+        device: &str,
+        peer: &str,
+        interface_name: &'static str,
+    ) -> Result<Proxy<'static>> {
+        let objects = self.objects().await?;
+        let (device_path, _) = find_p2p_device(&objects, device)?
+            .with_context(|| format!("The adapter of {} does not support Wi-Fi Direct", device))?;
+
+        let peer_path = interface(&objects, P2P_PEER_INTERFACE)
+            .find(|(path, properties)| {
+                property::<OwnedObjectPath>(properties, "Device").as_ref() == Some(device_path)
+                    && peer_address(path) == peer
+            })
+            .map(|(path, _)| path.clone())
+            .with_context(|| format!("Peer {} not found", peer))?;
+
+        Ok(Proxy::new(&self.connection, IWD_SERVICE, peer_path, interface_name).await?)
+    }
+
+    async fn device(&self, device_name: &str) -> Result<iwdDevice> {
+        for device in self.session.devices().await? {
+            if device.name().await? == device_name {
+                return Ok(device);
+            }
+        }
+        Err(anyhow!("No device named {} found", device_name))
+    }
+
+    /// An interface iwd puts on the object of a device in some modes, e.g. its station, or
+    /// `None` when the device does not have it at the moment.
+    async fn device_interface(
+        &self,
+        objects: &Objects,
+        device: &str,
+        interface_name: &'static str,
+    ) -> Result<Option<Proxy<'static>>> {
+        if device_properties(objects, device, interface_name)?.is_none() {
+            return Ok(None);
+        }
+        let device_path = device_path(objects, device)?;
+
+        Ok(Some(
+            Proxy::new(&self.connection, IWD_SERVICE, device_path, interface_name).await?,
+        ))
+    }
+
+    async fn station_proxy(&self, device: &str) -> Result<Proxy<'static>> {
+        let objects = self.objects().await?;
+        self.device_interface(&objects, device, STATION_INTERFACE)
+            .await?
+            .with_context(|| format!("No station found for {}", device))
+    }
+
+    async fn access_point_proxy(&self, device: &str) -> Result<Proxy<'static>> {
+        let objects = self.objects().await?;
+        self.device_interface(&objects, device, ACCESS_POINT_INTERFACE)
+            .await?
+            .with_context(|| format!("No AP found for {}", device))
+    }
+
+    async fn iwd_known_network(&self, network_name: &str) -> Result<iwdKnownNetwork> {
+        for network in self.session.known_networks().await? {
+            if network.name().await? == network_name {
+                return Ok(network);
+            }
+        }
+        Err(anyhow!("Known network {} not found", network_name))
+    }
+}
+
+/// The properties of an interface of the objects, by object path.
+fn interface<'a>(
+    objects: &'a Objects,
+    interface: &'a str,
+) -> impl Iterator<Item = (&'a OwnedObjectPath, &'a Properties)> {
+    objects.iter().filter_map(move |(path, interfaces)| {
+        interfaces
+            .iter()
+            .find(|(name, _)| name.as_str() == interface)
+            .map(|(_, properties)| (path, properties))
+    })
+}
+
+fn device_path(objects: &Objects, device: &str) -> Result<OwnedObjectPath> {
+    interface(objects, "net.connman.iwd.Device")
+        .find(|(_, properties)| property::<String>(properties, "Name").as_deref() == Some(device))
+        .map(|(path, _)| path.clone())
+        .with_context(|| format!("No device named {} found", device))
+}
+
+/// The properties of an interface of an object.
+fn object_interface<'a>(
+    objects: &'a Objects,
+    path: &OwnedObjectPath,
+    interface_name: &str,
+) -> Option<&'a Properties> {
+    objects
+        .get(path)?
+        .iter()
+        .find(|(name, _)| name.as_str() == interface_name)
+        .map(|(_, properties)| properties)
+}
+
+/// The properties of an interface iwd puts on the object of a device in some modes, `None`
+/// when the device does not have it at the moment.
+fn device_properties<'a>(
+    objects: &'a Objects,
+    device: &str,
+    interface_name: &str,
+) -> Result<Option<&'a Properties>> {
+    let device_path = device_path(objects, device)?;
+    Ok(object_interface(objects, &device_path, interface_name))
+}
+
+/// A network discovered by the station of a device.
+fn find_network<'a>(
+    objects: &'a Objects,
+    device: &str,
+    network: &str,
+) -> Result<(&'a OwnedObjectPath, &'a Properties)> {
+    let device_path = device_path(objects, device)?;
+    interface(objects, NETWORK_INTERFACE)
+        .find(|(_, properties)| {
+            property::<String>(properties, "Name").as_deref() == Some(network)
+                && property::<OwnedObjectPath>(properties, "Device").as_ref() == Some(&device_path)
+        })
+        .with_context(|| format!("Network {} not found", network))
+}
+
+/// The P2P device of the adapter of a device, iwd puts it under the object of the adapter like
+/// the devices.
+fn find_p2p_device<'a>(
+    objects: &'a Objects,
+    device: &str,
+) -> Result<Option<(&'a OwnedObjectPath, &'a Properties)>> {
+    let device_path = device_path(objects, device)?;
+    let adapter_path = interface(objects, "net.connman.iwd.Device")
+        .find(|(path, _)| **path == device_path)
+        .and_then(|(_, properties)| property::<OwnedObjectPath>(properties, "Adapter"))
+        .with_context(|| format!("No adapter found for {}", device))?;
+
+    let prefix = format!("{}/", adapter_path.as_str());
+    Ok(interface(objects, P2P_DEVICE_INTERFACE)
+        .find(|(path, _)| path.as_str().starts_with(&prefix)))
+}
+
+/// iwd names the peer objects after their address, e.g. `.../p2p/0a1b2c3d4e5f`.
+fn peer_address(path: &OwnedObjectPath) -> String {
+    let name = path.as_str().rsplit('/').next().unwrap_or_default();
+    if name.len() == 12 && name.chars().all(|c| c.is_ascii_hexdigit()) {
+        name.as_bytes()
+            .chunks(2)
+            .map(|byte| std::str::from_utf8(byte).unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join(":")
+    } else {
+        name.to_string()
+    }
+}
+
+/// The debug methods take the address of an access point as its six bytes.
+fn address_bytes(address: &str) -> Result<Vec<u8>> {
+    let bytes = address
+        .split(':')
+        .map(|byte| u8::from_str_radix(byte, 16))
+        .collect::<Result<Vec<u8>, _>>()
+        .ok()
+        .filter(|bytes| bytes.len() == 6);
+    bytes.with_context(|| format!("Invalid access point address {}", address))
+}
+
+fn property<T: TryFrom<OwnedValue>>(properties: &Properties, name: &str) -> Option<T> {
+    let value = properties.get(name)?.try_clone().ok()?;
+    T::try_from(value).ok()
+}
+
+/// The message of the iwd errors, e.g. `Operation not supported` rather than the whole D-Bus
+/// error, for the calls iwdrs does not make.
+fn method_error(e: zbus::Error) -> anyhow::Error {
+    match e {
+        zbus::Error::MethodError(name, message, _) => {
+            anyhow!(message.unwrap_or(name.trim_start_matches("net.connman.iwd.").to_string()))
+        }
+        e => e.into(),
+    }
+}
+
+fn wps_error(e: zbus::Error) -> anyhow::Error {
+    let error = match &e {
+        zbus::Error::MethodError(name, _, _) => match name.as_str() {
+            "net.connman.iwd.Aborted" => Some(WpsError::Canceled),
+            "net.connman.iwd.Timeout" | "net.connman.iwd.NotReachable" => Some(WpsError::Timeout),
+            "net.connman.iwd.SessionOverlap" => Some(WpsError::SessionOverlap),
+            "net.connman.iwd.InvalidFormat" => Some(WpsError::InvalidPin),
+            "net.connman.iwd.Busy" => Some(WpsError::Busy),
+            "net.connman.iwd.Failed" => Some(WpsError::Failed),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    match error {
+        Some(error) => error.into(),
+        None => method_error(e),
+    }
+}
+
+/// A discovered network, along with its known network.
+fn network_info(objects: &Objects, path: &OwnedObjectPath) -> Result<NetworkInfo> {
+    let properties = object_interface(objects, path, NETWORK_INTERFACE)
+        .with_context(|| format!("Network {} not found", path.as_str()))?;
+
+    let known_network = property::<OwnedObjectPath>(properties, "KnownNetwork")
+        .and_then(|path| object_interface(objects, &path, KNOWN_NETWORK_INTERFACE))
+        .and_then(known_network_info);
+
+    Ok(NetworkInfo {
+        name: property(properties, "Name").context("The network has no name")?,
+        network_type: property(properties, "Type").context("The network has no type")?,
+        is_connected: property(properties, "Connected").unwrap_or(false),
+        known_network,
+    })
+}
+
+fn known_network_info(properties: &Properties) -> Option<KnownNetworkInfo> {
+    Some(KnownNetworkInfo {
+        name: property(properties, "Name")?,
+        network_type: property(properties, "Type")?,
+        is_autoconnect: property(properties, "AutoConnect")?,
+        is_hidden: property(properties, "Hidden").unwrap_or(false),
+        last_connected: property::<String>(properties, "LastConnectedTime")
+            .and_then(|time| DateTime::parse_from_rfc3339(&time).ok()),
+    })
+}
+
+/// The diagnostics of the connection of a station. iwdrs is not used as it reads the transmit
+/// MCS from the receive one.
+fn station_diagnostics(properties: &Properties) -> Option<ActiveStationDiagnostics> {
+    fn parse<T: FromStr>(properties: &Properties, name: &str) -> Option<T> {
+        property::<String>(properties, name)?.parse().ok()
+    }
+    let rate_kbps =
+        |name: &str| property::<u32>(properties, name).map(|rate| 100 * u64::from(rate));
+
+    Some(ActiveStationDiagnostics {
+        connected_bss: property(properties, "ConnectedBss")?,
+        frequency_mhz: property(properties, "Frequency")?,
+        channel: property(properties, "Channel"),
+        security: parse(properties, "Security").unwrap_or(StationSecurity::Unknown),
+        rssi: property(properties, "RSSI"),
+        average_rssi: property(properties, "AverageRSSI"),
+        rx_mode: parse(properties, "RxMode"),
+        rx_rate_kbps: rate_kbps("RxBitrate"),
+        rx_mcs: property(properties, "RxMCS"),
+        tx_mode: parse(properties, "TxMode"),
+        tx_rate_kbps: rate_kbps("TxBitrate"),
+        tx_mcs: property(properties, "TxMCS"),
+        pairwise_cipher: parse(properties, "PairwiseCipher"),
+        inactive_time: property::<u32>(properties, "InactiveTime")
+            .map(|time_ms| Duration::from_millis(u64::from(time_ms))),
+        connected_time: property::<u32>(properties, "ConnectedTime")
+            .map(|time_s| Duration::from_secs(u64::from(time_s))),
+    })
+}
+
+#[async_trait]
+impl WifiBackend for IwdBackend {
+    async fn devices(&self) -> Result<Vec<DeviceInfo>> {
+        let mut devices = Vec::new();
+        for device in self.session.devices().await? {
+            devices.push(DeviceInfo {
+                name: device.name().await?,
+                address: device.address().await?,
+                mode: device.get_mode().await?,
+                is_powered: device.is_powered().await?,
+            });
+        }
+        Ok(devices)
+    }
+
+    async fn set_mode(&self, device: &str, mode: Mode) -> Result<()> {
+        self.device(device).await?.set_mode(mode).await?;
+        Ok(())
+    }
+
+    async fn set_power(&self, device: &str, is_powered: bool) -> Result<()> {
+        self.device(device).await?.set_power(is_powered).await?;
+        Ok(())
+    }
+
+    async fn adapter(&self, device: &str) -> Result<AdapterInfo> {
+        let adapter = self.device(device).await?.adapter().await?;
+
+        Ok(AdapterInfo {
+            name: adapter.name().await?,
+            model: adapter.model().await.ok(),
+            vendor: adapter.vendor().await.ok(),
+            is_powered: adapter.is_powered().await?,
+            supported_modes: adapter.supported_modes().await?,
+        })
+    }
+
+    async fn statistics(&self, device: &str) -> Result<InterfaceStatistics> {
+        let directory = Path::new("/sys/class/net").join(device).join("statistics");
+        let read = |name: &str| -> Result<u64> {
+            let path = directory.join(name);
+            let value = fs::read_to_string(&path)
+                .with_context(|| format!("Can not read {}", path.display()))?;
+            Ok(value.trim().parse()?)
+        };
+
+        Ok(InterfaceStatistics {
+            rx_bytes: read("rx_bytes")?,
+            tx_bytes: read("tx_bytes")?,
+            rx_packets: read("rx_packets")?,
+            tx_packets: read("tx_packets")?,
+            rx_errors: read("rx_errors")?,
+            tx_errors: read("tx_errors")?,
+            rx_dropped: read("rx_dropped")?,
+            tx_dropped: read("tx_dropped")?,
+        })
+    }
+
+    async fn rfkill(&self) -> Result<RfkillState> {
+        rfkill::state()
+    }
+
+    async fn station(&self, device: &str) -> Result<StationInfo> {
+        let objects = self.objects().await?;
+        let station = device_properties(&objects, device, STATION_INTERFACE)?
+            .with_context(|| format!("No station found for {}", device))?;
+
+        let state: State = property(station, "State").context("The station has no state")?;
+        let connected_network = match property::<OwnedObjectPath>(station, "ConnectedNetwork") {
+            Some(path) if state == State::Connected => Some(network_info(&objects, &path)?),
+            _ => None,
+        };
+
+        Ok(StationInfo {
+            state,
+            is_scanning: property(station, "Scanning").unwrap_or(false),
+            connected_network,
+        })
+    }
+
+    async fn networks(&self, device: &str) -> Result<Vec<(NetworkInfo, i16)>> {
+        let networks: Vec<(OwnedObjectPath, i16)> = self
+            .station_proxy(device)
+            .await?
+            .call("GetOrderedNetworks", &())
+            .await?;
+
+        let objects = self.objects().await?;
+        Ok(networks
+            .iter()
+            .filter_map(|(path, signal)| Some((network_info(&objects, path).ok()?, *signal)))
+            .collect())
+    }
+
+    async fn hidden_networks(&self, device: &str) -> Result<Vec<HiddenNetwork>> {
+        let hidden_networks: Vec<(String, i16, String)> = self
+            .station_proxy(device)
+            .await?
+            .call("GetHiddenAccessPoints", &())
+            .await?;
+
+        Ok(hidden_networks
+            .into_iter()
+            .filter_map(|(address, signal_strength, network_type)| {
+                Some(HiddenNetwork {
+                    address,
+                    signal_strength,
+                    network_type: network_type.parse().ok()?,
+                })
+            })
+            .collect())
+    }
+
+    async fn basic_service_sets(&self, device: &str, network: &str) -> Result<Vec<BssInfo>> {
+        let objects = self.objects().await?;
+
+        let device_path = device_path(&objects, device)?;
+        let (network_path, network_properties) = find_network(&objects, device, network)?;
+
+        let bss_paths: Vec<OwnedObjectPath> =
+            property(network_properties, "ExtendedServiceSet").unwrap_or_default();
+        let mut basic_service_sets: Vec<BssInfo> = bss_paths
+            .iter()
+            .filter_map(|path| {
+                let properties = objects
+                    .get(path)?
+                    .iter()
+                    .find(|(name, _)| name.as_str() == "net.connman.iwd.BasicServiceSet")?;
+                Some(BssInfo {
+                    address: property(properties.1, "Address")?,
+                    signal: None,
+                    frequency_mhz: None,
+                })
+            })
+            .collect();
+
+        // The debug interface, only there in developer mode, has the signal of every BSS
+        let debug = Proxy::new(
+            &self.connection,
+            IWD_SERVICE,
+            device_path.as_str(),
+            STATION_DEBUG_INTERFACE,
+        )
+        .await?;
+        if let Ok(networks) = debug
+            .call::<_, _, HashMap<OwnedObjectPath, Vec<Properties>>>("GetNetworks", &())
+            .await
+            && let Some(bss_list) = networks.get(network_path)
+        {
+            for properties in bss_list {
+                let Some(address) = property::<String>(properties, "Address") else {
+                    continue;
+                };
+                if let Some(bss) = basic_service_sets
+                    .iter_mut()
+                    .find(|bss| bss.address.eq_ignore_ascii_case(&address))
+                {
+                    bss.signal = property(properties, "RSSI");
+                    bss.frequency_mhz = property(properties, "Frequency");
+                }
+            }
+            basic_service_sets.sort_by_key(|bss| std::cmp::Reverse(bss.signal));
+        }
+
+        Ok(basic_service_sets)
+    }
+
+    async fn diagnostic(&self, device: &str) -> Result<Option<ActiveStationDiagnostics>> {
+        let objects = self.objects().await?;
+        // Only there while the station is connected
+        let Some(diagnostics) = self
+            .device_interface(&objects, device, STATION_DIAGNOSTIC_INTERFACE)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        Ok(diagnostics
+            .call::<_, _, Properties>("GetDiagnostics", &())
+            .await
+            .ok()
+            .and_then(|properties| station_diagnostics(&properties)))
+    }
+
+    async fn scan(&self, device: &str) -> Result<()> {
+        self.station_proxy(device)
+            .await?
+            .call::<_, _, ()>("Scan", &())
+            .await
+            .map_err(IWDError::<ScanError>::from)?;
+        Ok(())
+    }
+
+    async fn wait_for_scan_complete(&self, device: &str) -> Result<()> {
+        let station = self.station_proxy(device).await?;
+        // Listening before reading, so that the end of the scan can not be missed
+        let mut changes = station.receive_property_changed::<bool>("Scanning").await;
+        if !station.get_property::<bool>("Scanning").await? {
+            return Ok(());
+        }
+        while let Some(change) = changes.next().await {
+            if !change.get().await? {
+                return Ok(());
+            }
+        }
+        Err(anyhow!("The station of {} is gone", device))
+    }
+
+    async fn connect(&self, device: &str, network: &str) -> Result<()> {
+        let objects = self.objects().await?;
+        let (network_path, _) = find_network(&objects, device, network)?;
+
+        Proxy::new(
+            &self.connection,
+            IWD_SERVICE,
+            network_path.clone(),
+            NETWORK_INTERFACE,
+        )
+        .await?
+        .call::<_, _, ()>("Connect", &())
+        .await
+        .map_err(IWDError::<ConnectError>::from)?;
+        Ok(())
+    }
+
+    async fn connect_hidden_network(&self, device: &str, ssid: &str) -> Result<()> {
+        self.station_proxy(device)
+            .await?
+            .call::<_, _, ()>("ConnectHiddenNetwork", &ssid)
+            .await
+            .map_err(IWDError::<ConnectError>::from)?;
+        Ok(())
+    }
+
+    async fn disconnect(&self, device: &str) -> Result<()> {
+        self.station_proxy(device)
+            .await?
+            .call::<_, _, ()>("Disconnect", &())
+            .await
+            .map_err(IWDError::<DisconnectError>::from)?;
+        Ok(())
+    }
+
+    async fn is_developer_mode(&self, device: &str) -> Result<bool> {
+        let objects = self.objects().await?;
+        Ok(device_properties(&objects, device, STATION_DEBUG_INTERFACE)?.is_some())
+    }
+
+    async fn connect_bss(&self, device: &str, address: &str) -> Result<()> {
+        self.station_debug_proxy(device)
+            .await?
+            .call::<_, _, ()>("ConnectBssid", &address_bytes(address)?)
+            .await
+            .map_err(method_error)
+    }
+
+    async fn roam(&self, device: &str, address: &str) -> Result<()> {
+        self.station_debug_proxy(device)
+            .await?
+            .call::<_, _, ()>("Roam", &address_bytes(address)?)
+            .await
+            .map_err(method_error)
+    }
+
+    async fn wps_push_button(&self, device: &str) -> Result<()> {
+        self.device_proxy(device, WPS_INTERFACE)
+            .await?
+            .call::<_, _, ()>("PushButton", &())
+            .await
+            .map_err(wps_error)
+    }
+
+    async fn wps_generate_pin(&self, device: &str) -> Result<String> {
+        self.device_proxy(device, WPS_INTERFACE)
+            .await?
+            .call::<_, _, String>("GeneratePin", &())
+            .await
+            .map_err(wps_error)
+    }
+
+    async fn wps_start_pin(&self, device: &str, pin: &str) -> Result<()> {
+        self.device_proxy(device, WPS_INTERFACE)
+            .await?
+            .call::<_, _, ()>("StartPin", &pin)
+            .await
+            .map_err(wps_error)
+    }
+
+    async fn wps_cancel(&self, device: &str) -> Result<()> {
+        self.device_proxy(device, WPS_INTERFACE)
+            .await?
+            .call::<_, _, ()>("Cancel", &())
+            .await
+            .map_err(method_error)
+    }
+
+    async fn start_dpp(&self, device: &str, role: DppRole) -> Result<String> {
+        let provisioning = self.device_proxy(device, DPP_INTERFACE).await?;
+        let method = match role {
+            DppRole::Enrollee => "StartEnrollee",
+            DppRole::Configurator => "StartConfigurator",
+        };
+
+        provisioning
+            .call::<_, _, String>(method, &())
+            .await
+            .map_err(method_error)
+    }
+
+    async fn stop_dpp(&self, device: &str) -> Result<()> {
+        self.device_proxy(device, DPP_INTERFACE)
+            .await?
+            .call::<_, _, ()>("Stop", &())
+            .await
+            .map_err(method_error)
+    }
+
+    async fn dpp(&self, device: &str) -> Result<Option<DppInfo>> {
+        let objects = self.objects().await?;
+        let device_path = device_path(&objects, device)?;
+
+        let Some((_, properties)) =
+            interface(&objects, DPP_INTERFACE).find(|(path, _)| **path == device_path)
+        else {
+            return Ok(None);
+        };
+
+        if property::<bool>(properties, "Started") != Some(true) {
+            return Ok(None);
+        }
+        let role = match property::<String>(properties, "Role").as_deref() {
+            Some("configurator") => DppRole::Configurator,
+            _ => DppRole::Enrollee,
+        };
+
+        Ok(property::<String>(properties, "URI").map(|uri| DppInfo { role, uri }))
+    }
+
+    async fn known_networks(&self) -> Result<Vec<KnownNetworkInfo>> {
+        let objects = self.objects().await?;
+        Ok(interface(&objects, KNOWN_NETWORK_INTERFACE)
+            .filter_map(|(_, properties)| known_network_info(properties))
+            .collect())
+    }
+
+    async fn forget(&self, network: &str) -> Result<()> {
+        self.iwd_known_network(network).await?.forget().await?;
+        Ok(())
+    }
+
+    async fn set_autoconnect(&self, network: &str, is_autoconnect: bool) -> Result<()> {
+        self.iwd_known_network(network)
+            .await?
+            .set_autoconnect(is_autoconnect)
+            .await?;
+        Ok(())
+    }
+
+    async fn access_point(&self, device: &str) -> Result<AccessPointInfo> {
+        let objects = self.objects().await?;
+        let access_point = device_properties(&objects, device, ACCESS_POINT_INTERFACE)?
+            .with_context(|| format!("No AP found for {}", device))?;
+
+        Ok(AccessPointInfo {
+            has_started: property(access_point, "Started").unwrap_or(false),
+            name: property(access_point, "Name"),
+            frequency: property(access_point, "Frequency"),
+            is_scanning: property(access_point, "Scanning"),
+            supported_ciphers: property(access_point, "PairwiseCiphers"),
+            used_cipher: property(access_point, "GroupCipher"),
+        })
+    }
+
+    async fn access_point_clients(&self, device: &str) -> Result<Vec<String>> {
+        let objects = self.objects().await?;
+        // Only there while the access point is started
+        let Some(diagnostics) = self
+            .device_interface(&objects, device, ACCESS_POINT_DIAGNOSTIC_INTERFACE)
+            .await?
+        else {
+            return Ok(Vec::new());
+        };
+
+        let clients: Vec<Properties> = diagnostics.call("GetDiagnostics", &()).await?;
+        Ok(clients
+            .iter()
+            .filter_map(|client| property(client, "Address"))
+            .collect())
+    }
+
+    async fn start_access_point(&self, device: &str, ssid: &str, psk: &str) -> Result<()> {
+        self.access_point_proxy(device)
+            .await?
+            .call::<_, _, ()>("Start", &(ssid, psk))
+            .await
+            .map_err(IWDError::<AccessPointStartError>::from)?;
+        Ok(())
+    }
+
+    async fn stop_access_point(&self, device: &str) -> Result<()> {
+        self.access_point_proxy(device)
+            .await?
+            .call::<_, _, ()>("Stop", &())
+            .await
+            .map_err(IWDError::<AccessPointStopError>::from)?;
+        Ok(())
+    }
+
+    async fn scan_access_point(&self, device: &str) -> Result<()> {
+        self.access_point_proxy(device)
+            .await?
+            .call::<_, _, ()>("Scan", &())
+            .await
+            .map_err(IWDError::<AccessPointScanError>::from)?;
+        Ok(())
+    }
+
+    async fn p2p_device(&self, device: &str) -> Result<Option<P2pDeviceInfo>> {
+        let objects = self.objects().await?;
+
+        Ok(
+            find_p2p_device(&objects, device)?.map(|(_, properties)| P2pDeviceInfo {
+                name: property(properties, "Name").unwrap_or_default(),
+                is_enabled: property(properties, "Enabled").unwrap_or(false),
+                available_connections: property(properties, "AvailableConnections").unwrap_or(0),
+            }),
+        )
+    }
+
+    async fn set_p2p_enabled(&self, device: &str, is_enabled: bool) -> Result<()> {
+        self.p2p_proxy(device)
+            .await?
+            .set_property("Enabled", is_enabled)
+            .await?;
+        Ok(())
+    }
+
+    async fn start_p2p_discovery(&self, device: &str) -> Result<()> {
+        // iwd keeps looking for peers as long as a client asked for it, and forgets the
+        // request of a client that leaves the bus
+        self.p2p_proxy(device)
+            .await?
+            .call::<_, _, ()>("RequestDiscovery", &())
+            .await
+            .map_err(method_error)
+    }
+
+    async fn stop_p2p_discovery(&self, device: &str) -> Result<()> {
+        self.p2p_proxy(device)
+            .await?
+            .call::<_, _, ()>("ReleaseDiscovery", &())
+            .await
+            .map_err(method_error)
+    }
+
+    async fn p2p_peers(&self, device: &str) -> Result<Vec<(P2pPeerInfo, i16)>> {
+        let peers: Vec<(OwnedObjectPath, i16)> = self
+            .p2p_proxy(device)
+            .await?
+            .call("GetPeers", &())
+            .await
+            .map_err(method_error)?;
+        let objects = self.objects().await?;
+
+        let mut peers: Vec<(P2pPeerInfo, i16)> = peers
+            .iter()
+            .filter_map(|(path, signal)| {
+                let (_, properties) =
+                    interface(&objects, P2P_PEER_INTERFACE).find(|(p, _)| *p == path)?;
+                let peer = P2pPeerInfo {
+                    address: peer_address(path),
+                    name: property(properties, "Name")?,
+                    category: property(properties, "DeviceCategory").unwrap_or_default(),
+                    subcategory: property(properties, "DeviceSubcategory"),
+                    is_connected: property(properties, "Connected").unwrap_or(false),
+                    interface: property(properties, "ConnectedInterface"),
+                    ip: property(properties, "ConnectedIP"),
+                };
+                Some((peer, *signal))
+            })
+            .collect();
+        peers.sort_by_key(|(_, signal)| -signal);
+
+        Ok(peers)
+    }
+
+    async fn p2p_push_button(&self, device: &str, peer: &str) -> Result<()> {
+        self.peer_proxy(device, peer, WPS_INTERFACE)
+            .await?
+            .call::<_, _, ()>("PushButton", &())
+            .await
+            .map_err(wps_error)
+    }
+
+    async fn p2p_generate_pin(&self, device: &str, peer: &str) -> Result<String> {
+        self.peer_proxy(device, peer, WPS_INTERFACE)
+            .await?
+            .call::<_, _, String>("GeneratePin", &())
+            .await
+            .map_err(wps_error)
+    }
+
+    async fn p2p_start_pin(&self, device: &str, peer: &str, pin: &str) -> Result<()> {
+        self.peer_proxy(device, peer, WPS_INTERFACE)
+            .await?
+            .call::<_, _, ()>("StartPin", &pin)
+            .await
+            .map_err(wps_error)
+    }
+
+    async fn p2p_cancel(&self, device: &str, peer: &str) -> Result<()> {
+        self.peer_proxy(device, peer, WPS_INTERFACE)
+            .await?
+            .call::<_, _, ()>("Cancel", &())
+            .await
+            .map_err(method_error)
+    }
+
+    async fn p2p_disconnect(&self, device: &str, peer: &str) -> Result<()> {
+        self.peer_proxy(device, peer, P2P_PEER_INTERFACE)
+            .await?
+            .call::<_, _, ()>("Disconnect", &())
+            .await
+            .map_err(method_error)
+    }
+
+    async fn register_signal_level_agent(
+        &self,
+        device: &str,
+        thresholds: &[i16],
+        agent: Arc<dyn SignalLevelAgent>,
+    ) -> Result<()> {
+        // iwd numbers the levels from the strongest threshold
+        let mut levels = thresholds.to_vec();
+        levels.sort_by_key(|level| std::cmp::Reverse(*level));
+
+        let path = OwnedObjectPath::try_from(format!(
+            "/impala/signal_level_agent/{}",
+            device.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+        ))?;
+        // iwd releases the agent along with the station, nothing is left to unregister
+        self.connection
+            .object_server()
+            .at(
+                &path,
+                IwdSignalLevelAgent {
+                    device: device.to_string(),
+                    levels: levels.clone(),
+                    agent,
+                },
+            )
+            .await?;
+
+        self.station_proxy(device)
+            .await?
+            .call::<_, _, ()>("RegisterSignalLevelAgent", &(&path, levels))
+            .await
+            .map_err(method_error)
+    }
+
+    async fn register_agent(&self, agent: Arc<dyn Agent>) -> Result<()> {
+        self.session.register_agent(IwdAgent(agent)).await?;
+        Ok(())
+    }
+}
+
+/// Forwards the requests of iwd to an `Agent`.
+struct IwdAgent(Arc<dyn Agent>);
+
+impl iwdAgent for IwdAgent {
+    async fn request_passphrase(&self, network: &iwdNetwork) -> Result<String, Canceled> {
+        let network_name = network.name().await.map_err(|_| Canceled())?;
+        self.0.request_passphrase(network_name).await
+    }
+
+    async fn request_private_key_passphrase(
+        &self,
+        network: &iwdNetwork,
+    ) -> Result<String, Canceled> {
+        let network_name = network.name().await.map_err(|_| Canceled())?;
+        self.0.request_private_key_passphrase(network_name).await
+    }
+
+    async fn request_user_name_and_passphrase(
+        &self,
+        network: &iwdNetwork,
+    ) -> Result<(String, String), Canceled> {
+        let network_name = network.name().await.map_err(|_| Canceled())?;
+        self.0.request_user_name_and_passphrase(network_name).await
+    }
+
+    async fn request_user_password(
+        &self,
+        network: &iwdNetwork,
+        user_name: Option<&String>,
+    ) -> Result<String, Canceled> {
+        let network_name = network.name().await.map_err(|_| Canceled())?;
+        self.0
+            .request_user_password(network_name, user_name.cloned())
+            .await
+    }
+}
+
+/// Forwards the signal level changes of a station to a `SignalLevelAgent`.
+struct IwdSignalLevelAgent {
+    device: String,
+    /// The thresholds, strongest first
+    levels: Vec<i16>,
+    agent: Arc<dyn SignalLevelAgent>,
+}
+
+#[zbus::interface(name = "net.connman.iwd.SignalLevelAgent")]
+impl IwdSignalLevelAgent {
+    fn release(&self) {}
+
+    /// The level is the number of thresholds the signal is under, 0 being above the strongest
+    fn changed(&self, _station: OwnedObjectPath, level: u8) {
+        let level = usize::from(level);
+
+        self.agent.changed(
+            &self.device,
+            SignalLevel {
+                min: self.levels.get(level).copied(),
+                max: level
+                    .checked_sub(1)
+                    .and_then(|level| self.levels.get(level))
+                    .copied(),
+            },
+        );
+    }
+}
@@ -9,6 +9,86 @@ pub fn cli() -> Command {
                 .short('m')
                 .required(false)
                 .help("Device mode")
-                .value_parser(["station", "ap"]),
+                .value_parser(["station", "ap", "p2p"]),
+        )
+        .arg(
+            arg!(--device <ifname>)
+                .short('d')
+                .required(false)
+                .global(true)
+                .help("Interface name of the device to manage, e.g. wlan0"),
+        )
+        .arg(
+            arg!(--simulate <scenario>)
+                .required(false)
+                .global(true)
+                .help("Run against the fake devices and networks described in a scenario file, without iwd"),
+        )
+        .subcommand(
+            Command::new("station")
+                .about("Manage the station without starting the TUI")
+                .subcommand_required(true)
+                .subcommand(Command::new("list").about("List the available networks"))
+                .subcommand(
+                    Command::new("connect")
+                        .about("Connect to a network")
+                        .arg(arg!(<ssid> "Name of the network"))
+                        .arg(
+                            arg!(--"passphrase-stdin")
+                                .help("Read the passphrase from the standard input"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("disconnect").about("Disconnect from the connected network"),
+                ),
+        )
+        .subcommand(
+            Command::new("known")
+                .about("Manage the known networks without starting the TUI")
+                .subcommand_required(true)
+                .subcommand(Command::new("list").about("List the known networks"))
+                .subcommand(
+                    Command::new("forget")
+                        .about("Forget a known network")
+                        .arg(arg!(<ssid> "Name of the network")),
+                ),
+        )
+        .subcommand(
+            Command::new("status")
+                .about("Print the status of the device")
+                .arg(
+                    arg!(--json)
+                        .help("Print the status as JSON")
+                        .conflicts_with("format"),
+                )
+                .arg(arg!(--format <template>).help(
+                    "Print the status using a template, e.g. \"{station.connected_network.name}\"",
+                )),
+        )
+        .subcommand(
+            Command::new("bar")
+                .about("Print a status line every time the status changes, for status bars")
+                .arg(arg!(--json).help("Print the status in the Waybar JSON format"))
+                .arg(
+                    arg!(--interval <seconds>)
+                        .help("Interval between two status checks when the iwd signals can not be received")
+                        .value_parser(clap::value_parser!(u64).range(1..))
+                        .default_value("2"),
+                ),
+        )
+        .subcommand(
+            Command::new("config")
+                .about("Manage the config file")
+                .subcommand_required(true)
+                .subcommand(Command::new("check").about("Check the config file for errors"))
+                .subcommand(
+                    Command::new("migrate")
+                        .about("Rewrite the config file to the current version of the schema"),
+                ),
+        )
+        .subcommand(
+            Command::new("scan")
+                .about("Scan for networks")
+                .arg(arg!(--wait).help("Wait for the scan to complete")),
         )
 }
@@ -0,0 +1,289 @@
+use anyhow::{Context, Result};
+use std::{io::Read, sync::Arc, time::Duration};
+
+use clap::ArgMatches;
+use iwdrs::{
+    error::{
+        IWDError,
+        network::ConnectError,
+        station::{DisconnectError, ScanError},
+    },
+    network::NetworkType,
+};
+
+use crate::{
+    agent::PassphraseAgent,
+    backend::WifiBackend,
+    config::Config,
+    device::Device,
+    mode::station::{Station, known_network::KnownNetwork, network::signal_percentage},
+};
+
+pub mod bar;
+pub mod config;
+pub mod status;
+
+use status::Status;
+
+/// Exit status of the non-interactive commands.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExitStatus {
+    Success = 0,
+    Failure = 1,
+    NotFound = 2,
+    ConnectionFailed = 3,
+    Blocked = 4,
+}
+
+pub async fn run(
+    args: &ArgMatches,
+    config: Arc<Config>,
+    backend: Arc<dyn WifiBackend>,
+) -> Result<ExitStatus> {
+    let device_name = args.get_one::<String>("device").map(String::as_str);
+
+    match args.subcommand() {
+        Some(("station" | "scan", _)) if is_blocked(&backend).await => Ok(ExitStatus::Blocked),
+        Some(("station", args)) => match args.subcommand() {
+            Some(("list", _)) => list_networks(backend, config, device_name).await,
+            Some(("connect", args)) => {
+                let ssid = args.get_one::<String>("ssid").unwrap();
+                let passphrase = if args.get_flag("passphrase-stdin") {
+                    Some(read_passphrase()?)
+                } else {
+                    None
+                };
+                connect(backend, config, device_name, ssid, passphrase).await
+            }
+            Some(("disconnect", _)) => disconnect(backend, config, device_name).await,
+            _ => unreachable!(),
+        },
+        Some(("known", args)) => match args.subcommand() {
+            Some(("list", _)) => list_known_networks(backend).await,
+            Some(("forget", args)) => {
+                let ssid = args.get_one::<String>("ssid").unwrap();
+                forget(backend, ssid).await
+            }
+            _ => unreachable!(),
+        },
+        Some(("status", args)) => {
+            let status = Status::new(backend, config, device_name).await?;
+            if args.get_flag("json") {
+                println!("{}", status.to_json()?);
+            } else if let Some(template) = args.get_one::<String>("format") {
+                println!("{}", status.format(template)?);
+            } else {
+                status.print();
+            }
+            Ok(ExitStatus::Success)
+        }
+        Some(("bar", args)) => {
+            let interval = Duration::from_secs(*args.get_one::<u64>("interval").unwrap());
+            bar::run(
+                backend,
+                config,
+                device_name,
+                args.get_flag("json"),
+                interval,
+            )
+            .await
+        }
+        Some(("scan", args)) => scan(backend, config, device_name, args.get_flag("wait")).await,
+        _ => unreachable!(),
+    }
+}
+
+/// The station can not do anything while the radio is turned off by rfkill.
+async fn is_blocked(backend: &Arc<dyn WifiBackend>) -> bool {
+    let rfkill = backend.rfkill().await.unwrap_or_default();
+    if rfkill.is_blocked() {
+        eprintln!("{}", rfkill.message());
+    }
+    rfkill.is_blocked()
+}
+
+fn read_passphrase() -> Result<String> {
+    let mut passphrase = String::new();
+    std::io::stdin()
+        .read_to_string(&mut passphrase)
+        .context("Can not read the passphrase from stdin")?;
+
+    Ok(passphrase.trim_end_matches(['\r', '\n']).to_string())
+}
+
+async fn find_station(
+    backend: Arc<dyn WifiBackend>,
+    config: Arc<Config>,
+    device_name: Option<&str>,
+) -> Result<Station> {
+    let device = Device::find(backend, config, device_name).await?;
+    device
+        .station
+        .with_context(|| format!("No station found on {}", device.name))
+}
+
+async fn list_networks(
+    backend: Arc<dyn WifiBackend>,
+    config: Arc<Config>,
+    device_name: Option<&str>,
+) -> Result<ExitStatus> {
+    let station = find_station(backend, config, device_name).await?;
+
+    for (net, signal) in station.known_networks.iter().chain(&station.new_networks) {
+        println!(
+            "{}\t{}\t{}%\t{}\t{}",
+            net.name,
+            net.network_type,
+            signal_percentage(*signal),
+            if net.known_network.is_some() {
+                "known"
+            } else {
+                "new"
+            },
+            if net.is_connected { "connected" } else { "" },
+        );
+    }
+
+    Ok(ExitStatus::Success)
+}
+
+async fn connect(
+    backend: Arc<dyn WifiBackend>,
+    config: Arc<Config>,
+    device_name: Option<&str>,
+    ssid: &str,
+    passphrase: Option<String>,
+) -> Result<ExitStatus> {
+    let station = find_station(backend.clone(), config, device_name).await?;
+
+    let Some((net, _)) = station
+        .known_networks
+        .iter()
+        .chain(&station.new_networks)
+        .find(|(net, _)| net.name == ssid)
+    else {
+        eprintln!("Network {} not found", ssid);
+        return Ok(ExitStatus::NotFound);
+    };
+
+    if net.network_type == NetworkType::Eap && net.known_network.is_none() {
+        eprintln!("WPA Enterprise networks must be configured from the TUI before connecting");
+        return Ok(ExitStatus::Failure);
+    }
+
+    let has_passphrase = passphrase.is_some();
+    backend
+        .register_agent(Arc::new(PassphraseAgent::new(passphrase)))
+        .await?;
+
+    match backend.connect(&station.device_name, &net.name).await {
+        Ok(()) => {
+            println!("Connected to {}", net.name);
+            Ok(ExitStatus::Success)
+        }
+        Err(e) => match e.downcast_ref::<IWDError<ConnectError>>() {
+            Some(IWDError::OperationError(e)) => {
+                eprintln!("{}", e);
+                if matches!(e, ConnectError::Aborted) && !has_passphrase {
+                    eprintln!("Use --passphrase-stdin to provide the passphrase");
+                }
+                Ok(ExitStatus::ConnectionFailed)
+            }
+            _ => Err(e),
+        },
+    }
+}
+
+async fn disconnect(
+    backend: Arc<dyn WifiBackend>,
+    config: Arc<Config>,
+    device_name: Option<&str>,
+) -> Result<ExitStatus> {
+    let station = find_station(backend.clone(), config, device_name).await?;
+
+    let Some(network) = station.connected_network else {
+        eprintln!("Not connected to any network");
+        return Ok(ExitStatus::Failure);
+    };
+
+    match backend.disconnect(&station.device_name).await {
+        Ok(()) => {
+            println!("Disconnected from {}", network.name);
+            Ok(ExitStatus::Success)
+        }
+        Err(e) => match e.downcast_ref::<IWDError<DisconnectError>>() {
+            Some(IWDError::OperationError(e)) => {
+                eprintln!("{}", e);
+                Ok(ExitStatus::Failure)
+            }
+            _ => Err(e),
+        },
+    }
+}
+
+async fn list_known_networks(backend: Arc<dyn WifiBackend>) -> Result<ExitStatus> {
+    for net in KnownNetwork::all(backend).await? {
+        println!(
+            "{}\t{}\t{}\t{}\t{}",
+            net.name,
+            net.network_type,
+            if net.is_autoconnect {
+                "autoconnect"
+            } else {
+                "manual"
+            },
+            if net.is_hidden { "hidden" } else { "visible" },
+            net.last_connected
+                .map(|time| time.to_rfc3339())
+                .unwrap_or("-".to_string()),
+        );
+    }
+
+    Ok(ExitStatus::Success)
+}
+
+async fn forget(backend: Arc<dyn WifiBackend>, ssid: &str) -> Result<ExitStatus> {
+    let Some(net) = KnownNetwork::all(backend.clone())
+        .await?
+        .into_iter()
+        .find(|net| net.name == ssid)
+    else {
+        eprintln!("Known network {} not found", ssid);
+        return Ok(ExitStatus::NotFound);
+    };
+
+    backend.forget(&net.name).await?;
+    println!("The Network {} is removed", net.name);
+
+    Ok(ExitStatus::Success)
+}
+
+async fn scan(
+    backend: Arc<dyn WifiBackend>,
+    config: Arc<Config>,
+    device_name: Option<&str>,
+    wait: bool,
+) -> Result<ExitStatus> {
+    let station = find_station(backend.clone(), config, device_name).await?;
+
+    if let Err(e) = backend.scan(&station.device_name).await {
+        match e.downcast_ref::<IWDError<ScanError>>() {
+            // A scan is already running, we can still wait for it
+            Some(IWDError::OperationError(ScanError::Busy)) if wait => {}
+            Some(IWDError::OperationError(e)) => {
+                eprintln!("{}", e);
+                return Ok(ExitStatus::Failure);
+            }
+            _ => return Err(e),
+        }
+    }
+
+    if wait {
+        backend.wait_for_scan_complete(&station.device_name).await?;
+        println!("Scan completed");
+    } else {
+        println!("Start Scanning");
+    }
+
+    Ok(ExitStatus::Success)
+}
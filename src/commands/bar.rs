@@ -0,0 +1,167 @@
+use anyhow::Result;
+use std::{io::Write, sync::Arc, time::Duration};
+
+use serde::Serialize;
+use tokio::sync::mpsc;
+
+use crate::{
+    backend::WifiBackend,
+    commands::{ExitStatus, status::Status},
+    config::Config,
+    event::Event,
+    icons::Icons,
+    mode::station::network::frequency_band,
+    monitor,
+};
+
+/// One line of the status bar module, following the schema of Waybar's custom modules.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct BarLine {
+    pub text: String,
+    pub tooltip: String,
+    pub class: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percentage: Option<i16>,
+}
+
+impl BarLine {
+    pub fn new(status: &Status, icons: Icons) -> Self {
+        if status.device.rfkill.is_blocked() {
+            return Self {
+                text: format!("{} Off", icons.disconnected()),
+                tooltip: format!(
+                    "{}: {} by rfkill",
+                    status.device.name,
+                    status.device.rfkill.label()
+                ),
+                class: "blocked".to_string(),
+                percentage: Some(0),
+            };
+        }
+
+        if !status.device.powered {
+            return Self {
+                text: format!("{} Off", icons.disconnected()),
+                tooltip: format!("{}: powered off", status.device.name),
+                class: "off".to_string(),
+                percentage: Some(0),
+            };
+        }
+
+        if let Some(ap) = &status.access_point {
+            let ssid = ap.ssid.clone().unwrap_or("-".to_string());
+            return Self {
+                text: format!("{} {ssid}", icons.access_point()),
+                tooltip: format!(
+                    "{}: access point {}\nConnected devices: {}",
+                    status.device.name,
+                    ssid,
+                    ap.connected_devices.len()
+                ),
+                class: "ap".to_string(),
+                percentage: Some(0),
+            };
+        }
+
+        let Some(station) = &status.station else {
+            return Self::unavailable(&format!("{}: no station", status.device.name), icons);
+        };
+
+        match &station.connected_network {
+            Some(net) => {
+                let band = station
+                    .diagnostic
+                    .as_ref()
+                    .map(|diagnostic| frequency_band(diagnostic.frequency_mhz))
+                    .unwrap_or("-");
+                let security = station
+                    .diagnostic
+                    .as_ref()
+                    .map(|diagnostic| diagnostic.security.clone())
+                    .unwrap_or(net.security.clone());
+
+                let icon = match net.signal {
+                    Some(signal) => icons.signal(signal),
+                    None => icons.connected().trim_end(),
+                };
+                let signal = net
+                    .signal
+                    .map(|signal| format!("{}%", signal))
+                    .unwrap_or("-".to_string());
+
+                Self {
+                    text: format!("{} {}", icon, net.name),
+                    tooltip: format!(
+                        "{}\nSignal: {}\nBand: {}\nSecurity: {}\nState: {}",
+                        net.name, signal, band, security, station.state
+                    ),
+                    class: station.state.clone(),
+                    percentage: net.signal,
+                }
+            }
+            None => Self {
+                text: format!("{} {}", icons.disconnected(), station.state),
+                tooltip: format!("{}: {}", status.device.name, station.state),
+                class: station.state.clone(),
+                percentage: Some(0),
+            },
+        }
+    }
+
+    pub fn unavailable(reason: &str, icons: Icons) -> Self {
+        Self {
+            text: format!("{} Unavailable", icons.disconnected()),
+            tooltip: reason.to_string(),
+            class: "unavailable".to_string(),
+            percentage: Some(0),
+        }
+    }
+}
+
+/// Prints a new line every time the status changes, until stdout is closed. The status is
+/// checked again on the iwd signals, or every `interval` when they can not be received.
+pub async fn run(
+    backend: Arc<dyn WifiBackend>,
+    config: Arc<Config>,
+    device_name: Option<&str>,
+    json: bool,
+    interval: Duration,
+) -> Result<ExitStatus> {
+    let mut previous_line = None;
+    let mut ticker = tokio::time::interval(interval);
+
+    let (sender, mut events) = mpsc::unbounded_channel();
+    let mut is_monitored = monitor::spawn(sender).await.is_ok();
+
+    loop {
+        let line = match Status::new(backend.clone(), config.clone(), device_name).await {
+            Ok(status) => BarLine::new(&status, config.icons),
+            Err(e) => BarLine::unavailable(&e.to_string(), config.icons),
+        };
+
+        if previous_line.as_ref() != Some(&line) {
+            let output = if json {
+                serde_json::to_string(&line)?
+            } else {
+                line.text.clone()
+            };
+
+            let mut stdout = std::io::stdout().lock();
+            if writeln!(stdout, "{output}")
+                .and_then(|_| stdout.flush())
+                .is_err()
+            {
+                return Ok(ExitStatus::Success);
+            }
+
+            previous_line = Some(line);
+        }
+
+        if is_monitored {
+            // Anything else than a refresh means that the signals stopped
+            is_monitored = matches!(events.recv().await, Some(Event::Refresh(_)));
+        } else {
+            ticker.tick().await;
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use anyhow::{Context, Result};
+use std::fs;
+
+use clap::ArgMatches;
+
+use crate::{commands::ExitStatus, config::Config};
+
+pub fn run(args: &ArgMatches) -> Result<ExitStatus> {
+    match args.subcommand() {
+        Some(("check", _)) => Ok(check()),
+        Some(("migrate", _)) => migrate(),
+        _ => unreachable!(),
+    }
+}
+
+fn check() -> ExitStatus {
+    match Config::load() {
+        Ok(config) => {
+            for warning in &config.warnings {
+                eprintln!("Warning: {}", warning);
+            }
+            println!("{}: OK", Config::path().display());
+            ExitStatus::Success
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitStatus::Failure
+        }
+    }
+}
+
+fn migrate() -> Result<ExitStatus> {
+    let path = Config::path();
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            println!("{} does not exist, nothing to migrate", path.display());
+            return Ok(ExitStatus::Success);
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let (migrated, changes) = match Config::migrate(&content) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("{}: {}", path.display(), e);
+            return Ok(ExitStatus::Failure);
+        }
+    };
+
+    if changes.is_empty() {
+        println!("{} is up to date", path.display());
+        return Ok(ExitStatus::Success);
+    }
+
+    let backup = path.with_extension("toml.bak");
+    fs::copy(&path, &backup)
+        .with_context(|| format!("Can not back up the config to {}", backup.display()))?;
+    fs::write(&path, migrated)?;
+
+    for change in changes {
+        println!("{}", change);
+    }
+    println!(
+        "{} migrated, the previous version is saved as {}",
+        path.display(),
+        backup.display()
+    );
+
+    Ok(ExitStatus::Success)
+}
@@ -0,0 +1,272 @@
+use anyhow::Result;
+use std::sync::Arc;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    adapter::Adapter,
+    backend::WifiBackend,
+    config::Config,
+    device::Device,
+    mode::station::network::{Network, signal_percentage},
+    rfkill::RfkillState,
+};
+
+#[derive(Debug, Serialize)]
+pub struct Status {
+    pub device: DeviceStatus,
+    pub adapter: AdapterStatus,
+    pub station: Option<StationStatus>,
+    pub access_point: Option<AccessPointStatus>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeviceStatus {
+    pub name: String,
+    pub address: String,
+    pub mode: String,
+    pub powered: bool,
+    pub rfkill: RfkillState,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdapterStatus {
+    pub name: String,
+    pub model: Option<String>,
+    pub vendor: Option<String>,
+    pub powered: bool,
+    pub supported_modes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StationStatus {
+    pub state: String,
+    pub scanning: bool,
+    pub connected_network: Option<NetworkStatus>,
+    pub networks: Vec<NetworkStatus>,
+    pub diagnostic: Option<DiagnosticStatus>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkStatus {
+    pub name: String,
+    pub security: String,
+    /// Signal strength in dBm, `None` when iwd does not report it
+    pub rssi: Option<i16>,
+    /// Signal strength in percent
+    pub signal: Option<i16>,
+    pub known: bool,
+    pub connected: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiagnosticStatus {
+    pub connected_bss: String,
+    pub frequency_mhz: u32,
+    pub channel: Option<u16>,
+    pub security: String,
+    pub rssi: Option<i16>,
+    pub average_rssi: Option<i16>,
+    pub rx_mode: Option<String>,
+    pub rx_rate_kbps: Option<u64>,
+    pub rx_mcs: Option<u8>,
+    pub tx_mode: Option<String>,
+    pub tx_rate_kbps: Option<u64>,
+    pub tx_mcs: Option<u8>,
+    pub pairwise_cipher: Option<String>,
+    pub inactive_time_ms: Option<u128>,
+    pub connected_time_s: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccessPointStatus {
+    pub started: bool,
+    pub ssid: Option<String>,
+    pub frequency_mhz: Option<u32>,
+    pub cipher: Option<String>,
+    pub connected_devices: Vec<String>,
+}
+
+impl NetworkStatus {
+    /// `signal` is in 100 * dBm.
+    fn new(net: &Network, signal: Option<i16>) -> Self {
+        Self {
+            name: net.name.clone(),
+            security: net.network_type.to_string().to_lowercase(),
+            rssi: signal.map(|signal| signal / 100),
+            signal: signal.map(signal_percentage),
+            known: net.known_network.is_some(),
+            connected: net.is_connected,
+        }
+    }
+}
+
+impl Status {
+    pub async fn new(
+        backend: Arc<dyn WifiBackend>,
+        config: Arc<Config>,
+        device_name: Option<&str>,
+    ) -> Result<Self> {
+        let device = Device::find(backend.clone(), config.clone(), device_name).await?;
+        // Reported as unblocked when there is no rfkill switch to read
+        let rfkill = backend.rfkill().await.unwrap_or_default();
+        let adapter = Adapter::new(backend, device.name.clone(), config).await?;
+
+        let station = device.station.as_ref().map(|station| {
+            let networks: Vec<NetworkStatus> = station
+                .known_networks
+                .iter()
+                .chain(&station.new_networks)
+                .map(|(net, signal)| NetworkStatus::new(net, Some(*signal)))
+                .collect();
+
+            let connected_network = station.connected_network.as_ref().map(|connected_net| {
+                networks
+                    .iter()
+                    .find(|net| net.name == connected_net.name)
+                    .cloned()
+                    // Not in the scan results yet, the diagnostics may still know the signal
+                    .unwrap_or_else(|| {
+                        let rssi = station.diagnostic.as_ref().and_then(|d| d.rssi);
+                        NetworkStatus::new(connected_net, rssi.map(|rssi| rssi * 100))
+                    })
+            });
+
+            let diagnostic = station.diagnostic.as_ref().map(|d| DiagnosticStatus {
+                connected_bss: d.connected_bss.clone(),
+                frequency_mhz: d.frequency_mhz,
+                channel: d.channel,
+                security: d.security.to_string(),
+                rssi: d.rssi,
+                average_rssi: d.average_rssi,
+                rx_mode: d.rx_mode.as_ref().map(|mode| mode.to_string()),
+                rx_rate_kbps: d.rx_rate_kbps,
+                rx_mcs: d.rx_mcs,
+                tx_mode: d.tx_mode.as_ref().map(|mode| mode.to_string()),
+                tx_rate_kbps: d.tx_rate_kbps,
+                tx_mcs: d.tx_mcs,
+                pairwise_cipher: d.pairwise_cipher.as_ref().map(|cipher| cipher.to_string()),
+                inactive_time_ms: d.inactive_time.map(|time| time.as_millis()),
+                connected_time_s: d.connected_time.map(|time| time.as_secs()),
+            });
+
+            StationStatus {
+                state: station.state.to_string().to_lowercase(),
+                scanning: station.is_scanning,
+                connected_network,
+                networks,
+                diagnostic,
+            }
+        });
+
+        let access_point = device.ap.as_ref().map(|ap| AccessPointStatus {
+            started: ap.has_started,
+            ssid: ap.name.clone(),
+            frequency_mhz: ap.frequency,
+            cipher: ap.used_cipher.clone(),
+            connected_devices: ap.connected_devices.clone(),
+        });
+
+        Ok(Self {
+            device: DeviceStatus {
+                name: device.name.clone(),
+                address: device.address.clone(),
+                mode: device.mode.to_string(),
+                powered: device.is_powered,
+                rfkill,
+            },
+            adapter: AdapterStatus {
+                name: adapter.name.clone(),
+                model: adapter.model.clone(),
+                vendor: adapter.vendor.clone(),
+                powered: adapter.is_powered,
+                supported_modes: adapter.supported_modes.clone(),
+            },
+            station,
+            access_point,
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Replaces every `{path}` placeholder of the template with the matching value of the
+    /// JSON document, e.g. `{device.name}`, `{station.connected_network.signal}` or
+    /// `{station.networks.0.name}`. Missing values are replaced with an empty string.
+    pub fn format(&self, template: &str) -> Result<String> {
+        let status = serde_json::to_value(self)?;
+        let mut output = String::new();
+        let mut rest = template;
+
+        while let Some(start) = rest.find('{') {
+            output.push_str(&rest[..start]);
+            match rest[start..].find('}') {
+                Some(end) => {
+                    let path = &rest[start + 1..start + end];
+                    output.push_str(&lookup(&status, path));
+                    rest = &rest[start + end + 1..];
+                }
+                None => {
+                    output.push_str(&rest[start..]);
+                    rest = "";
+                }
+            }
+        }
+        output.push_str(rest);
+
+        Ok(output)
+    }
+
+    pub fn print(&self) {
+        println!(
+            "Device: {} ({}, {})",
+            self.device.name,
+            self.device.mode,
+            match self.device.rfkill {
+                RfkillState::Unblocked if self.device.powered => "on",
+                RfkillState::Unblocked => "off",
+                rfkill => rfkill.label(),
+            }
+        );
+
+        if let Some(station) = &self.station {
+            println!("State: {}", station.state);
+            if let Some(net) = &station.connected_network {
+                match net.signal {
+                    Some(signal) => {
+                        println!("Network: {} ({}, {}%)", net.name, net.security, signal)
+                    }
+                    None => println!("Network: {} ({})", net.name, net.security),
+                }
+            }
+            if let Some(diagnostic) = &station.diagnostic {
+                println!(
+                    "Frequency: {:.2} GHz",
+                    diagnostic.frequency_mhz as f32 / 1000.
+                );
+                println!("Security: {}", diagnostic.security);
+            }
+        }
+
+        if let Some(ap) = &self.access_point {
+            println!("Access Point: {}", ap.ssid.as_deref().unwrap_or("-"));
+            println!("Connected devices: {}", ap.connected_devices.len());
+        }
+    }
+}
+
+fn lookup(value: &Value, path: &str) -> String {
+    let value = path.split('.').try_fold(value, |value, key| match value {
+        Value::Object(map) => map.get(key),
+        Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    });
+
+    match value {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Null) | None => String::new(),
+        Some(v) => v.to_string(),
+    }
+}
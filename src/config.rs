@@ -1,27 +1,76 @@
-use toml;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
 
-use dirs;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer, de};
+
+use crate::icons::Icons;
+use crate::keymap::{Keymap, KeymapConfig};
+use crate::mode::station::sort::SortKey;
+use crate::theme::{Theme, ThemeConfig};
+
+/// The version of the config schema written by `impala config migrate`.
+pub const CONFIG_VERSION: u32 = 2;
+
+/// Keys renamed since the first version of the schema, with their current name.
+pub const DEPRECATED_KEYS: &[(&str, &str)] = &[
+    ("ap", "access_point"),
+    ("station.toggle_scanning", "station.start_scanning"),
+];
 
 #[derive(Deserialize, Debug)]
 pub struct Config {
+    #[serde(default = "default_config_version")]
+    pub version: u32,
+
     #[serde(default = "default_switch_mode")]
     pub switch: char,
 
-    #[serde(default = "default_device_mode")]
+    #[serde(default = "default_device_mode", deserialize_with = "deserialize_mode")]
     pub mode: String,
 
     #[serde(default = "default_esc_quit")]
     pub esc_quit: bool,
 
+    #[serde(default)]
+    pub icons: Icons,
+
+    /// Whether the mouse is captured, the terminal keeps its text selection when disabled.
+    #[serde(default = "default_mouse")]
+    pub mouse: bool,
+
     #[serde(default)]
     pub device: Device,
 
     #[serde(default)]
     pub station: Station,
 
-    #[serde(default)]
+    #[serde(default, rename = "access_point", alias = "ap")]
     pub ap: AccessPoint,
+
+    #[serde(default, rename = "keymap")]
+    keymap_config: KeymapConfig,
+
+    /// The bindings of every action, built from the settings above.
+    #[serde(skip)]
+    pub keymap: Keymap,
+
+    #[serde(default, rename = "theme")]
+    theme_config: ThemeConfig,
+
+    /// The colors of the built-in theme with the overrides of the `[theme]` section.
+    #[serde(skip)]
+    pub theme: Theme,
+
+    /// Unknown and deprecated keys found in the config file.
+    #[serde(skip)]
+    pub warnings: Vec<String>,
+}
+
+// Files without a version were written for the first schema
+fn default_config_version() -> u32 {
+    1
 }
 
 fn default_switch_mode() -> char {
@@ -32,16 +81,38 @@ fn default_device_mode() -> String {
     "station".to_string()
 }
 
+fn deserialize_mode<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mode = String::deserialize(deserializer)?;
+    match mode.as_str() {
+        "station" | "ap" | "p2p" => Ok(mode),
+        _ => Err(de::Error::invalid_value(
+            de::Unexpected::Str(&mode),
+            &"\"station\", \"ap\" or \"p2p\"",
+        )),
+    }
+}
+
 fn default_esc_quit() -> bool {
     false
 }
 
+fn default_mouse() -> bool {
+    true
+}
+
 // Device
 #[derive(Deserialize, Debug)]
+#[serde(default)]
 pub struct Device {
     #[serde(default = "default_show_device_infos")]
     pub infos: char,
     pub toggle_power: char,
+
+    #[serde(default = "default_select_device")]
+    pub select: char,
 }
 
 impl Default for Device {
@@ -49,6 +120,7 @@ impl Default for Device {
         Self {
             infos: 'i',
             toggle_power: 'o',
+            select: 'd',
         }
     }
 }
@@ -57,10 +129,15 @@ fn default_show_device_infos() -> char {
     'i'
 }
 
+fn default_select_device() -> char {
+    'd'
+}
+
 // Station
 #[derive(Deserialize, Debug)]
+#[serde(default)]
 pub struct Station {
-    #[serde(default = "default_station_start_scanning")]
+    #[serde(default = "default_station_start_scanning", alias = "toggle_scanning")]
     pub start_scanning: char,
 
     #[serde(default)]
@@ -68,6 +145,11 @@ pub struct Station {
 
     #[serde(default)]
     pub new_network: NewNetwork,
+
+    /// A notification is shown when the signal of the connection crosses one of these levels,
+    /// in dBm. An empty list disables the notifications.
+    #[serde(default = "default_station_signal_thresholds")]
+    pub signal_thresholds: Vec<i16>,
 }
 
 impl Default for Station {
@@ -76,21 +158,30 @@ impl Default for Station {
             start_scanning: 's',
             known_network: KnownNetwork::default(),
             new_network: NewNetwork::default(),
+            signal_thresholds: default_station_signal_thresholds(),
         }
     }
 }
 
+fn default_station_signal_thresholds() -> Vec<i16> {
+    vec![-67, -75]
+}
+
 fn default_station_start_scanning() -> char {
     's'
 }
 
 #[derive(Deserialize, Debug)]
+#[serde(default)]
 pub struct KnownNetwork {
     #[serde(default = "default_station_remove_known_network")]
     pub remove: char,
     pub toggle_autoconnect: char,
     pub show_all: char,
     pub share: char,
+
+    /// The initial order of the table.
+    pub sort: SortKey,
 }
 
 impl Default for KnownNetwork {
@@ -100,6 +191,7 @@ impl Default for KnownNetwork {
             toggle_autoconnect: 't',
             show_all: 'a',
             share: 'p',
+            sort: SortKey::default(),
         }
     }
 }
@@ -109,22 +201,28 @@ fn default_station_remove_known_network() -> char {
 }
 
 #[derive(Deserialize, Debug)]
+#[serde(default)]
 pub struct NewNetwork {
     pub show_all: char,
     pub connect_hidden: char,
+
+    /// The initial order of the table.
+    pub sort: SortKey,
 }
 
 impl Default for NewNetwork {
     fn default() -> Self {
         Self {
             show_all: 'a',
-            connect_hidden: 'n',
+            connect_hidden: 'h',
+            sort: SortKey::default(),
         }
     }
 }
 
 // Access Point
 #[derive(Deserialize, Debug)]
+#[serde(default)]
 pub struct AccessPoint {
     #[serde(default = "default_ap_start")]
     pub start: char,
@@ -151,21 +249,321 @@ fn default_ap_stop() -> char {
 }
 
 impl Config {
-    pub fn new() -> Self {
-        let conf_path = dirs::config_dir()
+    /// The path of the config file, `$XDG_CONFIG_HOME/impala/config.toml`.
+    pub fn path() -> PathBuf {
+        dirs::config_dir()
             .unwrap()
             .join("impala")
-            .join("config.toml");
+            .join("config.toml")
+    }
+
+    /// Reads the config file, a missing file gives the default config.
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::load_from(&Self::path())
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self, ConfigError> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                return Err(ConfigError {
+                    path: path.to_path_buf(),
+                    line: None,
+                    column: None,
+                    key: None,
+                    message: e.to_string(),
+                });
+            }
+        };
+
+        let mut unknown_keys = Vec::new();
+        let deserializer =
+            toml::Deserializer::parse(&content).map_err(|e| ConfigError::new(path, &content, e))?;
+        let mut config: Config =
+            serde_ignored::deserialize(deserializer, |key| unknown_keys.push(key.to_string()))
+                .map_err(|e| ConfigError::new(path, &content, e))?;
+
+        let table = content.parse::<toml::Table>().unwrap_or_default();
+
+        config.keymap = Keymap::new(&config, &config.keymap_config, |key| is_set(&table, key))
+            .map_err(|e| ConfigError::at_key(path, &content, &e.key, e.message))?;
+
+        config.theme = Theme::new(&config.theme_config);
+
+        if let Some(threshold) = config
+            .station
+            .signal_thresholds
+            .iter()
+            .find(|threshold| !(-100..0).contains(*threshold))
+        {
+            return Err(ConfigError::at_key(
+                path,
+                &content,
+                "station.signal_thresholds",
+                format!(
+                    "invalid signal threshold {}, expected a level in dBm from -100 to -1",
+                    threshold
+                ),
+            ));
+        }
+
+        // iwd wants distinct levels
+        let thresholds = &config.station.signal_thresholds;
+        if let Some(threshold) = thresholds
+            .iter()
+            .enumerate()
+            .find_map(|(i, threshold)| thresholds[..i].contains(threshold).then_some(threshold))
+        {
+            return Err(ConfigError::at_key(
+                path,
+                &content,
+                "station.signal_thresholds",
+                format!("duplicate signal threshold {}", threshold),
+            ));
+        }
+
+        unknown_keys.extend(Keymap::unknown_keys(&config.keymap_config));
+        config.warnings = unknown_keys
+            .into_iter()
+            .map(|key| format!("Unknown key `{}`", key))
+            .collect();
+
+        for (old_key, new_key) in DEPRECATED_KEYS {
+            if get_key(&table, old_key).is_some() {
+                config.warnings.push(format!(
+                    "`{}` is deprecated, use `{}` instead",
+                    old_key, new_key
+                ));
+            }
+        }
+
+        if config.version > CONFIG_VERSION {
+            config.warnings.push(format!(
+                "The config is for version {} of the schema, this impala only knows version {}",
+                config.version, CONFIG_VERSION
+            ));
+        }
+
+        Ok(config)
+    }
+
+    /// Rewrites a config file to the current version of the schema, keeping its comments.
+    /// Returns the new content and the list of changes.
+    pub fn migrate(content: &str) -> Result<(String, Vec<String>), toml_edit::TomlError> {
+        let mut document = content.parse::<toml_edit::DocumentMut>()?;
+        let mut changes = Vec::new();
+
+        for (old_key, new_key) in DEPRECATED_KEYS {
+            let (old_parent, old_name) = split_key(old_key);
+            let (new_parent, new_name) = split_key(new_key);
+
+            let Some(old_table) = table_mut(&mut document, old_parent) else {
+                continue;
+            };
+            let Some(item) = old_table.remove(old_name) else {
+                continue;
+            };
+
+            match table_mut(&mut document, new_parent) {
+                Some(new_table) if !new_table.contains_key(new_name) => {
+                    new_table.insert(new_name, item);
+                    changes.push(format!("Renamed `{}` to `{}`", old_key, new_key));
+                }
+                _ => changes.push(format!(
+                    "Removed `{}`, `{}` is already set",
+                    old_key, new_key
+                )),
+            }
+        }
+
+        let version = document.get("version").and_then(|v| v.as_integer());
+        if version != Some(CONFIG_VERSION as i64) {
+            document.insert("version", toml_edit::value(CONFIG_VERSION as i64));
+            changes.push(format!("Set `version` to {}", CONFIG_VERSION));
+        }
+
+        Ok((document.to_string(), changes))
+    }
+}
+
+/// Splits a dotted key into its parent table and its name, e.g. `station` and `start_scanning`.
+fn split_key(key: &str) -> (Option<&str>, &str) {
+    match key.rsplit_once('.') {
+        Some((parent, name)) => (Some(parent), name),
+        None => (None, key),
+    }
+}
+
+fn get_key<'a>(table: &'a toml::Table, key: &str) -> Option<&'a toml::Value> {
+    let (parent, name) = split_key(key);
+    match parent {
+        Some(parent) => get_key(table, parent)?.as_table()?.get(name),
+        None => table.get(name),
+    }
+}
 
-        let config = std::fs::read_to_string(conf_path).unwrap_or_default();
-        let app_config: Config = toml::from_str(&config).unwrap();
+/// Whether a key is written in the config file, under its current or its deprecated name.
+fn is_set(table: &toml::Table, key: &str) -> bool {
+    get_key(table, key).is_some()
+        || DEPRECATED_KEYS.iter().any(|(old_key, new_key)| {
+            key.strip_prefix(new_key)
+                .filter(|rest| rest.is_empty() || rest.starts_with('.'))
+                .is_some_and(|rest| get_key(table, &format!("{}{}", old_key, rest)).is_some())
+        })
+}
 
-        app_config
+fn table_mut<'a>(
+    document: &'a mut toml_edit::DocumentMut,
+    key: Option<&str>,
+) -> Option<&'a mut dyn toml_edit::TableLike> {
+    let mut table: &mut dyn toml_edit::TableLike = document.as_table_mut();
+    if let Some(key) = key {
+        for name in key.split('.') {
+            table = table.get_mut(name)?.as_table_like_mut()?;
+        }
     }
+    Some(table)
 }
 
 impl Default for Config {
     fn default() -> Self {
-        Self::new()
+        let mut config = Self {
+            version: CONFIG_VERSION,
+            switch: default_switch_mode(),
+            mode: default_device_mode(),
+            esc_quit: default_esc_quit(),
+            icons: Icons::default(),
+            mouse: default_mouse(),
+            device: Device::default(),
+            station: Station::default(),
+            ap: AccessPoint::default(),
+            keymap_config: KeymapConfig::default(),
+            keymap: Keymap::default(),
+            theme_config: ThemeConfig::default(),
+            theme: Theme::new(&ThemeConfig::default()),
+            warnings: Vec::new(),
+        };
+        config.keymap = Keymap::new(&config, &KeymapConfig::default(), |_| false).unwrap();
+        config
     }
 }
+
+/// An invalid config file, with the position of the offending key.
+#[derive(Debug, Clone)]
+pub struct ConfigError {
+    pub path: PathBuf,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub key: Option<String>,
+    pub message: String,
+}
+
+impl ConfigError {
+    fn new(path: &Path, content: &str, error: toml::de::Error) -> Self {
+        let mut config_error = Self {
+            path: path.to_path_buf(),
+            line: None,
+            column: None,
+            key: None,
+            message: error.message().trim().to_string(),
+        };
+
+        if let Some(span) = error.span() {
+            let before = &content[..span.start.min(content.len())];
+            let line = before.matches('\n').count();
+            let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+
+            config_error.line = Some(line + 1);
+            config_error.column = Some(before[line_start..].chars().count() + 1);
+            config_error.key = key_at(content, line);
+        }
+
+        config_error
+    }
+
+    fn at_key(path: &Path, content: &str, key: &str, message: String) -> Self {
+        let (line, column) = match locate_key(content, key) {
+            Some((line, column)) => (Some(line), Some(column)),
+            None => (None, None),
+        };
+
+        Self {
+            path: path.to_path_buf(),
+            line,
+            column,
+            key: Some(key.to_string()),
+            message,
+        }
+    }
+}
+
+/// Returns the line and column (1 based) where a dotted key is set.
+fn locate_key(content: &str, key: &str) -> Option<(usize, usize)> {
+    let (parent, name) = split_key(key);
+    let mut table = None;
+
+    for (index, line) in content.lines().enumerate() {
+        let text = line.trim_start();
+        if text.starts_with('[') {
+            table = Some(text.trim_start_matches('[').split(']').next()?.trim());
+            continue;
+        }
+
+        if table == parent
+            && let Some((line_key, _)) = text.split_once('=')
+            && line_key.trim().trim_matches('"') == name
+        {
+            return Some((index + 1, line.len() - text.len() + 1));
+        }
+    }
+
+    None
+}
+
+/// Returns the dotted key defined on the given line (0 based), e.g. `station.start_scanning`.
+fn key_at(content: &str, line: usize) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let text = lines.get(line)?.trim();
+
+    let table = |header: &str| {
+        header
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .trim()
+            .to_string()
+    };
+
+    if text.starts_with('[') {
+        return Some(table(text));
+    }
+
+    let (key, _) = text.split_once('=')?;
+    let key = key.trim().trim_matches('"');
+
+    let header = lines[..line]
+        .iter()
+        .rev()
+        .map(|line| line.trim())
+        .find(|line| line.starts_with('['));
+
+    match header {
+        Some(header) => Some(format!("{}.{}", table(header), key)),
+        None => Some(key.to_string()),
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let (Some(line), Some(column)) = (self.line, self.column) {
+            write!(f, ":{}:{}", line, column)?;
+        }
+        if let Some(key) = &self.key {
+            write!(f, ": `{}`", key)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl std::error::Error for ConfigError {}
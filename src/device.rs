@@ -1,53 +1,64 @@
 use anyhow::Context;
 use anyhow::Result;
+pub mod selector;
+pub mod traffic;
+
 use std::sync::Arc;
 
-use iwdrs::{device::Device as iwdDevice, modes::Mode, session::Session};
+use iwdrs::modes::Mode;
 
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Flex, Layout},
-    style::{Color, Style, Stylize},
+    style::{Style, Stylize},
     text::{Line, Span},
-    widgets::{Block, BorderType, Borders, Padding, Row, Table, TableState},
+    widgets::{Block, BorderType, Borders, Padding, Paragraph, Row, Table, TableState, Wrap},
 };
 
 use crate::{
     app::FocusedBlock,
+    backend::{DeviceInfo, WifiBackend},
     config::Config,
-    mode::{ap::AccessPoint, station::Station},
+    device::traffic::Traffic,
+    keymap::Action,
+    layout::is_compact,
+    mode::{ViewMode, ap::AccessPoint, p2p::P2p, station::Station},
+    monitor::Changes,
 };
 
 #[derive(Clone)]
 pub struct Device {
-    device: iwdDevice,
-    session: Arc<Session>,
+    backend: Arc<dyn WifiBackend>,
     pub name: String,
     pub address: String,
     pub mode: Mode,
     pub is_powered: bool,
     pub station: Option<Station>,
     pub ap: Option<AccessPoint>,
+    /// Set when the device is shown in the Wi-Fi Direct view
+    pub p2p: Option<P2p>,
+    pub traffic: Traffic,
+    config: Arc<Config>,
 }
 
 impl Device {
-    pub async fn new(session: Arc<Session>) -> Result<Self> {
-        let device = session.devices().await?.pop().context("No device found")?;
-        let name = device.name().await?;
-        let address = device.address().await?;
-        let mode = device.get_mode().await?;
-        let is_powered = device.is_powered().await?;
-
-        let (station, ap) = match mode {
+    pub async fn new(
+        backend: Arc<dyn WifiBackend>,
+        device: DeviceInfo,
+        config: Arc<Config>,
+    ) -> Result<Self> {
+        let (station, ap) = match device.mode {
             Mode::Station => {
-                if let Ok(station) = Station::new(session.clone()).await {
+                if let Ok(station) =
+                    Station::new(backend.clone(), device.name.clone(), &config).await
+                {
                     (Some(station), None)
                 } else {
                     (None, None)
                 }
             }
             Mode::Ap => {
-                if let Ok(ap) = AccessPoint::new(session.clone()).await {
+                if let Ok(ap) = AccessPoint::new(backend.clone(), device.name.clone()).await {
                     (None, Some(ap))
                 } else {
                     (None, None)
@@ -56,49 +67,113 @@ impl Device {
         };
 
         Ok(Self {
-            device,
-            session,
-            name,
-            address,
-            mode,
-            is_powered,
+            backend,
+            name: device.name,
+            address: device.address,
+            mode: device.mode,
+            is_powered: device.is_powered,
             station,
             ap,
+            p2p: None,
+            traffic: Traffic::default(),
+            config,
         })
     }
 
+    /// Returns all the devices managed by iwd, sorted by name.
+    pub async fn all(backend: Arc<dyn WifiBackend>, config: Arc<Config>) -> Result<Vec<Self>> {
+        let mut devices = Vec::new();
+        for device in backend.devices().await? {
+            devices.push(Self::new(backend.clone(), device, config.clone()).await?);
+        }
+        devices.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(devices)
+    }
+
+    /// Returns the device with the given interface name, or the first one.
+    pub async fn find(
+        backend: Arc<dyn WifiBackend>,
+        config: Arc<Config>,
+        name: Option<&str>,
+    ) -> Result<Self> {
+        let devices = Self::all(backend, config).await?;
+
+        match name {
+            Some(name) => devices
+                .into_iter()
+                .find(|device| device.name == name)
+                .with_context(|| format!("No device named {} found", name)),
+            None => devices.into_iter().next().context("No device found"),
+        }
+    }
+
+    pub fn view_mode(&self) -> ViewMode {
+        match (self.mode, &self.p2p) {
+            (Mode::Station, Some(_)) => ViewMode::P2p,
+            (Mode::Station, None) => ViewMode::Station,
+            (Mode::Ap, _) => ViewMode::Ap,
+        }
+    }
+
     pub async fn set_mode(&self, mode: Mode) -> Result<()> {
-        self.device.set_mode(mode).await?;
-        Ok(())
+        self.backend.set_mode(&self.name, mode).await
+    }
+
+    /// Records the traffic every tick, the kernel counters do not send any change.
+    pub async fn sample_traffic(&mut self) {
+        match self.backend.statistics(&self.name).await {
+            Ok(statistics) => self.traffic.push(statistics),
+            Err(e) => self.traffic.error = Some(e.to_string()),
+        }
     }
 
     pub async fn power_off(&self) -> Result<()> {
-        self.device.set_power(false).await?;
-        Ok(())
+        self.backend.set_power(&self.name, false).await
     }
 
     pub async fn power_on(&self) -> Result<()> {
-        self.device.set_power(true).await?;
-        Ok(())
+        self.backend.set_power(&self.name, true).await
     }
 
-    pub async fn refresh(&mut self) -> Result<()> {
-        self.is_powered = self.device.is_powered().await?;
-        self.mode = self.device.get_mode().await?;
+    pub async fn refresh(&mut self, changes: Changes) -> Result<()> {
+        if changes.device {
+            let device = self
+                .backend
+                .devices()
+                .await?
+                .into_iter()
+                .find(|device| device.name == self.name)
+                .with_context(|| format!("No device named {} found", self.name))?;
+            self.is_powered = device.is_powered;
+            self.mode = device.mode;
+        }
         if self.is_powered {
             match self.mode {
                 Mode::Station => {
                     if let Some(station) = &mut self.station {
-                        station.refresh().await?;
+                        if changes.station {
+                            station.refresh_state().await?;
+                        }
+                        if changes.networks {
+                            station.refresh_networks().await?;
+                        }
                     } else {
-                        self.station = Station::new(self.session.clone()).await.ok();
+                        self.station =
+                            Station::new(self.backend.clone(), self.name.clone(), &self.config)
+                                .await
+                                .ok();
                     }
                 }
                 Mode::Ap => {
                     if let Some(ap) = &mut self.ap {
-                        ap.refresh().await?;
+                        if changes.access_point {
+                            ap.refresh().await?;
+                        }
                     } else {
-                        self.ap = AccessPoint::new(self.session.clone()).await.ok();
+                        self.ap = AccessPoint::new(self.backend.clone(), self.name.clone())
+                            .await
+                            .ok();
                     }
                 }
             }
@@ -107,13 +182,16 @@ impl Device {
     }
 
     pub fn render(&mut self, frame: &mut Frame, focused_block: FocusedBlock, config: Arc<Config>) {
+        let theme = config.theme;
+        let help_height = if is_compact(frame.area()) { 2 } else { 1 };
+
         let (device_block, help_block) = {
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([
                     Constraint::Fill(1),
                     Constraint::Length(5),
-                    Constraint::Length(1),
+                    Constraint::Length(help_height),
                 ])
                 .margin(1)
                 .split(frame.area());
@@ -136,8 +214,8 @@ impl Device {
         let device_table = Table::new(vec![row], widths)
             .header({
                 Row::new(vec![
-                    Line::from("Name").yellow().centered(),
-                    Line::from("Powered").yellow().centered(),
+                    Line::from("Name").fg(theme.header).centered(),
+                    Line::from("Powered").fg(theme.header).centered(),
                 ])
                 .style(Style::new().bold())
                 .bottom_margin(1)
@@ -147,33 +225,72 @@ impl Device {
                     .title(" Device ")
                     .title_style(Style::default().bold())
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Green))
+                    .border_style(theme.border(true))
                     .border_type(BorderType::Thick)
                     .padding(Padding::horizontal(1)),
             )
             .column_spacing(1)
             .flex(Flex::SpaceAround)
-            .row_highlight_style(Style::default().bg(Color::DarkGray).fg(Color::White));
+            .row_highlight_style(theme.highlight());
 
         let mut device_state = TableState::default().with_selected(0);
         frame.render_stateful_widget(device_table, device_block, &mut device_state);
 
         let help_message = match focused_block {
             FocusedBlock::Device => Line::from(vec![
-                Span::from(config.device.infos.to_string()).bold(),
+                Span::from(config.keymap.key(Action::DeviceInfos)).bold(),
                 Span::from(" Infos"),
                 Span::from(" | "),
-                Span::from(config.device.toggle_power.to_string()).bold(),
+                Span::from(config.keymap.key(Action::TogglePower)).bold(),
                 Span::from(" Toggle Power"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::SelectDevice)).bold(),
+                Span::from(" Select Device"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::ShowTraffic)).bold(),
+                Span::from(" Traffic"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Help)).bold(),
+                Span::from(" Help"),
+            ]),
+            FocusedBlock::AdapterInfos => Line::from(vec![
+                Span::from(config.keymap.key(Action::Close)).bold(),
+                Span::from(" Discard"),
+            ]),
+            FocusedBlock::DeviceSelector => Line::from(vec![
+                Span::from(config.keymap.key(Action::Up)).bold(),
+                Span::from("  Up"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Down)).bold(),
+                Span::from("  Down"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Submit)).bold(),
+                Span::from(" Select"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Close)).bold(),
+                Span::from(" Discard"),
+            ]),
+            FocusedBlock::Traffic => Line::from(vec![
+                Span::from(config.keymap.key(Action::Close)).bold(),
+                Span::from(" Close"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::PopupHelp)).bold(),
+                Span::from(" Help"),
             ]),
-            FocusedBlock::AdapterInfos => {
-                Line::from(vec![Span::from("󱊷 ").bold(), Span::from(" Discard")])
-            }
             _ => Line::from(""),
         };
 
-        let help_message = help_message.centered().blue();
+        let help_message = Paragraph::new(help_message)
+            .centered()
+            .wrap(Wrap { trim: true })
+            .fg(theme.help);
 
         frame.render_widget(help_message, help_block);
     }
 }
+
+pub fn sorted_devices<'a>(device: &'a Device, other_devices: &'a [Device]) -> Vec<&'a Device> {
+    let mut devices: Vec<&Device> = std::iter::once(device).chain(other_devices).collect();
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices
+}
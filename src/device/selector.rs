@@ -0,0 +1,121 @@
+use iwdrs::modes::Mode;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Borders, Clear, Padding, Row, Table, TableState},
+};
+
+use crate::device::Device;
+use crate::layout::popup_area;
+use crate::theme::Theme;
+
+#[derive(Debug)]
+pub struct DeviceSelector {
+    pub state: TableState,
+}
+
+impl DeviceSelector {
+    pub fn new(selected: usize) -> Self {
+        Self {
+            state: TableState::default().with_selected(selected),
+        }
+    }
+
+    pub fn next(&mut self, len: usize) {
+        let i = match self.state.selected() {
+            Some(i) if i + 1 < len => i + 1,
+            _ => 0,
+        };
+        self.state.select(Some(i));
+    }
+
+    pub fn previous(&mut self, len: usize) {
+        let i = match self.state.selected() {
+            Some(0) | None => len.saturating_sub(1),
+            Some(i) => i - 1,
+        };
+        self.state.select(Some(i));
+    }
+
+    pub fn render(
+        &mut self,
+        frame: &mut Frame,
+        devices: &[&Device],
+        current_device: &str,
+        theme: &Theme,
+    ) {
+        let area = popup_area(frame.area(), 60, devices.len() as u16 + 5);
+
+        let rows: Vec<Row> = devices
+            .iter()
+            .map(|device| {
+                let state = if !device.is_powered {
+                    "-".to_string()
+                } else {
+                    match device.mode {
+                        Mode::Station => device
+                            .station
+                            .as_ref()
+                            .map(|station| station.state.to_string())
+                            .unwrap_or("-".to_string()),
+                        Mode::Ap => match &device.ap {
+                            Some(ap) if ap.has_started => {
+                                ap.name.clone().unwrap_or("Started".to_string())
+                            }
+                            _ => "Stopped".to_string(),
+                        },
+                    }
+                };
+
+                let name = if device.name == current_device {
+                    format!("{} (current)", device.name)
+                } else {
+                    device.name.clone()
+                };
+
+                Row::new(vec![
+                    Line::from(name).centered(),
+                    Line::from(device.mode.to_string()).centered(),
+                    Line::from(if device.is_powered { "On" } else { "Off" }).centered(),
+                    Line::from(state).centered(),
+                ])
+            })
+            .collect();
+
+        let widths = [
+            Constraint::Length(18),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(16),
+        ];
+
+        let table = Table::new(rows, widths)
+            .header(
+                Row::new(vec![
+                    Line::from("Name").fg(theme.header).centered(),
+                    Line::from("Mode").fg(theme.header).centered(),
+                    Line::from("Powered").fg(theme.header).centered(),
+                    Line::from("State").fg(theme.header).centered(),
+                ])
+                .style(Style::new().bold())
+                .bottom_margin(1),
+            )
+            .block(
+                Block::default()
+                    .title(" Devices ")
+                    .title_style(Style::default().bold())
+                    .borders(Borders::ALL)
+                    .border_style(theme.border(true))
+                    .border_type(BorderType::Thick)
+                    .padding(Padding::horizontal(1)),
+            )
+            .column_spacing(1)
+            .flex(Flex::SpaceAround)
+            .row_highlight_style(theme.highlight());
+
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(table, area, &mut self.state);
+    }
+}
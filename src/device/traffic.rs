@@ -0,0 +1,246 @@
+use std::{collections::VecDeque, time::Instant};
+
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Style, Stylize},
+    symbols::Marker,
+    text::{Line, Span},
+    widgets::{
+        Axis, Block, BorderType, Borders, Cell, Chart, Clear, Dataset, GraphType, Padding, Row,
+        Table,
+    },
+};
+
+use crate::{backend::InterfaceStatistics, layout::popup_area, theme::Theme};
+
+// One sample is taken every tick, i.e. every second, so this is five minutes of history
+const HISTORY_LENGTH: usize = 300;
+
+/// The traffic between two samples, per second.
+#[derive(Debug, Clone, Copy, Default)]
+struct Rate {
+    rx_bytes: f64,
+    tx_bytes: f64,
+    rx_packets: f64,
+    tx_packets: f64,
+}
+
+/// The traffic of the interface since impala started, to tell an idle link from a slow one.
+#[derive(Debug, Clone, Default)]
+pub struct Traffic {
+    /// The counters of the first sample, the session totals start from them
+    first: Option<InterfaceStatistics>,
+    last: Option<(Instant, InterfaceStatistics)>,
+    rates: VecDeque<Rate>,
+    /// Why the counters can not be read, e.g. outside of Linux
+    pub error: Option<String>,
+}
+
+fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        0..1_000 => format!("{} B", bytes),
+        1_000..1_000_000 => format!("{:.1} kB", bytes as f64 / 1e3),
+        1_000_000..1_000_000_000 => format!("{:.1} MB", bytes as f64 / 1e6),
+        _ => format!("{:.2} GB", bytes as f64 / 1e9),
+    }
+}
+
+fn format_bitrate(bytes_per_second: f64) -> String {
+    let bits = bytes_per_second * 8.;
+    if bits < 1e6 {
+        format!("{:.1} kbit/s", bits / 1e3)
+    } else {
+        format!("{:.1} Mbit/s", bits / 1e6)
+    }
+}
+
+impl Traffic {
+    pub fn push(&mut self, statistics: InterfaceStatistics) {
+        let now = Instant::now();
+
+        if let Some((at, last)) = self.last {
+            // The counters start over when the interface is brought down and up
+            if statistics.rx_bytes < last.rx_bytes || statistics.tx_bytes < last.tx_bytes {
+                self.first = Some(statistics);
+            } else {
+                let seconds = now.duration_since(at).as_secs_f64().max(f64::EPSILON);
+                let per_second =
+                    |current: u64, last: u64| current.saturating_sub(last) as f64 / seconds;
+
+                if self.rates.len() == HISTORY_LENGTH {
+                    self.rates.pop_front();
+                }
+                self.rates.push_back(Rate {
+                    rx_bytes: per_second(statistics.rx_bytes, last.rx_bytes),
+                    tx_bytes: per_second(statistics.tx_bytes, last.tx_bytes),
+                    rx_packets: per_second(statistics.rx_packets, last.rx_packets),
+                    tx_packets: per_second(statistics.tx_packets, last.tx_packets),
+                });
+            }
+        }
+
+        self.first.get_or_insert(statistics);
+        self.last = Some((now, statistics));
+        self.error = None;
+    }
+
+    pub fn render(&self, frame: &mut Frame, device_name: &str, theme: &Theme) {
+        // The borders and the padding take two lines on each side
+        let area = popup_area(frame.area(), 90, 8 + 1 + 14 + 4);
+
+        let block = Block::default()
+            .title(format!(" Traffic of {} ", device_name))
+            .title_style(Style::default().bold())
+            .title_alignment(Alignment::Center)
+            .padding(Padding::uniform(1))
+            .borders(Borders::ALL)
+            .border_style(theme.border(true))
+            .border_type(BorderType::Thick);
+
+        let [counters_area, _, chart_area] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(8),
+                Constraint::Length(1),
+                Constraint::Fill(1),
+            ])
+            .areas(block.inner(area));
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let (Some(first), Some((_, last))) = (self.first, self.last) else {
+            frame.render_widget(
+                Line::from(
+                    self.error
+                        .clone()
+                        .unwrap_or("Reading the counters of the interface".to_string()),
+                )
+                .centered()
+                .fg(theme.dimmed),
+                counters_area,
+            );
+            return;
+        };
+
+        let rate = self.rates.back().copied().unwrap_or_default();
+        let key =
+            |key: &'static str| Cell::from(key).style(Style::default().bold().fg(theme.header));
+        // The errors and the drops of the session, and since the interface is up
+        let session_and_total = |current: u64, first: u64| {
+            Cell::from(format!(
+                "{} ({} in total)",
+                current.saturating_sub(first),
+                current
+            ))
+        };
+
+        let counters = Table::new(
+            vec![
+                Row::new(vec![
+                    key("Rate"),
+                    Cell::from(format_bitrate(rate.rx_bytes)),
+                    Cell::from(format_bitrate(rate.tx_bytes)),
+                ]),
+                Row::new(vec![
+                    key("Packets/s"),
+                    Cell::from(format!("{:.0}", rate.rx_packets)),
+                    Cell::from(format!("{:.0}", rate.tx_packets)),
+                ]),
+                Row::new(vec![
+                    key("Session"),
+                    Cell::from(format_bytes(last.rx_bytes.saturating_sub(first.rx_bytes))),
+                    Cell::from(format_bytes(last.tx_bytes.saturating_sub(first.tx_bytes))),
+                ]),
+                Row::new(vec![
+                    key("Packets"),
+                    Cell::from((last.rx_packets.saturating_sub(first.rx_packets)).to_string()),
+                    Cell::from((last.tx_packets.saturating_sub(first.tx_packets)).to_string()),
+                ]),
+                Row::new(vec![
+                    key("Errors"),
+                    session_and_total(last.rx_errors, first.rx_errors),
+                    session_and_total(last.tx_errors, first.tx_errors),
+                ]),
+                Row::new(vec![
+                    key("Drops"),
+                    session_and_total(last.rx_dropped, first.rx_dropped),
+                    session_and_total(last.tx_dropped, first.tx_dropped),
+                ]),
+            ],
+            [
+                Constraint::Length(12),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(
+            Row::new(vec!["", "Receive", "Transmit"])
+                .style(Style::default().bold().fg(theme.header))
+                .bottom_margin(1),
+        )
+        .column_spacing(2)
+        .style(Style::default().fg(theme.text));
+        frame.render_widget(counters, counters_area);
+
+        // In Mbit/s, the latest sample at 0 and the older ones on the left
+        let points = |bytes: fn(&Rate) -> f64| -> Vec<(f64, f64)> {
+            let count = self.rates.len();
+            self.rates
+                .iter()
+                .enumerate()
+                .map(|(index, rate)| (index as f64 - (count as f64 - 1.), bytes(rate) * 8. / 1e6))
+                .collect()
+        };
+        let rx_points = points(|rate| rate.rx_bytes);
+        let tx_points = points(|rate| rate.tx_bytes);
+
+        let max = rx_points
+            .iter()
+            .chain(&tx_points)
+            .map(|(_, rate)| *rate)
+            .fold(1., f64::max);
+        let window = self.rates.len().max(2) as f64 - 1.;
+
+        let chart = Chart::new(vec![
+            Dataset::default()
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(theme.info))
+                .data(&rx_points),
+            Dataset::default()
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(theme.header))
+                .data(&tx_points),
+        ])
+        .block(
+            Block::default()
+                // The title tells the two lines apart, a legend would hide them
+                .title(Line::from(vec![
+                    Span::from(" Throughput  "),
+                    Span::from("Receive").fg(theme.info),
+                    Span::from("  "),
+                    Span::from("Transmit").fg(theme.header),
+                    Span::from(" "),
+                ]))
+                .borders(Borders::ALL)
+                .border_style(theme.border(false)),
+        )
+        .x_axis(
+            Axis::default()
+                .bounds([-window, 0.])
+                .labels([format!("-{}s", window), "now".to_string()])
+                .style(Style::default().fg(theme.dimmed)),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds([0., max])
+                .labels(["0".to_string(), format!("{:.1} Mbit/s", max)])
+                .style(Style::default().fg(theme.dimmed)),
+        )
+        .style(Style::default().fg(theme.text));
+        frame.render_widget(chart, chart_area);
+    }
+}
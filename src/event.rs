@@ -3,19 +3,21 @@ use std::time::Duration;
 
 use crossterm::event::{Event as CrosstermEvent, KeyEvent, MouseEvent};
 use futures::{FutureExt, StreamExt};
-use iwdrs::modes::Mode;
 use tokio::sync::mpsc;
 
-use crate::notification::Notification;
+use crate::{backend::SignalLevel, mode::ViewMode, monitor::Changes, notification::Notification};
 
 #[derive(Clone, Debug)]
 pub enum Event {
     Tick,
+    Refresh(Changes),
+    /// The iwd signals are not received anymore
+    MonitorStopped,
     Key(KeyEvent),
     Mouse(MouseEvent),
     Resize(u16, u16),
     Notification(Notification),
-    Reset(Mode),
+    Reset(ViewMode),
     Auth(String),
     EapNeworkConfigured(String),
     ConfigureNewEapNetwork(String),
@@ -24,6 +26,10 @@ pub enum Event {
     AuthReqUsernameAndPassword(String),
     UsernameAndPasswordSubmit,
     ConnectToHiddenNetwork(String),
+    /// The WPS connection is over, whether it succeeded or not
+    WpsEnded,
+    /// The signal of the connection of a device moved to another level
+    SignalLevel(String, SignalLevel),
 }
 
 #[allow(dead_code)]
@@ -58,6 +64,9 @@ impl EventHandler {
                         if key.kind == crossterm::event::KeyEventKind::Press => {
                           sender_cloned.send(Event::Key(key)).unwrap();
                         },
+                      CrosstermEvent::Mouse(mouse) => {
+                        sender_cloned.send(Event::Mouse(mouse)).unwrap();
+                      },
                       CrosstermEvent::Resize(x, y) => {
                         sender_cloned.send(Event::Resize(x, y)).unwrap();
                       },
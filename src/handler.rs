@@ -1,18 +1,26 @@
 use anyhow::Result;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use crate::app::{App, FocusedBlock};
+use crate::backend::DppRole;
 use crate::config::Config;
 use crate::device::Device;
 use crate::event::Event;
+use crate::help::Help;
+use crate::keymap::{Action, Context, KeyBinding, KeyMatch};
+use crate::layout::table_row_at;
+use crate::mode::ViewMode;
 use crate::mode::ap::APFocusedSection;
+use crate::mode::station::filter::{next_row, previous_row};
 use crate::mode::station::hidden_network::ConnectHiddenNetwork;
 use crate::mode::station::share::Share;
+use crate::mode::station::wps::Wps;
 use crate::notification::{self, Notification};
 
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use iwdrs::modes::Mode;
+use crossterm::event::{KeyEvent, MouseButton, MouseEvent, MouseEventKind};
 use iwdrs::network::NetworkType;
+use ratatui::layout::Position;
 use tokio::sync::mpsc::UnboundedSender;
 use tui_input::backend::crossterm::EventHandler;
 
@@ -41,11 +49,10 @@ pub async fn toggle_connect(app: &mut App, sender: UnboundedSender<Event>) -> Re
                             return Ok(());
                         }
                         tokio::spawn({
-                            let iwd_station =
-                                station.session.stations().await.unwrap().pop().unwrap();
+                            let station = station.clone();
                             let ssid = net.address.clone();
                             async move {
-                                if let Err(e) = iwd_station.connect_hidden_network(ssid).await {
+                                if let Err(e) = station.connect_hidden_network(ssid).await {
                                     let _ = Notification::send(
                                         e.to_string(),
                                         notification::NotificationLevel::Error,
@@ -60,7 +67,7 @@ pub async fn toggle_connect(app: &mut App, sender: UnboundedSender<Event>) -> Re
             FocusedBlock::KnownNetworks => match &station.connected_network {
                 Some(connected_net) => {
                     if let Some(selected_net_index) = station.known_networks_state.selected() {
-                        if selected_net_index > station.known_networks.len() - 1 {
+                        if selected_net_index >= station.known_networks.len() {
                             // Can not connect to unavailble network
                             return Ok(());
                         }
@@ -87,7 +94,7 @@ pub async fn toggle_connect(app: &mut App, sender: UnboundedSender<Event>) -> Re
                 }
                 None => {
                     if let Some(selected_net_index) = station.known_networks_state.selected() {
-                        if selected_net_index > station.known_networks.len() - 1 {
+                        if selected_net_index >= station.known_networks.len() {
                             // Can not connect to unavailble network
                             return Ok(());
                         }
@@ -151,584 +158,1019 @@ async fn toggle_device_power(sender: UnboundedSender<Event>, device: &Device) ->
     Ok(())
 }
 
+/// The blocks with text inputs: the keys that are not bound to a popup action are typed in.
+fn is_text_input(focused_block: FocusedBlock) -> bool {
+    matches!(
+        focused_block,
+        FocusedBlock::PskAuthKey
+            | FocusedBlock::RequestKeyPasshphrase
+            | FocusedBlock::RequestPassword
+            | FocusedBlock::RequestUsernameAndPassword
+            | FocusedBlock::WpaEntrepriseAuth
+            | FocusedBlock::ConnectHiddenNetwork
+            | FocusedBlock::AccessPointInput
+    )
+}
+
+/// The keymap contexts in which the keys are looked up, by order of precedence.
+fn contexts(app: &App) -> &'static [Context] {
+    if app.reset.enable {
+        return &[Context::Popup, Context::Navigation, Context::Global];
+    }
+
+    match app.focused_block {
+        FocusedBlock::Device if !app.device.is_powered => &[Context::Device, Context::Global],
+        _ if !app.device.is_powered => &[Context::Popup],
+        FocusedBlock::Device => &[Context::Device, Context::Global],
+        FocusedBlock::KnownNetworks => &[
+            Context::KnownNetworks,
+            Context::Search,
+            Context::Navigation,
+            Context::Global,
+        ],
+        FocusedBlock::NewNetworks => &[
+            Context::NewNetworks,
+            Context::Search,
+            Context::Navigation,
+            Context::Global,
+        ],
+        FocusedBlock::AccessPoint => &[Context::AccessPoint, Context::Navigation, Context::Global],
+        FocusedBlock::AccessPointConnectedDevices => &[Context::Navigation, Context::Global],
+        FocusedBlock::P2pPeers => &[Context::P2pPeers, Context::Navigation, Context::Global],
+        FocusedBlock::DeviceSelector | FocusedBlock::NetworkDetails | FocusedBlock::Wps => {
+            &[Context::Popup, Context::Navigation]
+        }
+        _ => &[Context::Popup],
+    }
+}
+
 pub async fn handle_key_events(
     key_event: KeyEvent,
     app: &mut App,
     sender: UnboundedSender<Event>,
     config: Arc<Config>,
 ) -> Result<()> {
-    if app.reset.enable {
-        match key_event.code {
-            KeyCode::Char('q') => {
-                app.quit();
+    let key = KeyBinding::from(key_event);
+
+    if let Some(help) = &mut app.help {
+        // Up and down scroll the help, any other key closes it
+        match config.keymap.lookup(&[Context::Navigation], &[key]) {
+            KeyMatch::Action(Action::Down) => help.scroll_down(),
+            KeyMatch::Action(Action::Up) => help.scroll_up(),
+            _ => app.help = None,
+        }
+        return Ok(());
+    }
+
+    if !app.reset.enable
+        && app.device.is_powered
+        && matches!(
+            app.focused_block,
+            FocusedBlock::KnownNetworks | FocusedBlock::NewNetworks
+        )
+        && app
+            .device
+            .station
+            .as_ref()
+            .is_some_and(|station| station.filter.is_editing)
+    {
+        app.pending_keys.clear();
+        let action = match config.keymap.lookup(&[Context::Popup], &[key]) {
+            KeyMatch::Action(action) => Some(action),
+            _ => None,
+        };
+        handle_filter_input(action, key_event, app);
+        return Ok(());
+    }
+
+    if !app.reset.enable && app.device.is_powered && is_text_input(app.focused_block) {
+        app.pending_keys.clear();
+        let action = match config.keymap.lookup(&[Context::Popup], &[key]) {
+            KeyMatch::Action(action) => Some(action),
+            _ => None,
+        };
+        if action == Some(Action::PopupHelp) {
+            app.help = Some(Help::default());
+            return Ok(());
+        }
+        return handle_text_input(action, key_event, app, sender).await;
+    }
+
+    let contexts = contexts(app);
+
+    app.pending_keys.push(key);
+    let mut key_match = config.keymap.lookup(contexts, &app.pending_keys);
+
+    // The key does not continue the pending sequence, it may start a new one
+    if key_match == KeyMatch::None && app.pending_keys.len() > 1 {
+        app.pending_keys = vec![key];
+        key_match = config.keymap.lookup(contexts, &app.pending_keys);
+    }
+
+    match key_match {
+        KeyMatch::Action(action) => {
+            app.pending_keys.clear();
+            handle_action(action, app, sender).await
+        }
+        KeyMatch::Pending => Ok(()),
+        KeyMatch::None => {
+            app.pending_keys.clear();
+            Ok(())
+        }
+    }
+}
+
+/// Two clicks on the same cell within this delay make a double click.
+const DOUBLE_CLICK_DELAY: Duration = Duration::from_millis(400);
+
+/// The blocks of the main view, the other ones are popups drawn over them.
+fn is_main_block(focused_block: FocusedBlock) -> bool {
+    matches!(
+        focused_block,
+        FocusedBlock::Device
+            | FocusedBlock::KnownNetworks
+            | FocusedBlock::NewNetworks
+            | FocusedBlock::AccessPoint
+            | FocusedBlock::AccessPointConnectedDevices
+            | FocusedBlock::P2pPeers
+    )
+}
+
+/// The block of the main view under the mouse.
+fn block_at(app: &App, position: Position) -> Option<FocusedBlock> {
+    if let Some(station) = &app.device.station
+        && app.device.view_mode() == ViewMode::Station
+    {
+        return [
+            (station.device_area, FocusedBlock::Device),
+            (station.known_networks_area, FocusedBlock::KnownNetworks),
+            (station.new_networks_area, FocusedBlock::NewNetworks),
+        ]
+        .into_iter()
+        .find(|(area, _)| area.contains(position))
+        .map(|(_, block)| block);
+    }
+
+    if let Some(ap) = &app.device.ap
+        && app.device.view_mode() == ViewMode::Ap
+    {
+        return [
+            (ap.device_area, FocusedBlock::Device),
+            (ap.access_point_area, FocusedBlock::AccessPoint),
+            (
+                ap.connected_devices_area,
+                FocusedBlock::AccessPointConnectedDevices,
+            ),
+        ]
+        .into_iter()
+        .find(|(area, _)| area.contains(position))
+        .map(|(_, block)| block);
+    }
+
+    if let Some(p2p) = &app.device.p2p
+        && app.device.view_mode() == ViewMode::P2p
+    {
+        return [
+            (p2p.device_area, FocusedBlock::Device),
+            (p2p.peers_area, FocusedBlock::P2pPeers),
+        ]
+        .into_iter()
+        .find(|(area, _)| area.contains(position))
+        .map(|(_, block)| block);
+    }
+
+    None
+}
+
+pub async fn handle_mouse_events(
+    mouse_event: MouseEvent,
+    app: &mut App,
+    sender: UnboundedSender<Event>,
+) -> Result<()> {
+    // The wheel scrolls the help, a click closes it
+    if let Some(help) = &mut app.help {
+        match mouse_event.kind {
+            MouseEventKind::ScrollDown => help.scroll_down(),
+            MouseEventKind::ScrollUp => help.scroll_up(),
+            MouseEventKind::Down(_) => app.help = None,
+            _ => {}
+        }
+        return Ok(());
+    }
+
+    // The reset popup and the powered off device are only driven by the keyboard
+    if app.reset.enable || !app.device.is_powered {
+        return Ok(());
+    }
+
+    let position = Position::new(mouse_event.column, mouse_event.row);
+
+    match mouse_event.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            let is_double_click = app.last_click.is_some_and(|(instant, last_position)| {
+                last_position == position && instant.elapsed() < DOUBLE_CLICK_DELAY
+            });
+            app.last_click = if is_double_click {
+                None
+            } else {
+                Some((Instant::now(), position))
+            };
+            handle_click(position, is_double_click, app, sender).await
+        }
+        MouseEventKind::ScrollDown => handle_scroll(Action::Down, position, app, sender).await,
+        MouseEventKind::ScrollUp => handle_scroll(Action::Up, position, app, sender).await,
+        _ => Ok(()),
+    }
+}
+
+async fn handle_click(
+    position: Position,
+    is_double_click: bool,
+    app: &mut App,
+    sender: UnboundedSender<Event>,
+) -> Result<()> {
+    match app.focused_block {
+        FocusedBlock::PskAuthKey => app.auth.psk.click(position),
+        FocusedBlock::WpaEntrepriseAuth => {
+            if let Some(eap) = &mut app.auth.eap {
+                eap.click(position, sender);
             }
-            KeyCode::Esc if app.config.esc_quit => {
-                app.quit();
+        }
+        FocusedBlock::ConnectHiddenNetwork => {
+            if let Some(station) = &mut app.device.station
+                && let Some(conn) = &mut station.connct_hidden_network
+            {
+                conn.click(position);
             }
-            KeyCode::Char('c' | 'C') if key_event.modifiers == KeyModifiers::CONTROL => {
-                app.quit();
+        }
+        focused_block if is_main_block(focused_block) => {
+            let Some(block) = block_at(app, position) else {
+                return Ok(());
+            };
+            app.focused_block = block;
+
+            if block == FocusedBlock::P2pPeers
+                && let Some(p2p) = &mut app.device.p2p
+            {
+                if let Some(row) = table_row_at(p2p.peers_area, p2p.peers_state.offset(), position)
+                    && row < p2p.peers.len()
+                {
+                    p2p.peers_state.select(Some(row));
+                    if is_double_click {
+                        handle_action(Action::ConnectPeer, app, sender).await?;
+                    }
+                }
+                return Ok(());
             }
 
-            KeyCode::Char('j') | KeyCode::Down if app.reset.selected_mode == Mode::Station => {
-                app.reset.selected_mode = Mode::Ap;
-            }
+            let Some(station) = &mut app.device.station else {
+                return Ok(());
+            };
+
+            // Same limits as the keyboard navigation
+            let row = match block {
+                FocusedBlock::KnownNetworks if !station.known_networks.is_empty() => {
+                    let rows = station.known_networks_rows();
+                    table_row_at(
+                        station.known_networks_area,
+                        station.known_networks_state.offset(),
+                        position,
+                    )
+                    .and_then(|row| rows.get(row).copied())
+                    .map(|index| (&mut station.known_networks_state, index))
+                }
+                FocusedBlock::NewNetworks if !station.new_networks.is_empty() => {
+                    let rows = station.new_networks_rows();
+                    table_row_at(
+                        station.new_networks_area,
+                        station.new_networks_state.offset(),
+                        position,
+                    )
+                    .and_then(|row| rows.get(row).copied())
+                    .map(|index| (&mut station.new_networks_state, index))
+                }
+                _ => None,
+            };
 
-            KeyCode::Char('k') | KeyCode::Up if app.reset.selected_mode == Mode::Ap => {
-                app.reset.selected_mode = Mode::Station;
+            if let Some((state, index)) = row {
+                state.select(Some(index));
+                if is_double_click {
+                    toggle_connect(app, sender).await?;
+                }
             }
+        }
+        _ => {}
+    }
 
-            KeyCode::Enter => {
-                sender.send(Event::Reset(app.reset.selected_mode))?;
-            }
+    Ok(())
+}
 
-            _ => {}
+/// The wheel moves the selection of the table under the mouse.
+async fn handle_scroll(
+    action: Action,
+    position: Position,
+    app: &mut App,
+    sender: UnboundedSender<Event>,
+) -> Result<()> {
+    if !is_main_block(app.focused_block) {
+        return Ok(());
+    }
+
+    if let Some(
+        block @ (FocusedBlock::KnownNetworks | FocusedBlock::NewNetworks | FocusedBlock::P2pPeers),
+    ) = block_at(app, position)
+    {
+        app.focused_block = block;
+        handle_action(action, app, sender).await?;
+    }
+
+    Ok(())
+}
+
+/// Keys typed in the filter of the network tables, the matches are selected as the query changes.
+fn handle_filter_input(action: Option<Action>, key_event: KeyEvent, app: &mut App) {
+    let Some(station) = &mut app.device.station else {
+        return;
+    };
+
+    match action {
+        Some(Action::PopupHelp) => app.help = Some(Help::default()),
+        Some(Action::Submit) => station.filter.is_editing = false,
+        Some(Action::Close) => {
+            station.filter.input.reset();
+            station.filter.is_editing = false;
+        }
+        _ => {
+            station
+                .filter
+                .input
+                .handle_event(&crossterm::event::Event::Key(key_event));
+        }
+    }
+
+    station.apply_filter();
+}
+
+async fn handle_text_input(
+    action: Option<Action>,
+    key_event: KeyEvent,
+    app: &mut App,
+    sender: UnboundedSender<Event>,
+) -> Result<()> {
+    let input_event = crossterm::event::Event::Key(key_event);
+
+    if app.focused_block == FocusedBlock::AccessPointInput {
+        if let Some(ap) = &mut app.device.ap {
+            match action {
+                Some(Action::Submit) => {
+                    ap.start(sender.clone()).await?;
+                    app.focused_block = FocusedBlock::AccessPoint;
+                }
+                Some(Action::Close) => {
+                    ap.ap_start
+                        .store(false, std::sync::atomic::Ordering::Relaxed);
+                    app.focused_block = FocusedBlock::AccessPoint;
+                }
+                Some(Action::NextField) => match ap.focused_section {
+                    APFocusedSection::SSID => {
+                        ap.focused_section = APFocusedSection::PSK;
+                    }
+                    APFocusedSection::PSK => {
+                        ap.focused_section = APFocusedSection::SSID;
+                    }
+                },
+                _ => match ap.focused_section {
+                    APFocusedSection::SSID => {
+                        ap.ssid.handle_event(&input_event);
+                    }
+                    APFocusedSection::PSK => {
+                        ap.psk.handle_event(&input_event);
+                    }
+                },
+            }
         }
         return Ok(());
     }
 
-    if !app.device.is_powered {
-        match app.focused_block {
-            FocusedBlock::AdapterInfos if key_event.code == KeyCode::Esc => {
-                app.focused_block = FocusedBlock::Device;
+    let Some(station) = &mut app.device.station else {
+        return Ok(());
+    };
+
+    match app.focused_block {
+        FocusedBlock::PskAuthKey => match action {
+            Some(Action::Submit) => {
+                app.auth.psk.submit(&app.agent).await?;
+                app.focused_block = FocusedBlock::NewNetworks;
+            }
+            Some(Action::Close) => {
+                app.auth.psk.cancel(&app.agent).await?;
+                app.focused_block = FocusedBlock::NewNetworks;
+            }
+            Some(Action::NextField) => {
+                app.auth.psk.show_password = !app.auth.psk.show_password;
+            }
+            _ => {
+                app.auth.psk.passphrase.handle_event(&input_event);
+            }
+        },
+
+        FocusedBlock::RequestKeyPasshphrase => {
+            if let Some(req) = &mut app.auth.request_key_passphrase {
+                match action {
+                    Some(Action::Submit) => {
+                        req.submit(&app.agent).await?;
+                        app.focused_block = FocusedBlock::KnownNetworks;
+                    }
+                    Some(Action::Close) => {
+                        req.cancel(&app.agent).await?;
+                        app.auth.request_key_passphrase = None;
+                        app.focused_block = FocusedBlock::KnownNetworks;
+                    }
+                    Some(Action::NextField) => {
+                        req.show_password = !req.show_password;
+                    }
+                    _ => {
+                        req.passphrase.handle_event(&input_event);
+                    }
+                }
             }
+        }
 
-            FocusedBlock::Device => match key_event.code {
-                KeyCode::Char('q') => {
-                    app.quit();
+        FocusedBlock::RequestPassword => {
+            if let Some(req) = &mut app.auth.request_password {
+                match action {
+                    Some(Action::Submit) => {
+                        req.submit(&app.agent).await?;
+                        app.focused_block = FocusedBlock::KnownNetworks;
+                    }
+                    Some(Action::Close) => {
+                        req.cancel(&app.agent).await?;
+                        app.auth.request_password = None;
+                        app.focused_block = FocusedBlock::KnownNetworks;
+                    }
+                    Some(Action::NextField) => {
+                        req.show_password = !req.show_password;
+                    }
+                    _ => {
+                        req.password.handle_event(&input_event);
+                    }
                 }
-                KeyCode::Esc if app.config.esc_quit => {
-                    app.quit();
+            }
+        }
+
+        FocusedBlock::RequestUsernameAndPassword => {
+            if let Some(req) = &mut app.auth.request_username_and_password {
+                match action {
+                    Some(Action::Submit) => {
+                        req.submit(&app.agent).await?;
+                        app.focused_block = FocusedBlock::KnownNetworks;
+                    }
+                    Some(Action::Close) => {
+                        req.cancel(&app.agent).await?;
+                        app.auth.request_username_and_password = None;
+                        app.focused_block = FocusedBlock::KnownNetworks;
+                    }
+                    _ => {
+                        req.handle_key_events(key_event, sender).await?;
+                    }
                 }
+            }
+        }
 
-                KeyCode::Char('c' | 'C') if key_event.modifiers == KeyModifiers::CONTROL => {
-                    app.quit();
+        // The forms handle their own keys to move between the fields
+        FocusedBlock::WpaEntrepriseAuth => match action {
+            Some(Action::Close) => {
+                app.focused_block = FocusedBlock::NewNetworks;
+                app.auth.eap = None;
+            }
+            _ => {
+                if let Some(eap) = &mut app.auth.eap {
+                    eap.handle_key_events(key_event, sender);
                 }
+            }
+        },
 
-                KeyCode::Char(c) if c == config.device.infos => {
-                    app.focused_block = FocusedBlock::AdapterInfos;
+        FocusedBlock::ConnectHiddenNetwork => match action {
+            Some(Action::Close) => {
+                app.focused_block = FocusedBlock::NewNetworks;
+                station.connct_hidden_network = None;
+            }
+            _ => {
+                if let Some(conn) = &mut station.connct_hidden_network {
+                    conn.handle_key_events(key_event, sender);
                 }
-                KeyCode::Char(c) if c == config.device.toggle_power => {
-                    toggle_device_power(sender, &app.device).await?;
+            }
+        },
+
+        _ => {}
+    }
+
+    Ok(())
+}
+
+async fn handle_action(
+    action: Action,
+    app: &mut App,
+    sender: UnboundedSender<Event>,
+) -> Result<()> {
+    if action == Action::Quit {
+        app.quit();
+        return Ok(());
+    }
+
+    if matches!(action, Action::Help | Action::PopupHelp) {
+        app.help = Some(Help::default());
+        return Ok(());
+    }
+
+    if app.reset.enable {
+        match action {
+            Action::Down => app.reset.select_next(),
+            Action::Up => app.reset.select_previous(),
+            Action::Submit => {
+                let mode = app.reset.selected_mode;
+                if mode == ViewMode::P2p
+                    && !matches!(app.backend.p2p_device(&app.device.name).await, Ok(Some(_)))
+                {
+                    app.reset.enable = false;
+                    Notification::send(
+                        format!(
+                            "The adapter of {} does not support Wi-Fi Direct",
+                            app.device.name
+                        ),
+                        notification::NotificationLevel::Info,
+                        &sender,
+                    )?;
+                    return Ok(());
                 }
-                _ => {}
-            },
+
+                // The new view asks for a discovery again if it needs one
+                if let Some(p2p) = &app.device.p2p {
+                    p2p.stop_discovery().await;
+                }
+                sender.send(Event::Reset(mode))?
+            }
+            Action::Close => app.reset.enable = false,
             _ => {}
         }
-
         return Ok(());
     }
 
-    match app.device.mode {
-        Mode::Station => {
-            if let Some(station) = &mut app.device.station {
-                match app.focused_block {
-                    FocusedBlock::PskAuthKey => match key_event.code {
-                        KeyCode::Enter => {
-                            app.auth.psk.submit(&app.agent).await?;
-                            app.focused_block = FocusedBlock::NewNetworks;
+    match app.focused_block {
+        FocusedBlock::DeviceSelector => {
+            let devices_count = app.other_devices.len() + 1;
+            if let Some(selector) = &mut app.device_selector {
+                match action {
+                    Action::Close => {
+                        app.device_selector = None;
+                        app.focused_block = FocusedBlock::Device;
+                    }
+                    Action::Down => selector.next(devices_count),
+                    Action::Up => selector.previous(devices_count),
+                    Action::Submit => {
+                        if let Some(index) = selector.state.selected()
+                            && let Some(device) = app.devices().get(index)
+                        {
+                            let name = device.name.clone();
+                            app.switch_device(&name).await?;
                         }
+                    }
+                    _ => {}
+                }
+            }
+            return Ok(());
+        }
+        FocusedBlock::AdapterInfos => {
+            if action == Action::Close {
+                app.focused_block = FocusedBlock::Device;
+            }
+            return Ok(());
+        }
+        FocusedBlock::ShareNetwork => {
+            if action == Action::Close {
+                if let Some(station) = &mut app.device.station {
+                    station.share = None;
+                }
+                app.focused_block = FocusedBlock::KnownNetworks;
+            }
+            return Ok(());
+        }
+        FocusedBlock::Dpp => {
+            if action == Action::Close {
+                app.focused_block = match &mut app.device.station {
+                    Some(station) => {
+                        let parent_block = station
+                            .dpp
+                            .as_ref()
+                            .map(|dpp| dpp.parent_block())
+                            .unwrap_or(FocusedBlock::Device);
+                        station.stop_dpp().await;
+                        parent_block
+                    }
+                    None => FocusedBlock::Device,
+                };
+            }
+            return Ok(());
+        }
+        FocusedBlock::Wps => {
+            if let Some(p2p) = &mut app.device.p2p
+                && let Some(wps) = &mut p2p.wps
+            {
+                match action {
+                    Action::Up | Action::Down => wps.toggle_method(),
+                    Action::Submit => p2p.start_wps(sender).await?,
+                    Action::Close => {
+                        p2p.cancel_wps().await;
+                        app.focused_block = FocusedBlock::P2pPeers;
+                    }
+                    _ => {}
+                }
+            } else if let Some(station) = &mut app.device.station
+                && let Some(wps) = &mut station.wps
+            {
+                match action {
+                    Action::Up | Action::Down => wps.toggle_method(),
+                    Action::Submit => station.start_wps(sender).await?,
+                    Action::Close => {
+                        station.cancel_wps().await;
+                        app.focused_block = FocusedBlock::NewNetworks;
+                    }
+                    _ => {}
+                }
+            }
+            return Ok(());
+        }
+        FocusedBlock::LinkQuality | FocusedBlock::Traffic => {
+            if action == Action::Close {
+                app.focused_block = FocusedBlock::Device;
+            }
+            return Ok(());
+        }
+        FocusedBlock::NetworkDetails => {
+            if let Some(station) = &mut app.device.station
+                && let Some(details) = &mut station.details
+            {
+                match action {
+                    Action::Down => details.scroll_down(),
+                    Action::Up => details.scroll_up(),
+                    Action::Submit => station.connect_selected_bss(sender)?,
+                    Action::Close => {
+                        app.focused_block = if details.known_network.is_some() {
+                            FocusedBlock::KnownNetworks
+                        } else {
+                            FocusedBlock::NewNetworks
+                        };
+                        station.details = None;
+                    }
+                    _ => {}
+                }
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
 
-                        KeyCode::Esc => {
-                            app.auth.psk.cancel(&app.agent).await?;
-                            app.focused_block = FocusedBlock::NewNetworks;
-                        }
+    match action {
+        Action::DeviceInfos => {
+            app.focused_block = FocusedBlock::AdapterInfos;
+            return Ok(());
+        }
+        Action::TogglePower => return toggle_device_power(sender, &app.device).await,
+        Action::SelectDevice => {
+            app.open_device_selector();
+            return Ok(());
+        }
+        Action::ShowTraffic => {
+            app.focused_block = FocusedBlock::Traffic;
+            return Ok(());
+        }
+        _ => {}
+    }
 
-                        KeyCode::Tab => {
-                            app.auth.psk.show_password = !app.auth.psk.show_password;
-                        }
+    if !app.device.is_powered {
+        return Ok(());
+    }
 
-                        _ => {
-                            app.auth
-                                .psk
-                                .passphrase
-                                .handle_event(&crossterm::event::Event::Key(key_event));
-                        }
-                    },
-
-                    FocusedBlock::RequestKeyPasshphrase => {
-                        if let Some(req) = &mut app.auth.request_key_passphrase {
-                            match key_event.code {
-                                KeyCode::Enter => {
-                                    req.submit(&app.agent).await?;
-                                    app.focused_block = FocusedBlock::KnownNetworks;
-                                }
+    match app.device.view_mode() {
+        ViewMode::Station => {
+            let Some(station) = &mut app.device.station else {
+                sender.send(Event::Reset(ViewMode::Station))?;
+                return Ok(());
+            };
+
+            match action {
+                Action::SwitchMode => app.reset.enable = true,
+
+                Action::FocusNext => match app.focused_block {
+                    FocusedBlock::Device => app.focused_block = FocusedBlock::KnownNetworks,
+                    FocusedBlock::KnownNetworks => app.focused_block = FocusedBlock::NewNetworks,
+                    FocusedBlock::NewNetworks => app.focused_block = FocusedBlock::Device,
+                    _ => {}
+                },
+                Action::FocusPrevious => match app.focused_block {
+                    FocusedBlock::Device => app.focused_block = FocusedBlock::NewNetworks,
+                    FocusedBlock::NewNetworks => app.focused_block = FocusedBlock::KnownNetworks,
+                    FocusedBlock::KnownNetworks => app.focused_block = FocusedBlock::Device,
+                    _ => {}
+                },
+
+                Action::Scan => station.scan(sender).await?,
+
+                Action::ShowLinkQuality => app.focused_block = FocusedBlock::LinkQuality,
+
+                Action::StartDppEnrollee => {
+                    station.start_dpp(DppRole::Enrollee, sender).await?;
+                    if station.dpp.is_some() {
+                        app.focused_block = FocusedBlock::Dpp;
+                    }
+                }
 
-                                KeyCode::Esc => {
-                                    req.cancel(&app.agent).await?;
-                                    app.auth.request_key_passphrase = None;
-                                    app.focused_block = FocusedBlock::KnownNetworks;
-                                }
+                Action::StartDppConfigurator => {
+                    let is_connected = station
+                        .known_networks_state
+                        .selected()
+                        .and_then(|index| station.known_networks.get(index))
+                        .is_some_and(|(network, _)| network.is_connected);
+                    if !is_connected {
+                        Notification::send(
+                            "Only the connected network can be shared with DPP".to_string(),
+                            notification::NotificationLevel::Info,
+                            &sender,
+                        )?;
+                        return Ok(());
+                    }
 
-                                KeyCode::Tab => {
-                                    req.show_password = !req.show_password;
-                                }
+                    station.start_dpp(DppRole::Configurator, sender).await?;
+                    if station.dpp.is_some() {
+                        app.focused_block = FocusedBlock::Dpp;
+                    }
+                }
 
-                                _ => {
-                                    req.passphrase
-                                        .handle_event(&crossterm::event::Event::Key(key_event));
-                                }
-                            }
-                        }
+                Action::ShareKnownNetwork => {
+                    if unsafe { libc::geteuid() } != 0 {
+                        let _ = Notification::send(
+                            "impala must be run as root to share networks".to_string(),
+                            notification::NotificationLevel::Info,
+                            &sender,
+                        );
+                        return Ok(());
                     }
-                    FocusedBlock::RequestPassword => {
-                        if let Some(req) = &mut app.auth.request_password {
-                            match key_event.code {
-                                KeyCode::Enter => {
-                                    req.submit(&app.agent).await?;
-                                    app.focused_block = FocusedBlock::KnownNetworks;
-                                }
 
-                                KeyCode::Esc => {
-                                    req.cancel(&app.agent).await?;
-                                    app.auth.request_password = None;
-                                    app.focused_block = FocusedBlock::KnownNetworks;
-                                }
+                    if let Some(net_index) = station.known_networks_state.selected() {
+                        let network = if net_index >= station.known_networks.len() {
+                            let index = net_index.saturating_sub(station.known_networks.len());
+                            let network = &station.unavailable_known_networks[index];
+                            (network.name.clone(), network.network_type)
+                        } else {
+                            let (network, _) = &station.known_networks[net_index];
+                            (network.name.clone(), network.network_type)
+                        };
+
+                        if network.1 == NetworkType::Psk
+                            && let Ok(share) = Share::new(network.0)
+                        {
+                            station.share = Some(share);
+                            app.focused_block = FocusedBlock::ShareNetwork;
+                        }
+                    }
+                }
 
-                                KeyCode::Tab => {
-                                    req.show_password = !req.show_password;
-                                }
+                Action::RemoveKnownNetwork => {
+                    if let Some(net_index) = station.known_networks_state.selected() {
+                        if net_index >= station.known_networks.len() {
+                            let index = net_index.saturating_sub(station.known_networks.len());
+                            let network = &station.unavailable_known_networks[index];
+                            network.forget(sender.clone()).await?;
+                        } else {
+                            let (net, _signal) = &station.known_networks[net_index];
 
-                                _ => {
-                                    req.password
-                                        .handle_event(&crossterm::event::Event::Key(key_event));
-                                }
+                            if let Some(known_net) = &net.known_network {
+                                known_net.forget(sender.clone()).await?;
                             }
                         }
                     }
-                    FocusedBlock::RequestUsernameAndPassword => {
-                        if let Some(req) = &mut app.auth.request_username_and_password {
-                            match key_event.code {
-                                KeyCode::Enter => {
-                                    req.submit(&app.agent).await?;
-                                    app.focused_block = FocusedBlock::KnownNetworks;
-                                }
+                }
 
-                                KeyCode::Esc => {
-                                    req.cancel(&app.agent).await?;
-                                    app.auth.request_username_and_password = None;
-                                    app.focused_block = FocusedBlock::KnownNetworks;
-                                }
+                Action::ToggleAutoconnect => {
+                    if let Some(net_index) = station.known_networks_state.selected()
+                        && net_index < station.known_networks.len()
+                    {
+                        let (net, _) = &station.known_networks[net_index];
 
-                                _ => {
-                                    req.handle_key_events(key_event, sender).await?;
-                                }
-                            }
+                        if let Some(known_net) = &net.known_network {
+                            known_net.toggle_autoconnect(sender.clone()).await?;
                         }
                     }
+                }
 
-                    FocusedBlock::WpaEntrepriseAuth => match key_event.code {
-                        KeyCode::Esc => {
-                            app.focused_block = FocusedBlock::NewNetworks;
-                            app.auth.eap = None;
-                        }
+                Action::ShowAllKnownNetworks => {
+                    station.show_unavailable_known_networks =
+                        !station.show_unavailable_known_networks;
+                }
 
-                        _ => {
-                            if let Some(eap) = &mut app.auth.eap {
-                                eap.handle_key_events(key_event, sender);
-                            }
-                        }
-                    },
-                    FocusedBlock::AdapterInfos => {
-                        if key_event.code == KeyCode::Esc {
-                            app.focused_block = FocusedBlock::Device;
-                        }
+                Action::ShowAllNewNetworks => {
+                    station.show_hidden_networks = !station.show_hidden_networks;
+                }
+
+                Action::ConnectWps => {
+                    station.wps = Some(Wps::default());
+                    app.focused_block = FocusedBlock::Wps;
+                }
+
+                Action::ConnectHiddenNetwork => {
+                    station.connct_hidden_network = Some(ConnectHiddenNetwork::new());
+                    app.focused_block = FocusedBlock::ConnectHiddenNetwork;
+                }
+
+                Action::ConnectKnownNetwork | Action::ConnectNewNetwork => {
+                    toggle_connect(app, sender).await?
+                }
+
+                Action::Down => match app.focused_block {
+                    FocusedBlock::KnownNetworks if !station.known_networks.is_empty() => {
+                        let i = next_row(
+                            &station.known_networks_rows(),
+                            station.known_networks_state.selected(),
+                            false,
+                        );
+                        station.known_networks_state.select(i);
                     }
-                    FocusedBlock::ShareNetwork => {
-                        if key_event.code == KeyCode::Esc {
-                            station.share = None;
-                            app.focused_block = FocusedBlock::KnownNetworks;
-                        }
+                    FocusedBlock::NewNetworks if !station.new_networks.is_empty() => {
+                        let i = next_row(
+                            &station.new_networks_rows(),
+                            station.new_networks_state.selected(),
+                            false,
+                        );
+                        station.new_networks_state.select(i);
                     }
-                    FocusedBlock::ConnectHiddenNetwork => match key_event.code {
-                        KeyCode::Esc => {
-                            app.focused_block = FocusedBlock::NewNetworks;
-                            station.connct_hidden_network = None;
-                        }
-
-                        _ => {
-                            if let Some(conn) = &mut station.connct_hidden_network {
-                                conn.handle_key_events(key_event, sender);
-                            }
-                        }
-                    },
-                    _ => {
-                        match key_event.code {
-                            KeyCode::Char('q') => {
-                                app.quit();
-                            }
-                            KeyCode::Esc if app.config.esc_quit => {
-                                app.quit();
-                            }
+                    _ => {}
+                },
+
+                Action::Up => match app.focused_block {
+                    FocusedBlock::KnownNetworks if !station.known_networks.is_empty() => {
+                        let i = previous_row(
+                            &station.known_networks_rows(),
+                            station.known_networks_state.selected(),
+                            false,
+                        );
+                        station.known_networks_state.select(i);
+                    }
+                    FocusedBlock::NewNetworks if !station.new_networks.is_empty() => {
+                        let i = previous_row(
+                            &station.new_networks_rows(),
+                            station.new_networks_state.selected(),
+                            false,
+                        );
+                        station.new_networks_state.select(i);
+                    }
+                    _ => {}
+                },
+
+                Action::ShowKnownNetworkDetails => {
+                    if let Some(index) = station.known_networks_state.selected()
+                        && let Some(details) = station.known_network_details(index).await
+                    {
+                        station.details = Some(details);
+                        app.focused_block = FocusedBlock::NetworkDetails;
+                    }
+                }
 
-                            KeyCode::Char('c' | 'C') => {
-                                if key_event.modifiers == KeyModifiers::CONTROL {
-                                    app.quit();
-                                }
-                            }
+                Action::ShowNewNetworkDetails => {
+                    if let Some(index) = station.new_networks_state.selected()
+                        && let Some(details) = station.new_network_details(index).await
+                    {
+                        station.details = Some(details);
+                        app.focused_block = FocusedBlock::NetworkDetails;
+                    }
+                }
 
-                            // Switch mode
-                            KeyCode::Char(c)
-                                if c == config.switch
-                                    && key_event.modifiers == KeyModifiers::CONTROL =>
-                            {
-                                app.reset.enable = true;
-                            }
+                Action::SortKnownNetworks => station.cycle_known_networks_sort(),
+                Action::SortNewNetworks => station.cycle_new_networks_sort(),
 
-                            KeyCode::Tab => match app.focused_block {
-                                FocusedBlock::Device => {
-                                    app.focused_block = FocusedBlock::KnownNetworks;
-                                }
-                                FocusedBlock::KnownNetworks => {
-                                    app.focused_block = FocusedBlock::NewNetworks;
-                                }
-                                FocusedBlock::NewNetworks => {
-                                    app.focused_block = FocusedBlock::Device;
-                                }
-                                _ => {}
-                            },
-                            KeyCode::BackTab => match app.focused_block {
-                                FocusedBlock::Device => {
-                                    app.focused_block = FocusedBlock::NewNetworks;
-                                }
-                                FocusedBlock::NewNetworks => {
-                                    app.focused_block = FocusedBlock::KnownNetworks;
-                                }
-                                FocusedBlock::KnownNetworks => {
-                                    app.focused_block = FocusedBlock::Device;
-                                }
-                                _ => {}
-                            },
+                Action::Filter => station.filter.is_editing = true,
 
-                            KeyCode::Char(c) if c == config.station.start_scanning => {
-                                station.scan(sender).await?;
-                            }
-                            _ => match app.focused_block {
-                                FocusedBlock::Device => match key_event.code {
-                                    KeyCode::Char(c) if c == config.device.infos => {
-                                        app.focused_block = FocusedBlock::AdapterInfos;
-                                    }
-                                    KeyCode::Char(c) if c == config.device.toggle_power => {
-                                        toggle_device_power(sender, &app.device).await?;
-                                    }
-                                    _ => {}
-                                },
-
-                                FocusedBlock::KnownNetworks => {
-                                    match key_event.code {
-                                        // Share
-                                        KeyCode::Char(c)
-                                            if c == config.station.known_network.share =>
-                                        {
-                                            if unsafe { libc::geteuid() } != 0 {
-                                                let _ = Notification::send(
-                                                    "impala must be run as root to share networks"
-                                                        .to_string(),
-                                                    notification::NotificationLevel::Info,
-                                                    &sender,
-                                                );
-                                                return Ok(());
-                                            }
-
-                                            if let Some(net_index) =
-                                                station.known_networks_state.selected()
-                                            {
-                                                if net_index > station.known_networks.len() - 1 {
-                                                    let index = net_index.saturating_sub(
-                                                        station.known_networks.len(),
-                                                    );
-                                                    let network =
-                                                        &station.unavailable_known_networks[index];
-                                                    if network.network_type == NetworkType::Psk
-                                                        && let Ok(share) =
-                                                            Share::new(network.name.clone())
-                                                    {
-                                                        station.share = Some(share);
-                                                        app.focused_block =
-                                                            FocusedBlock::ShareNetwork;
-                                                    }
-                                                } else {
-                                                    let (network, _) =
-                                                        &station.known_networks[net_index];
-                                                    if network.network_type == NetworkType::Psk
-                                                        && let Ok(share) =
-                                                            Share::new(network.name.clone())
-                                                    {
-                                                        station.share = Some(share);
-                                                        app.focused_block =
-                                                            FocusedBlock::ShareNetwork;
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        // Remove a known network
-                                        KeyCode::Char(c)
-                                            if c == config.station.known_network.remove =>
-                                        {
-                                            if let Some(net_index) =
-                                                station.known_networks_state.selected()
-                                            {
-                                                if net_index > station.known_networks.len() - 1 {
-                                                    let index = net_index.saturating_sub(
-                                                        station.known_networks.len(),
-                                                    );
-                                                    let network =
-                                                        &station.unavailable_known_networks[index];
-                                                    network.forget(sender.clone()).await?;
-                                                } else {
-                                                    let (net, _signal) =
-                                                        &station.known_networks[net_index];
-
-                                                    if let Some(known_net) = &net.known_network {
-                                                        known_net.forget(sender.clone()).await?;
-                                                    }
-                                                }
-                                            }
-                                        }
-
-                                        // Toggle autoconnect
-                                        KeyCode::Char(c)
-                                            if c == config
-                                                .station
-                                                .known_network
-                                                .toggle_autoconnect =>
-                                        {
-                                            if let Some(net_index) =
-                                                station.known_networks_state.selected()
-                                                && net_index < station.known_networks.len()
-                                            {
-                                                let (net, _) = &station.known_networks[net_index];
-
-                                                if let Some(known_net) = &net.known_network {
-                                                    known_net
-                                                        .toggle_autoconnect(sender.clone())
-                                                        .await?;
-                                                }
-                                            }
-                                        }
-
-                                        // Show / Hide unavailable networks
-                                        KeyCode::Char(c)
-                                            if c == config.station.known_network.show_all =>
-                                        {
-                                            station.show_unavailable_known_networks =
-                                                !station.show_unavailable_known_networks;
-                                        }
-
-                                        // Connect/Disconnect
-                                        KeyCode::Enter | KeyCode::Char(' ') => {
-                                            toggle_connect(app, sender).await?
-                                        }
-
-                                        // Scroll down
-                                        KeyCode::Char('j') | KeyCode::Down
-                                            if !station.known_networks.is_empty() =>
-                                        {
-                                            let i = match station.known_networks_state.selected() {
-                                                Some(i) => {
-                                                    let limit = if station
-                                                        .show_unavailable_known_networks
-                                                    {
-                                                        station.known_networks.len()
-                                                            + station
-                                                                .unavailable_known_networks
-                                                                .len()
-                                                            - 1
-                                                    } else {
-                                                        station.known_networks.len() - 1
-                                                    };
-
-                                                    if i < limit { i + 1 } else { i }
-                                                }
-                                                None => 0,
-                                            };
-
-                                            station.known_networks_state.select(Some(i));
-                                        }
-                                        KeyCode::Char('k') | KeyCode::Up
-                                            if !station.known_networks.is_empty() =>
-                                        {
-                                            let i = match station.known_networks_state.selected() {
-                                                Some(i) => i.saturating_sub(1),
-                                                None => 0,
-                                            };
-
-                                            station.known_networks_state.select(Some(i));
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                                FocusedBlock::NewNetworks => match key_event.code {
-                                    // Connect to hidden network
-                                    KeyCode::Char('n') => {
-                                        station.connct_hidden_network =
-                                            Some(ConnectHiddenNetwork::new());
-                                        app.focused_block = FocusedBlock::ConnectHiddenNetwork;
-                                    }
-
-                                    // Show / Hide unavailable networks
-                                    KeyCode::Char(c)
-                                        if c == config.station.new_network.show_all =>
-                                    {
-                                        station.show_hidden_networks =
-                                            !station.show_hidden_networks;
-                                    }
-                                    KeyCode::Enter | KeyCode::Char(' ') => {
-                                        toggle_connect(app, sender).await?
-                                    }
-                                    KeyCode::Char('j') | KeyCode::Down
-                                        if !station.new_networks.is_empty() =>
-                                    {
-                                        let i = match station.new_networks_state.selected() {
-                                            Some(i) => {
-                                                let limit = if station.show_hidden_networks {
-                                                    station.new_networks.len()
-                                                        + station.new_hidden_networks.len()
-                                                        - 1
-                                                } else {
-                                                    station.new_networks.len() - 1
-                                                };
-                                                if i < limit { i + 1 } else { i }
-                                            }
-                                            None => 0,
-                                        };
-
-                                        station.new_networks_state.select(Some(i));
-                                    }
-                                    KeyCode::Char('k') | KeyCode::Up
-                                        if !station.new_networks.is_empty() =>
-                                    {
-                                        let i = match station.new_networks_state.selected() {
-                                            Some(i) => i.saturating_sub(1),
-                                            None => 0,
-                                        };
-
-                                        station.new_networks_state.select(Some(i));
-                                    }
-                                    _ => {}
-                                },
-                                _ => {}
-                            },
+                // Same as down and up, but going around
+                Action::NextMatch | Action::PreviousMatch => {
+                    let select = if action == Action::NextMatch {
+                        next_row
+                    } else {
+                        previous_row
+                    };
+                    match app.focused_block {
+                        FocusedBlock::KnownNetworks if !station.known_networks.is_empty() => {
+                            let i = select(
+                                &station.known_networks_rows(),
+                                station.known_networks_state.selected(),
+                                true,
+                            );
+                            station.known_networks_state.select(i);
+                        }
+                        FocusedBlock::NewNetworks if !station.new_networks.is_empty() => {
+                            let i = select(
+                                &station.new_networks_rows(),
+                                station.new_networks_state.selected(),
+                                true,
+                            );
+                            station.new_networks_state.select(i);
                         }
+                        _ => {}
                     }
                 }
-            } else {
-                sender.send(Event::Reset(Mode::Station))?;
+
+                _ => {}
             }
         }
 
-        Mode::Ap => {
-            if let Some(ap) = &mut app.device.ap {
-                match app.focused_block {
-                    FocusedBlock::AccessPointInput => match key_event.code {
-                        KeyCode::Enter => {
-                            ap.start(sender.clone()).await?;
-                            app.focused_block = FocusedBlock::AccessPoint;
-                        }
+        ViewMode::Ap => {
+            let Some(ap) = &mut app.device.ap else {
+                sender.send(Event::Reset(ViewMode::Ap))?;
+                return Ok(());
+            };
 
-                        KeyCode::Esc => {
-                            ap.ap_start
-                                .store(false, std::sync::atomic::Ordering::Relaxed);
-                            app.focused_block = FocusedBlock::AccessPoint;
-                        }
-                        KeyCode::Tab => match ap.focused_section {
-                            APFocusedSection::SSID => {
-                                ap.focused_section = APFocusedSection::PSK;
-                            }
-                            APFocusedSection::PSK => {
-                                ap.focused_section = APFocusedSection::SSID;
-                            }
-                        },
-                        _ => match ap.focused_section {
-                            APFocusedSection::SSID => {
-                                ap.ssid
-                                    .handle_event(&crossterm::event::Event::Key(key_event));
-                            }
-                            APFocusedSection::PSK => {
-                                ap.psk
-                                    .handle_event(&crossterm::event::Event::Key(key_event));
-                            }
-                        },
-                    },
+            match action {
+                Action::SwitchMode => app.reset.enable = true,
 
-                    FocusedBlock::AdapterInfos => {
-                        if key_event.code == KeyCode::Esc {
+                Action::FocusNext => match app.focused_block {
+                    FocusedBlock::Device => app.focused_block = FocusedBlock::AccessPoint,
+                    FocusedBlock::AccessPoint => {
+                        if ap.connected_devices.is_empty() {
                             app.focused_block = FocusedBlock::Device;
+                        } else {
+                            app.focused_block = FocusedBlock::AccessPointConnectedDevices;
                         }
                     }
-                    _ => {
-                        match key_event.code {
-                            KeyCode::Char('q') => {
-                                app.quit();
-                            }
-                            KeyCode::Esc if app.config.esc_quit => {
-                                app.quit();
-                            }
-
-                            KeyCode::Char('c' | 'C') => {
-                                if key_event.modifiers == KeyModifiers::CONTROL {
-                                    app.quit();
-                                }
-                            }
+                    FocusedBlock::AccessPointConnectedDevices => {
+                        app.focused_block = FocusedBlock::Device;
+                    }
+                    _ => {}
+                },
+                Action::FocusPrevious => match app.focused_block {
+                    FocusedBlock::Device => {
+                        if ap.connected_devices.is_empty() {
+                            app.focused_block = FocusedBlock::AccessPoint;
+                        } else {
+                            app.focused_block = FocusedBlock::AccessPointConnectedDevices;
+                        }
+                    }
+                    FocusedBlock::AccessPoint => app.focused_block = FocusedBlock::Device,
+                    FocusedBlock::AccessPointConnectedDevices => {
+                        app.focused_block = FocusedBlock::AccessPoint;
+                    }
+                    _ => {}
+                },
 
-                            // Switch mode
-                            KeyCode::Char(c)
-                                if c == config.switch
-                                    && key_event.modifiers == KeyModifiers::CONTROL =>
-                            {
-                                app.reset.enable = true;
-                            }
+                Action::StartAccessPoint => {
+                    ap.ap_start
+                        .store(true, std::sync::atomic::Ordering::Relaxed);
+                    app.focused_block = FocusedBlock::AccessPointInput;
+                }
 
-                            KeyCode::Tab => match app.focused_block {
-                                FocusedBlock::Device => {
-                                    app.focused_block = FocusedBlock::AccessPoint;
-                                }
-                                FocusedBlock::AccessPoint => {
-                                    if ap.connected_devices.is_empty() {
-                                        app.focused_block = FocusedBlock::Device;
-                                    } else {
-                                        app.focused_block =
-                                            FocusedBlock::AccessPointConnectedDevices;
-                                    }
-                                }
-                                FocusedBlock::AccessPointConnectedDevices => {
-                                    app.focused_block = FocusedBlock::Device;
-                                }
+                Action::StopAccessPoint => ap.stop(sender.clone()).await?,
 
-                                _ => {}
-                            },
+                _ => {}
+            }
+        }
 
-                            KeyCode::Char(c) if c == config.ap.start => {
-                                if app.focused_block == FocusedBlock::AccessPoint {
-                                    ap.ap_start
-                                        .store(true, std::sync::atomic::Ordering::Relaxed);
-                                    app.focused_block = FocusedBlock::AccessPointInput;
-                                }
-                            }
+        ViewMode::P2p => {
+            let Some(p2p) = &mut app.device.p2p else {
+                sender.send(Event::Reset(ViewMode::P2p))?;
+                return Ok(());
+            };
+
+            match action {
+                Action::SwitchMode => app.reset.enable = true,
+
+                Action::FocusNext | Action::FocusPrevious => match app.focused_block {
+                    FocusedBlock::Device => app.focused_block = FocusedBlock::P2pPeers,
+                    FocusedBlock::P2pPeers => app.focused_block = FocusedBlock::Device,
+                    _ => {}
+                },
+
+                Action::Down if app.focused_block == FocusedBlock::P2pPeers => {
+                    let rows: Vec<usize> = (0..p2p.peers.len()).collect();
+                    let i = next_row(&rows, p2p.peers_state.selected(), false);
+                    p2p.peers_state.select(i);
+                }
 
-                            KeyCode::Char(c) if c == config.ap.stop => {
-                                if app.focused_block == FocusedBlock::AccessPoint {
-                                    ap.stop(sender.clone()).await?;
-                                }
-                            }
+                Action::Up if app.focused_block == FocusedBlock::P2pPeers => {
+                    let rows: Vec<usize> = (0..p2p.peers.len()).collect();
+                    let i = previous_row(&rows, p2p.peers_state.selected(), false);
+                    p2p.peers_state.select(i);
+                }
 
-                            _ => {
-                                if app.focused_block == FocusedBlock::Device {
-                                    match key_event.code {
-                                        KeyCode::Char(c) if c == config.device.infos => {
-                                            app.focused_block = FocusedBlock::AdapterInfos;
-                                        }
-                                        KeyCode::Char(c) if c == config.device.toggle_power => {
-                                            toggle_device_power(sender, &app.device).await?;
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                            }
+                Action::ConnectPeer => {
+                    if let Some(peer) = p2p.selected_peer().cloned() {
+                        if peer.is_connected {
+                            p2p.disconnect(&peer, sender).await?;
+                            p2p.refresh().await?;
+                        } else {
+                            p2p.wps = Some(Wps::with_peer(peer));
+                            app.focused_block = FocusedBlock::Wps;
                         }
                     }
                 }
-            } else {
-                sender.send(Event::Reset(Mode::Ap))?;
+
+                _ => {}
             }
         }
     }
@@ -0,0 +1,373 @@
+use ratatui::{
+    Frame,
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph},
+};
+
+use crate::{
+    app::{App, FocusedBlock},
+    config::Config,
+    keymap::Action,
+    layout::popup_area,
+    mode::ViewMode,
+    theme::Theme,
+};
+
+/// A group of keys with what they do.
+pub struct Section {
+    pub title: &'static str,
+    pub keys: Vec<(String, &'static str)>,
+}
+
+impl Section {
+    fn new(title: &'static str) -> Self {
+        Self {
+            title,
+            keys: Vec::new(),
+        }
+    }
+
+    fn action(mut self, config: &Config, action: Action, description: &'static str) -> Self {
+        self.keys.push((config.keymap.key(action), description));
+        self
+    }
+
+    /// Keys handled by the forms themselves, which are not part of the keymap.
+    fn key(mut self, key: impl Into<String>, description: &'static str) -> Self {
+        self.keys.push((key.into(), description));
+        self
+    }
+}
+
+/// Every key available in the focused block, built from the keymap of the config.
+pub fn sections(app: &App) -> Vec<Section> {
+    let config = app.config.as_ref();
+    let icons = config.icons;
+
+    if app.reset.enable {
+        return vec![
+            Section::new("Switch mode")
+                .action(config, Action::Up, "Select the previous mode")
+                .action(config, Action::Down, "Select the next mode")
+                .action(config, Action::Submit, "Switch to the selected mode")
+                .action(config, Action::Close, "Cancel")
+                .action(config, Action::PopupHelp, "Show this help"),
+        ];
+    }
+
+    let navigation = Section::new("Navigation")
+        .action(config, Action::Up, "Select the previous row")
+        .action(config, Action::Down, "Select the next row");
+
+    let mut general = Section::new("General")
+        .action(config, Action::FocusNext, "Focus the next block")
+        .action(config, Action::FocusPrevious, "Focus the previous block");
+    if app.device.view_mode() == ViewMode::Station {
+        general = general.action(config, Action::Scan, "Scan for networks");
+    }
+    let general = general
+        .action(
+            config,
+            Action::SwitchMode,
+            "Switch between station, access point and Wi-Fi Direct",
+        )
+        .action(config, Action::Help, "Show this help")
+        .action(config, Action::Quit, "Quit");
+
+    let is_filtering = app
+        .device
+        .station
+        .as_ref()
+        .is_some_and(|station| station.filter.is_editing);
+    if is_filtering
+        && matches!(
+            app.focused_block,
+            FocusedBlock::KnownNetworks | FocusedBlock::NewNetworks
+        )
+    {
+        return vec![
+            Section::new("Filter")
+                .action(config, Action::Submit, "Keep the filter")
+                .action(config, Action::Close, "Clear the filter")
+                .action(config, Action::PopupHelp, "Show this help"),
+        ];
+    }
+
+    let search = Section::new("Search")
+        .action(
+            config,
+            Action::Filter,
+            "Filter the networks by name, security or signal",
+        )
+        .action(config, Action::NextMatch, "Select the next match")
+        .action(config, Action::PreviousMatch, "Select the previous match");
+
+    match app.focused_block {
+        FocusedBlock::Device => {
+            let mut device = Section::new("Device")
+                .action(config, Action::DeviceInfos, "Show the adapter infos")
+                .action(config, Action::TogglePower, "Power the device on or off")
+                .action(config, Action::SelectDevice, "Select another device")
+                .action(
+                    config,
+                    Action::ShowTraffic,
+                    "Show the traffic of the interface",
+                );
+            if app.device.view_mode() == ViewMode::Station {
+                device = device
+                    .action(
+                        config,
+                        Action::ShowLinkQuality,
+                        "Show the signal and the bitrates of the connection",
+                    )
+                    .action(
+                        config,
+                        Action::StartDppEnrollee,
+                        "Receive a network with DPP (Wi-Fi Easy Connect)",
+                    );
+            }
+            vec![device, general]
+        }
+        FocusedBlock::KnownNetworks => vec![
+            Section::new("Known networks")
+                .action(config, Action::ConnectKnownNetwork, "Connect or disconnect")
+                .action(config, Action::RemoveKnownNetwork, "Forget the network")
+                .action(
+                    config,
+                    Action::ToggleAutoconnect,
+                    "Enable or disable autoconnect",
+                )
+                .action(
+                    config,
+                    Action::ShowAllKnownNetworks,
+                    "Show or hide the networks out of reach",
+                )
+                .action(
+                    config,
+                    Action::ShareKnownNetwork,
+                    "Share the network with a QR code",
+                )
+                .action(
+                    config,
+                    Action::StartDppConfigurator,
+                    "Share the connected network with DPP, without the passphrase",
+                )
+                .action(
+                    config,
+                    Action::SortKnownNetworks,
+                    "Sort by signal, name, last connection or security",
+                )
+                .action(
+                    config,
+                    Action::ShowKnownNetworkDetails,
+                    "Show the details and the access points",
+                ),
+            search,
+            navigation,
+            general,
+        ],
+        FocusedBlock::NewNetworks => vec![
+            Section::new("New networks")
+                .action(config, Action::ConnectNewNetwork, "Connect")
+                .action(
+                    config,
+                    Action::ConnectHiddenNetwork,
+                    "Connect to a hidden network",
+                )
+                .action(
+                    config,
+                    Action::ConnectWps,
+                    "Connect with WPS, with the push button or a PIN",
+                )
+                .action(
+                    config,
+                    Action::ShowAllNewNetworks,
+                    "Show or hide the hidden networks",
+                )
+                .action(
+                    config,
+                    Action::SortNewNetworks,
+                    "Sort by signal, name, last connection or security",
+                )
+                .action(
+                    config,
+                    Action::ShowNewNetworkDetails,
+                    "Show the details and the access points",
+                ),
+            search,
+            navigation,
+            general,
+        ],
+        FocusedBlock::AccessPoint => vec![
+            Section::new("Access point")
+                .action(config, Action::StartAccessPoint, "Start a new access point")
+                .action(config, Action::StopAccessPoint, "Stop the access point"),
+            general,
+        ],
+        FocusedBlock::AccessPointConnectedDevices => vec![general],
+        FocusedBlock::P2pPeers => vec![
+            Section::new("Peers").action(
+                config,
+                Action::ConnectPeer,
+                "Connect with a push button or a PIN, or disconnect",
+            ),
+            navigation,
+            general,
+        ],
+        FocusedBlock::PskAuthKey => vec![
+            Section::new("Password")
+                .action(config, Action::Submit, "Connect")
+                .action(config, Action::NextField, "Show or hide the password")
+                .action(config, Action::Close, "Cancel")
+                .action(config, Action::PopupHelp, "Show this help"),
+        ],
+        FocusedBlock::RequestKeyPasshphrase | FocusedBlock::RequestPassword => vec![
+            Section::new("Password")
+                .action(config, Action::Submit, "Submit")
+                .action(config, Action::NextField, "Show or hide the password")
+                .action(config, Action::Close, "Cancel")
+                .action(config, Action::PopupHelp, "Show this help"),
+        ],
+        FocusedBlock::RequestUsernameAndPassword => vec![
+            Section::new("Username and password")
+                .action(config, Action::Submit, "Submit")
+                .key("tab", "Next field")
+                .key("shift+tab", "Previous field")
+                .action(config, Action::Close, "Cancel")
+                .action(config, Action::PopupHelp, "Show this help"),
+        ],
+        FocusedBlock::WpaEntrepriseAuth => vec![
+            Section::new("WPA Enterprise")
+                .key("tab", "Next field")
+                .key("shift+tab", "Previous field")
+                .key(
+                    format!("h,l,{},{}", icons.left(), icons.right()),
+                    "Switch the EAP or the phase 2 method",
+                )
+                .key(icons.enter(), "Check the field, or apply on APPLY")
+                .action(config, Action::Close, "Cancel")
+                .action(config, Action::PopupHelp, "Show this help"),
+        ],
+        FocusedBlock::ConnectHiddenNetwork => vec![
+            Section::new("Hidden network")
+                .key(icons.enter(), "Connect")
+                .action(config, Action::Close, "Cancel")
+                .action(config, Action::PopupHelp, "Show this help"),
+        ],
+        FocusedBlock::AccessPointInput => vec![
+            Section::new("New access point")
+                .action(config, Action::Submit, "Start the access point")
+                .action(
+                    config,
+                    Action::NextField,
+                    "Switch between the SSID and the password",
+                )
+                .action(config, Action::Close, "Cancel")
+                .action(config, Action::PopupHelp, "Show this help"),
+        ],
+        FocusedBlock::DeviceSelector => vec![
+            Section::new("Devices")
+                .action(config, Action::Up, "Select the previous device")
+                .action(config, Action::Down, "Select the next device")
+                .action(config, Action::Submit, "Switch to the selected device")
+                .action(config, Action::Close, "Cancel")
+                .action(config, Action::PopupHelp, "Show this help"),
+        ],
+        FocusedBlock::Wps => vec![
+            Section::new("WPS")
+                .action(config, Action::Up, "Select the other method")
+                .action(config, Action::Down, "Select the other method")
+                .action(config, Action::Submit, "Start the connection")
+                .action(config, Action::Close, "Cancel")
+                .action(config, Action::PopupHelp, "Show this help"),
+        ],
+        FocusedBlock::NetworkDetails => vec![
+            Section::new("Network details")
+                .action(config, Action::Up, "Select the previous access point")
+                .action(config, Action::Down, "Select the next access point")
+                .action(
+                    config,
+                    Action::Submit,
+                    "Connect through the access point, or roam to it (iwd -E)",
+                )
+                .action(config, Action::Close, "Close")
+                .action(config, Action::PopupHelp, "Show this help"),
+        ],
+        FocusedBlock::AdapterInfos
+        | FocusedBlock::ShareNetwork
+        | FocusedBlock::Dpp
+        | FocusedBlock::LinkQuality
+        | FocusedBlock::Traffic => {
+            vec![
+                Section::new("Popup")
+                    .action(config, Action::Close, "Close")
+                    .action(config, Action::PopupHelp, "Show this help"),
+            ]
+        }
+    }
+}
+
+/// The overlay listing the keys of the focused block.
+#[derive(Debug, Default)]
+pub struct Help {
+    scroll: u16,
+}
+
+impl Help {
+    pub fn scroll_down(&mut self) {
+        self.scroll = self.scroll.saturating_add(1);
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, sections: &[Section], theme: &Theme) {
+        let key_width = sections
+            .iter()
+            .flat_map(|section| &section.keys)
+            .map(|(key, _)| key.chars().count())
+            .max()
+            .unwrap_or_default();
+
+        let mut lines = Vec::new();
+        for section in sections {
+            if !lines.is_empty() {
+                lines.push(Line::from(""));
+            }
+            lines.push(Line::from(section.title).bold().fg(theme.header));
+            for (key, description) in &section.keys {
+                lines.push(Line::from(vec![
+                    Span::from(format!("{:>width$}", key, width = key_width)).bold(),
+                    Span::from("  "),
+                    Span::from(*description),
+                ]));
+            }
+        }
+
+        // The borders and the padding take two lines on each side
+        let area = popup_area(frame.area(), 70, lines.len() as u16 + 4);
+        let visible_height = area.height.saturating_sub(4);
+        self.scroll = self
+            .scroll
+            .min((lines.len() as u16).saturating_sub(visible_height));
+
+        let help = Paragraph::new(lines)
+            .scroll((self.scroll, 0))
+            .style(Style::default().fg(theme.text))
+            .block(
+                Block::default()
+                    .title(" Help ")
+                    .title_style(Style::default().bold())
+                    .title_bottom(Line::from(" Press a key to close ").centered())
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Thick)
+                    .border_style(theme.border(true))
+                    .padding(Padding::uniform(1)),
+            );
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(help, area);
+    }
+}
@@ -0,0 +1,126 @@
+use serde::Deserialize;
+
+/// The set of glyphs used by the TUI, `nerd` requires a Nerd Font, `unicode` any font with the
+/// box drawing and arrow characters and `ascii` works on a serial console.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Icons {
+    #[default]
+    Nerd,
+    Unicode,
+    Ascii,
+}
+
+impl Icons {
+    /// Icon matching a signal strength percentage.
+    pub fn signal(self, signal: i16) -> &'static str {
+        let level = match signal {
+            n if n >= 75 => 3,
+            n if (50..75).contains(&n) => 2,
+            n if (25..50).contains(&n) => 1,
+            _ => 0,
+        };
+
+        match self {
+            Icons::Nerd => ["󰤟", "󰤢", "󰤥", "󰤨"][level],
+            Icons::Unicode => ["▂   ", "▂▄  ", "▂▄▆ ", "▂▄▆█"][level],
+            Icons::Ascii => ["[#   ]", "[##  ]", "[### ]", "[####]"][level],
+        }
+    }
+
+    pub fn disconnected(self) -> &'static str {
+        match self {
+            Icons::Nerd => "󰤮",
+            Icons::Unicode => "✗",
+            Icons::Ascii => "x",
+        }
+    }
+
+    pub fn access_point(self) -> &'static str {
+        match self {
+            Icons::Nerd => "󰀂",
+            Icons::Unicode => "⇅",
+            Icons::Ascii => "AP",
+        }
+    }
+
+    /// Marks the connected network.
+    pub fn connected(self) -> &'static str {
+        match self {
+            Icons::Nerd => "󰖩 ",
+            Icons::Unicode => "✓",
+            Icons::Ascii => "*",
+        }
+    }
+
+    /// Marks the selected choice of a list.
+    pub fn selected(self) -> &'static str {
+        match self {
+            Icons::Nerd => "\u{f061}",
+            Icons::Unicode => "→",
+            Icons::Ascii => ">",
+        }
+    }
+
+    /// Whether the password being typed is shown.
+    pub fn password_visibility(self, show_password: bool) -> &'static str {
+        match (self, show_password) {
+            (Icons::Nerd, true) => "󰈈 ",
+            (Icons::Nerd, false) => "󰈉 ",
+            (_, true) => "visible",
+            (_, false) => "hidden",
+        }
+    }
+
+    pub fn space(self) -> &'static str {
+        match self {
+            Icons::Nerd => "󱁐 ",
+            Icons::Unicode => "␣",
+            Icons::Ascii => "space",
+        }
+    }
+
+    pub fn enter(self) -> &'static str {
+        match self {
+            Icons::Nerd | Icons::Unicode => "↵ ",
+            Icons::Ascii => "enter",
+        }
+    }
+
+    pub fn esc(self) -> &'static str {
+        match self {
+            Icons::Nerd => "󱊷 ",
+            Icons::Unicode | Icons::Ascii => "esc",
+        }
+    }
+
+    pub fn up(self) -> &'static str {
+        match self {
+            Icons::Nerd => "\u{f062}",
+            Icons::Unicode => "↑",
+            Icons::Ascii => "up",
+        }
+    }
+
+    pub fn down(self) -> &'static str {
+        match self {
+            Icons::Nerd => "\u{f063}",
+            Icons::Unicode => "↓",
+            Icons::Ascii => "down",
+        }
+    }
+
+    pub fn left(self) -> &'static str {
+        match self {
+            Icons::Nerd | Icons::Unicode => "←",
+            Icons::Ascii => "left",
+        }
+    }
+
+    pub fn right(self) -> &'static str {
+        match self {
+            Icons::Nerd | Icons::Unicode => "→",
+            Icons::Ascii => "right",
+        }
+    }
+}
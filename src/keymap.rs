@@ -0,0 +1,687 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    str::FromStr,
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+use crate::{config::Config, icons::Icons};
+
+/// A key with its modifiers, e.g. `ctrl-r`, `alt-x`, `f5` or `pageup`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // Shift is already part of the character (or of BackTab)
+        let mut modifiers = modifiers;
+        let mut code = code;
+        match code {
+            KeyCode::Char(c) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                if modifiers.contains(KeyModifiers::CONTROL) {
+                    code = KeyCode::Char(c.to_ascii_lowercase());
+                }
+            }
+            KeyCode::BackTab => modifiers.remove(KeyModifiers::SHIFT),
+            _ => {}
+        }
+        Self { code, modifiers }
+    }
+}
+
+impl From<KeyEvent> for KeyBinding {
+    fn from(key_event: KeyEvent) -> Self {
+        Self::new(key_event.code, key_event.modifiers)
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut key = s;
+
+        // A lone "-" is a key, not a separator
+        while let Some((modifier, rest)) = key.split_once('-')
+            && !rest.is_empty()
+        {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" => modifiers.insert(KeyModifiers::CONTROL),
+                "alt" => modifiers.insert(KeyModifiers::ALT),
+                "shift" => modifiers.insert(KeyModifiers::SHIFT),
+                _ => return Err(format!("unknown modifier `{}` in `{}`", modifier, s)),
+            }
+            key = rest;
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_lowercase().as_str() {
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "space" => KeyCode::Char(' '),
+                "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                f if f.starts_with('f') && f[1..].parse::<u8>().is_ok_and(|n| n > 0) => {
+                    KeyCode::F(f[1..].parse().unwrap())
+                }
+                _ => return Err(format!("unknown key `{}`", s)),
+            },
+        };
+
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl KeyBinding {
+    /// The key as shown in the help bars.
+    pub fn label(&self, icons: Icons) -> String {
+        let mut label = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            label.push_str("ctrl+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            label.push_str("alt+");
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            label.push_str("shift+");
+        }
+        match self.code {
+            KeyCode::Char(' ') => label.push_str(icons.space()),
+            KeyCode::Char(c) => label.push(c),
+            KeyCode::Enter => label.push_str(icons.enter()),
+            KeyCode::Esc => label.push_str(icons.esc()),
+            KeyCode::Tab => label.push_str("tab"),
+            KeyCode::BackTab => label.push_str("shift+tab"),
+            KeyCode::Up => label.push_str(icons.up()),
+            KeyCode::Down => label.push_str(icons.down()),
+            KeyCode::Left => label.push_str(icons.left()),
+            KeyCode::Right => label.push_str(icons.right()),
+            KeyCode::PageUp => label.push_str("pageup"),
+            KeyCode::PageDown => label.push_str("pagedown"),
+            KeyCode::F(n) => label.push_str(&format!("F{}", n)),
+            code => label.push_str(&code.to_string().to_lowercase()),
+        }
+        label
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label(Icons::Ascii))
+    }
+}
+
+/// One or more keys pressed one after the other, e.g. `g g`.
+pub type KeySequence = Vec<KeyBinding>;
+
+fn parse_sequence(s: &str) -> Result<KeySequence, String> {
+    let sequence = s
+        .split_whitespace()
+        .map(KeyBinding::from_str)
+        .collect::<Result<KeySequence, String>>()?;
+
+    if sequence.is_empty() {
+        return Err("empty key binding".to_string());
+    }
+    Ok(sequence)
+}
+
+/// The group of actions a key is looked up in, depending on the focused block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Context {
+    Global,
+    Navigation,
+    Device,
+    KnownNetworks,
+    NewNetworks,
+    Search,
+    AccessPoint,
+    P2pPeers,
+    Popup,
+}
+
+impl Context {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Context::Global => "global",
+            Context::Navigation => "navigation",
+            Context::Device => "device",
+            Context::KnownNetworks => "known_networks",
+            Context::NewNetworks => "new_networks",
+            Context::Search => "search",
+            Context::AccessPoint => "access_point",
+            Context::P2pPeers => "p2p_peers",
+            Context::Popup => "popup",
+        }
+    }
+}
+
+/// The contexts that are active at the same time, a key can only be bound to one action in
+/// each of them.
+const SCOPES: &[&[Context]] = &[
+    &[Context::Device, Context::Global],
+    &[
+        Context::KnownNetworks,
+        Context::Search,
+        Context::Navigation,
+        Context::Global,
+    ],
+    &[
+        Context::NewNetworks,
+        Context::Search,
+        Context::Navigation,
+        Context::Global,
+    ],
+    &[Context::AccessPoint, Context::Navigation, Context::Global],
+    &[Context::P2pPeers, Context::Navigation, Context::Global],
+    &[Context::Popup, Context::Navigation],
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    SwitchMode,
+    FocusNext,
+    FocusPrevious,
+    Scan,
+    Help,
+
+    Up,
+    Down,
+
+    DeviceInfos,
+    TogglePower,
+    SelectDevice,
+    ShowLinkQuality,
+    ShowTraffic,
+    StartDppEnrollee,
+
+    ConnectKnownNetwork,
+    RemoveKnownNetwork,
+    ToggleAutoconnect,
+    ShowAllKnownNetworks,
+    ShareKnownNetwork,
+    StartDppConfigurator,
+    SortKnownNetworks,
+    ShowKnownNetworkDetails,
+
+    ConnectNewNetwork,
+    ShowAllNewNetworks,
+    ConnectHiddenNetwork,
+    ConnectWps,
+    SortNewNetworks,
+    ShowNewNetworkDetails,
+
+    Filter,
+    NextMatch,
+    PreviousMatch,
+
+    StartAccessPoint,
+    StopAccessPoint,
+
+    ConnectPeer,
+
+    Submit,
+    Close,
+    NextField,
+    PopupHelp,
+}
+
+/// Every action with its context and its name in the config file.
+const ACTIONS: &[(Action, Context, &str)] = &[
+    (Action::Quit, Context::Global, "quit"),
+    (Action::SwitchMode, Context::Global, "switch_mode"),
+    (Action::FocusNext, Context::Global, "focus_next"),
+    (Action::FocusPrevious, Context::Global, "focus_previous"),
+    (Action::Scan, Context::Global, "scan"),
+    (Action::Help, Context::Global, "help"),
+    (Action::Up, Context::Navigation, "up"),
+    (Action::Down, Context::Navigation, "down"),
+    (Action::DeviceInfos, Context::Device, "infos"),
+    (Action::TogglePower, Context::Device, "toggle_power"),
+    (Action::SelectDevice, Context::Device, "select"),
+    (Action::ShowLinkQuality, Context::Device, "link_quality"),
+    (Action::ShowTraffic, Context::Device, "traffic"),
+    (Action::StartDppEnrollee, Context::Device, "dpp"),
+    (
+        Action::ConnectKnownNetwork,
+        Context::KnownNetworks,
+        "connect",
+    ),
+    (Action::RemoveKnownNetwork, Context::KnownNetworks, "remove"),
+    (
+        Action::ToggleAutoconnect,
+        Context::KnownNetworks,
+        "toggle_autoconnect",
+    ),
+    (
+        Action::ShowAllKnownNetworks,
+        Context::KnownNetworks,
+        "show_all",
+    ),
+    (Action::ShareKnownNetwork, Context::KnownNetworks, "share"),
+    (Action::StartDppConfigurator, Context::KnownNetworks, "dpp"),
+    (Action::SortKnownNetworks, Context::KnownNetworks, "sort"),
+    (
+        Action::ShowKnownNetworkDetails,
+        Context::KnownNetworks,
+        "details",
+    ),
+    (Action::ConnectNewNetwork, Context::NewNetworks, "connect"),
+    (Action::ShowAllNewNetworks, Context::NewNetworks, "show_all"),
+    (
+        Action::ConnectHiddenNetwork,
+        Context::NewNetworks,
+        "connect_hidden",
+    ),
+    (Action::ConnectWps, Context::NewNetworks, "wps"),
+    (Action::SortNewNetworks, Context::NewNetworks, "sort"),
+    (
+        Action::ShowNewNetworkDetails,
+        Context::NewNetworks,
+        "details",
+    ),
+    (Action::Filter, Context::Search, "filter"),
+    (Action::NextMatch, Context::Search, "next_match"),
+    (Action::PreviousMatch, Context::Search, "previous_match"),
+    (Action::StartAccessPoint, Context::AccessPoint, "start"),
+    (Action::StopAccessPoint, Context::AccessPoint, "stop"),
+    (Action::ConnectPeer, Context::P2pPeers, "connect"),
+    (Action::Submit, Context::Popup, "submit"),
+    (Action::Close, Context::Popup, "close"),
+    (Action::NextField, Context::Popup, "next_field"),
+    (Action::PopupHelp, Context::Popup, "help"),
+];
+
+impl Action {
+    pub fn context(&self) -> Context {
+        ACTIONS.iter().find(|(a, _, _)| a == self).unwrap().1
+    }
+
+    pub fn name(&self) -> &'static str {
+        ACTIONS.iter().find(|(a, _, _)| a == self).unwrap().2
+    }
+
+    fn find(context: &str, name: &str) -> Option<Self> {
+        ACTIONS
+            .iter()
+            .find(|(_, c, n)| c.name() == context && *n == name)
+            .map(|(action, _, _)| *action)
+    }
+}
+
+/// The bindings of an action in the `[keymap.<context>]` tables of the config file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Bindings {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Bindings {
+    fn keys(&self) -> Vec<&str> {
+        match self {
+            Bindings::One(key) => vec![key.as_str()],
+            Bindings::Many(keys) => keys.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+pub type KeymapConfig = HashMap<String, HashMap<String, Bindings>>;
+
+/// An invalid binding, `key` is the config key it comes from, e.g. `keymap.device.infos`.
+#[derive(Debug, Clone)]
+pub struct KeymapError {
+    pub key: String,
+    pub message: String,
+}
+
+/// The result of looking up the keys pressed so far.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyMatch {
+    Action(Action),
+    /// The keys are the beginning of a longer sequence
+    Pending,
+    None,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Keymap {
+    bindings: HashMap<Action, Vec<KeySequence>>,
+    /// Where each binding is set, to point at it in the errors
+    sources: HashMap<Action, String>,
+    /// The actions bound in the config file, their keys win over the built-in ones
+    custom: HashSet<Action>,
+    icons: Icons,
+}
+
+impl Keymap {
+    /// Builds the keymap from the defaults, the single key settings (e.g. `device.infos`)
+    /// and the `[keymap.<context>]` tables, in this order of precedence.
+    ///
+    /// `is_set` tells whether a single key setting is written in the config file, the
+    /// other ones keep their default.
+    pub fn new(
+        config: &Config,
+        keymap_config: &KeymapConfig,
+        is_set: impl Fn(&str) -> bool,
+    ) -> Result<Self, KeymapError> {
+        let mut keymap = Self {
+            icons: config.icons,
+            ..Self::default()
+        };
+
+        let char_key = |c: char| c.to_string();
+        let mut quit = vec!["q".to_string(), "ctrl-c".to_string()];
+        if config.esc_quit {
+            quit.push("esc".to_string());
+        }
+
+        let defaults: Vec<(Action, Vec<String>, &str)> = vec![
+            (Action::Quit, quit, ""),
+            (
+                Action::SwitchMode,
+                vec![format!("ctrl-{}", config.switch)],
+                "switch",
+            ),
+            (Action::FocusNext, vec!["tab".to_string()], ""),
+            (Action::FocusPrevious, vec!["backtab".to_string()], ""),
+            (
+                Action::Scan,
+                vec![char_key(config.station.start_scanning)],
+                "station.start_scanning",
+            ),
+            (Action::Help, vec!["?".to_string(), "f1".to_string()], ""),
+            (Action::Up, vec!["k".to_string(), "up".to_string()], ""),
+            (Action::Down, vec!["j".to_string(), "down".to_string()], ""),
+            (
+                Action::DeviceInfos,
+                vec![char_key(config.device.infos)],
+                "device.infos",
+            ),
+            (
+                Action::TogglePower,
+                vec![char_key(config.device.toggle_power)],
+                "device.toggle_power",
+            ),
+            (
+                Action::SelectDevice,
+                vec![char_key(config.device.select)],
+                "device.select",
+            ),
+            (Action::ShowLinkQuality, vec!["l".to_string()], ""),
+            (Action::ShowTraffic, vec!["t".to_string()], ""),
+            (Action::StartDppEnrollee, vec!["e".to_string()], ""),
+            (
+                Action::ConnectKnownNetwork,
+                vec!["space".to_string(), "enter".to_string()],
+                "",
+            ),
+            (
+                Action::RemoveKnownNetwork,
+                vec![char_key(config.station.known_network.remove)],
+                "station.known_network.remove",
+            ),
+            (
+                Action::ToggleAutoconnect,
+                vec![char_key(config.station.known_network.toggle_autoconnect)],
+                "station.known_network.toggle_autoconnect",
+            ),
+            (
+                Action::ShowAllKnownNetworks,
+                vec![char_key(config.station.known_network.show_all)],
+                "station.known_network.show_all",
+            ),
+            (
+                Action::ShareKnownNetwork,
+                vec![char_key(config.station.known_network.share)],
+                "station.known_network.share",
+            ),
+            (Action::StartDppConfigurator, vec!["e".to_string()], ""),
+            (Action::SortKnownNetworks, vec!["S".to_string()], ""),
+            (Action::ShowKnownNetworkDetails, vec!["i".to_string()], ""),
+            (
+                Action::ConnectNewNetwork,
+                vec!["space".to_string(), "enter".to_string()],
+                "",
+            ),
+            (
+                Action::ShowAllNewNetworks,
+                vec![char_key(config.station.new_network.show_all)],
+                "station.new_network.show_all",
+            ),
+            (
+                Action::ConnectHiddenNetwork,
+                vec![char_key(config.station.new_network.connect_hidden)],
+                "station.new_network.connect_hidden",
+            ),
+            (Action::ConnectWps, vec!["w".to_string()], ""),
+            (Action::SortNewNetworks, vec!["S".to_string()], ""),
+            (Action::ShowNewNetworkDetails, vec!["i".to_string()], ""),
+            (Action::Filter, vec!["/".to_string()], ""),
+            (Action::NextMatch, vec!["n".to_string()], ""),
+            (Action::PreviousMatch, vec!["N".to_string()], ""),
+            (
+                Action::StartAccessPoint,
+                vec![char_key(config.ap.start)],
+                "access_point.start",
+            ),
+            (
+                Action::StopAccessPoint,
+                vec![char_key(config.ap.stop)],
+                "access_point.stop",
+            ),
+            (
+                Action::ConnectPeer,
+                vec!["space".to_string(), "enter".to_string()],
+                "",
+            ),
+            (Action::Submit, vec!["enter".to_string()], ""),
+            (Action::Close, vec!["esc".to_string()], ""),
+            (Action::NextField, vec!["tab".to_string()], ""),
+            // `?` is typed in the text inputs
+            (Action::PopupHelp, vec!["f1".to_string()], ""),
+        ];
+
+        for (action, keys, source) in defaults {
+            let is_custom = !source.is_empty() && is_set(source);
+            let source = if source.is_empty() {
+                format!("keymap.{}.{}", action.context().name(), action.name())
+            } else {
+                source.to_string()
+            };
+            keymap.bind(action, keys.iter().map(String::as_str), source, is_custom)?;
+        }
+
+        for (context, actions) in keymap_config {
+            for (name, bindings) in actions {
+                // Unknown actions are reported as warnings with the other unknown keys
+                if let Some(action) = Action::find(context, name) {
+                    keymap.bind(
+                        action,
+                        bindings.keys().into_iter(),
+                        format!("keymap.{}.{}", context, name),
+                        true,
+                    )?;
+                }
+            }
+        }
+
+        keymap.resolve_conflicts()?;
+
+        Ok(keymap)
+    }
+
+    /// The `[keymap.<context>]` keys that do not match any action.
+    pub fn unknown_keys(keymap_config: &KeymapConfig) -> Vec<String> {
+        let mut keys: Vec<String> = keymap_config
+            .iter()
+            .flat_map(|(context, actions)| {
+                actions
+                    .keys()
+                    .filter(|name| Action::find(context, name).is_none())
+                    .map(move |name| format!("keymap.{}.{}", context, name))
+            })
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    fn bind<'a>(
+        &mut self,
+        action: Action,
+        keys: impl Iterator<Item = &'a str>,
+        source: String,
+        is_custom: bool,
+    ) -> Result<(), KeymapError> {
+        let sequences = keys
+            .map(parse_sequence)
+            .collect::<Result<Vec<KeySequence>, String>>()
+            .map_err(|message| KeymapError {
+                key: source.clone(),
+                message,
+            })?;
+
+        self.bindings.insert(action, sequences);
+        self.sources.insert(action, source);
+        if is_custom {
+            self.custom.insert(action);
+        } else {
+            self.custom.remove(&action);
+        }
+        Ok(())
+    }
+
+    /// Drops the built-in keys taken by a key of the config file, two keys of the config
+    /// file bound to different actions are an error.
+    fn resolve_conflicts(&mut self) -> Result<(), KeymapError> {
+        let mut overridden = Vec::new();
+
+        for scope in SCOPES {
+            let bindings: Vec<(Action, &KeySequence)> = ACTIONS
+                .iter()
+                .filter(|(_, context, _)| scope.contains(context))
+                .flat_map(|(action, _, _)| {
+                    self.bindings
+                        .get(action)
+                        .into_iter()
+                        .flatten()
+                        .map(move |sequence| (*action, sequence))
+                })
+                .collect();
+
+            for (i, (action, sequence)) in bindings.iter().enumerate() {
+                for (other_action, other_sequence) in &bindings[i + 1..] {
+                    if action == other_action
+                        || !(sequence.starts_with(other_sequence)
+                            || other_sequence.starts_with(sequence))
+                    {
+                        continue;
+                    }
+
+                    match (
+                        self.custom.contains(action),
+                        self.custom.contains(other_action),
+                    ) {
+                        (true, false) => {
+                            overridden.push((*other_action, (*other_sequence).clone()))
+                        }
+                        (false, true) => overridden.push((*action, (*sequence).clone())),
+                        _ => {
+                            return Err(KeymapError {
+                                key: self.sources[other_action].clone(),
+                                message: format!(
+                                    "`{}` conflicts with `{}` ({})",
+                                    self.sources[other_action],
+                                    self.sources[action],
+                                    display_sequence(other_sequence),
+                                ),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        for (action, sequence) in overridden {
+            if let Some(sequences) = self.bindings.get_mut(&action) {
+                sequences.retain(|s| *s != sequence);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Looks up the keys pressed so far in the given contexts, the first ones take precedence.
+    pub fn lookup(&self, contexts: &[Context], keys: &[KeyBinding]) -> KeyMatch {
+        let mut is_pending = false;
+
+        for context in contexts {
+            for (action, _, _) in ACTIONS.iter().filter(|(_, c, _)| c == context) {
+                for sequence in self.bindings.get(action).into_iter().flatten() {
+                    if sequence.as_slice() == keys {
+                        return KeyMatch::Action(*action);
+                    }
+                    if sequence.starts_with(keys) {
+                        is_pending = true;
+                    }
+                }
+            }
+        }
+
+        if is_pending {
+            KeyMatch::Pending
+        } else {
+            KeyMatch::None
+        }
+    }
+
+    /// The keys bound to an action, as shown in the help.
+    pub fn key(&self, action: Action) -> String {
+        self.bindings
+            .get(&action)
+            .map(|sequences| {
+                sequences
+                    .iter()
+                    .map(|sequence| {
+                        sequence
+                            .iter()
+                            .map(|binding| binding.label(self.icons))
+                            .collect::<Vec<String>>()
+                            .join(" ")
+                    })
+                    .collect::<Vec<String>>()
+                    .join(",")
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn display_sequence(sequence: &KeySequence) -> String {
+    sequence
+        .iter()
+        .map(KeyBinding::to_string)
+        .collect::<Vec<String>>()
+        .join(" ")
+}
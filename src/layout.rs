@@ -0,0 +1,67 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Position, Rect},
+    style::{Style, Stylize},
+    text::{Line, Text},
+    widgets::{Clear, Paragraph, Wrap},
+};
+
+use crate::theme::Theme;
+
+/// Below this size the blocks can not be drawn and a placeholder is shown instead.
+pub const MIN_WIDTH: u16 = 40;
+pub const MIN_HEIGHT: u16 = 12;
+
+/// Below this width the tables only show their main columns.
+pub const COMPACT_WIDTH: u16 = 100;
+
+pub fn is_too_small(area: Rect) -> bool {
+    area.width < MIN_WIDTH || area.height < MIN_HEIGHT
+}
+
+pub fn is_compact(area: Rect) -> bool {
+    area.width < COMPACT_WIDTH
+}
+
+/// Centers a popup of the given size, shrinking it when the terminal is smaller.
+pub fn popup_area(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::horizontal([Constraint::Length(width.min(area.width))])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::vertical([Constraint::Length(height.min(area.height))])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
+/// Index of the row drawn at the position in a table with borders and a header followed by an
+/// empty line, `offset` being the first row shown.
+pub fn table_row_at(area: Rect, offset: usize, position: Position) -> Option<usize> {
+    let first_row = area.y + 3;
+    if !area.contains(position) || position.y < first_row || position.y >= area.bottom() - 1 {
+        return None;
+    }
+    Some(offset + (position.y - first_row) as usize)
+}
+
+pub fn render_too_small(frame: &mut Frame, theme: &Theme) {
+    let area = frame.area();
+    let text = Text::from(vec![
+        Line::from("Terminal too small").bold(),
+        Line::from(format!("{}x{}", area.width, area.height)).fg(theme.error),
+        Line::from(format!("Needs at least {}x{}", MIN_WIDTH, MIN_HEIGHT)),
+    ]);
+
+    let [message_area] = Layout::vertical([Constraint::Length(text.height() as u16)])
+        .flex(Flex::Center)
+        .areas(area);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(text)
+            .centered()
+            .wrap(Wrap { trim: true })
+            .style(Style::default().fg(theme.text)),
+        message_area,
+    );
+}
@@ -10,6 +10,16 @@ pub mod handler;
 
 pub mod config;
 
+pub mod keymap;
+
+pub mod theme;
+
+pub mod icons;
+
+pub mod layout;
+
+pub mod help;
+
 pub mod notification;
 
 pub mod device;
@@ -18,6 +28,8 @@ pub mod adapter;
 
 pub mod cli;
 
+pub mod commands;
+
 pub mod rfkill;
 
 pub mod mode;
@@ -26,6 +38,10 @@ pub mod reset;
 
 pub mod agent;
 
+pub mod monitor;
+
+pub mod backend;
+
 pub fn iwd_network_name(name: &str) -> String {
     match name
         .chars()
@@ -35,3 +51,18 @@ pub fn iwd_network_name(name: &str) -> String {
         false => format!("={}", hex::encode(name)),
     }
 }
+
+/// The file where iwd stores a known network, iwd does not support WEP.
+pub fn iwd_network_file(name: &str, network_type: iwdrs::network::NetworkType) -> Option<String> {
+    let extension = match network_type {
+        iwdrs::network::NetworkType::Open => "open",
+        iwdrs::network::NetworkType::Psk => "psk",
+        iwdrs::network::NetworkType::Eap => "8021x",
+        iwdrs::network::NetworkType::Wep => return None,
+    };
+    Some(format!(
+        "/var/lib/iwd/{}.{}",
+        iwd_network_name(name),
+        extension
+    ))
+}
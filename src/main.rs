@@ -4,10 +4,14 @@ use env_logger::Target;
 use impala::notification;
 use impala::{
     app::App,
+    backend::{FakeBackend, IwdBackend, WifiBackend, fake::scenario},
     cli,
+    commands::{self, ExitStatus},
     config::Config,
     event::{Event, EventHandler},
-    handler::{handle_key_events, toggle_connect},
+    handler::{handle_key_events, handle_mouse_events, toggle_connect},
+    mode::ViewMode,
+    monitor::{self, Changes},
     notification::{Notification, NotificationLevel},
     rfkill,
     tui::Tui,
@@ -26,22 +30,80 @@ async fn main() -> Result<()> {
 
     let args = cli::cli().get_matches();
 
-    rfkill::check()?;
+    if let Some(("config", args)) = args.subcommand() {
+        match commands::config::run(args) {
+            Ok(status) => exit(status as i32),
+            Err(e) => {
+                eprintln!("{}", e);
+                exit(ExitStatus::Failure as i32);
+            }
+        }
+    }
 
-    let config = Arc::new(Config::new());
+    // An invalid config should not prevent impala from starting
+    let (config, config_error) = match Config::load() {
+        Ok(config) => (config, None),
+        Err(e) => (Config::default(), Some(e)),
+    };
+    let config = Arc::new(config);
+
+    let simulated_scenario = args.get_one::<String>("simulate");
 
-    let backend = CrosstermBackend::new(io::stdout());
-    let terminal = Terminal::new(backend)?;
+    let backend: Arc<dyn WifiBackend> = match simulated_scenario {
+        Some(path) => Arc::new(FakeBackend::new(scenario::load(path)?)),
+        None => {
+            // The commands report the blocked device themselves
+            if args.subcommand().is_none() {
+                rfkill::check()?;
+            }
+            Arc::new(IwdBackend::new().await?)
+        }
+    };
+
+    if args.subcommand().is_some() {
+        if let Some(e) = &config_error {
+            eprintln!("Invalid config, using the default one: {}", e);
+        }
+
+        match commands::run(&args, config.clone(), backend).await {
+            Ok(status) => exit(status as i32),
+            Err(e) => {
+                if e.to_string()
+                    .contains("org.freedesktop.DBus.Error.AccessDenied")
+                {
+                    eprintln!("Insufficient Permissions.");
+                    eprintln!(
+                        "You do not have the required permissions. Ensure you are part of the appropriate user group or use sudo."
+                    )
+                } else {
+                    eprintln!("{}", e);
+                }
+                exit(ExitStatus::Failure as i32);
+            }
+        }
+    }
+
+    let terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
     let events = EventHandler::new(1_000);
     let mut tui = Tui::new(terminal, events);
-    tui.init()?;
+    tui.init(config.mouse)?;
 
     let mode = args.get_one::<String>("mode").cloned();
     let mode = mode.unwrap_or_else(|| config.mode.clone());
 
-    let mode = Mode::try_from(mode.as_str())?;
+    let mode = ViewMode::try_from(mode.as_str())?;
+
+    let device_name = args.get_one::<String>("device").cloned();
 
-    let mut app = match App::new(tui.events.sender.clone(), config.clone(), mode).await {
+    let mut app = match App::new(
+        backend.clone(),
+        tui.events.sender.clone(),
+        config.clone(),
+        mode,
+        device_name,
+    )
+    .await
+    {
         Ok(app) => app,
         Err(e) => {
             tui.exit()?;
@@ -60,6 +122,27 @@ async fn main() -> Result<()> {
         }
     };
 
+    if let Some(e) = config_error {
+        app.notifications.push(Notification {
+            message: format!("Invalid config, using the default one\n{}", e),
+            level: NotificationLevel::Warning,
+            ttl: 10,
+        });
+    } else if !config.warnings.is_empty() {
+        app.notifications.push(Notification {
+            message: format!(
+                "{}\nRun `impala config check` for details",
+                config.warnings.join("\n")
+            ),
+            level: NotificationLevel::Warning,
+            ttl: 10,
+        });
+    }
+
+    // Fall back to polling the backend when the iwd signals can not be received
+    let mut is_monitored =
+        simulated_scenario.is_none() && monitor::spawn(tui.events.sender.clone()).await.is_ok();
+
     let mut exit_error_message = None;
 
     while app.running {
@@ -70,6 +153,22 @@ async fn main() -> Result<()> {
                     exit_error_message = Some(e);
                     break;
                 }
+
+                if !is_monitored && let Err(e) = app.refresh(Changes::all()).await {
+                    exit_error_message = Some(e);
+                    break;
+                }
+            }
+
+            Event::Refresh(changes) => {
+                if let Err(e) = app.refresh(changes).await {
+                    exit_error_message = Some(e);
+                    break;
+                }
+            }
+
+            Event::MonitorStopped => {
+                is_monitored = false;
             }
 
             Event::Key(key_event) => {
@@ -82,17 +181,51 @@ async fn main() -> Result<()> {
                 .await;
             }
 
+            Event::Mouse(mouse_event) => {
+                let _ = handle_mouse_events(mouse_event, &mut app, tui.events.sender.clone()).await;
+            }
+
             Event::Notification(notification) => {
                 app.notifications.push(notification);
             }
 
+            Event::WpsEnded => {
+                if let Some(station) = &mut app.device.station
+                    && station.wps.take().is_some()
+                    && app.focused_block == impala::app::FocusedBlock::Wps
+                {
+                    app.focused_block = impala::app::FocusedBlock::NewNetworks;
+                }
+                if let Some(p2p) = &mut app.device.p2p
+                    && p2p.wps.take().is_some()
+                    && app.focused_block == impala::app::FocusedBlock::Wps
+                {
+                    app.focused_block = impala::app::FocusedBlock::P2pPeers;
+                }
+            }
+
+            Event::SignalLevel(device_name, level) => {
+                app.signal_level_changed(&device_name, level).await;
+            }
+
             Event::Reset(mode) => {
-                if let Err(e) = App::reset(mode).await {
+                let device_name = app.device.name.clone();
+                if let Err(e) =
+                    App::reset(backend.clone(), config.clone(), mode, &device_name).await
+                {
                     exit_error_message = Some(e);
                     break;
                 };
 
-                match App::new(tui.events.sender.clone(), config.clone(), mode).await {
+                match App::new(
+                    backend.clone(),
+                    tui.events.sender.clone(),
+                    config.clone(),
+                    mode,
+                    Some(device_name),
+                )
+                .await
+                {
                     Ok(v) => app = v,
                     Err(e) => {
                         if e.to_string()
@@ -183,10 +316,10 @@ You do not have the required permissions. Ensure you are part of the appropriate
                     && let Some(station) = &mut app.device.station
                 {
                     tokio::spawn({
-                        let iwd_station = station.session.stations().await.unwrap().pop().unwrap();
+                        let station = station.clone();
                         let sender = tui.events.sender.clone();
                         async move {
-                            if let Err(e) = iwd_station.connect_hidden_network(ssid).await {
+                            if let Err(e) = station.connect_hidden_network(ssid).await {
                                 let _ = Notification::send(
                                     e.to_string(),
                                     notification::NotificationLevel::Error,
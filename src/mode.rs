@@ -1,2 +1,52 @@
 pub mod ap;
+pub mod p2p;
 pub mod station;
+
+use anyhow::{Result, anyhow};
+use iwdrs::modes::Mode;
+
+/// The views impala shows a device in. Wi-Fi Direct is not an iwd mode, its P2P device runs
+/// beside the station of the adapter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewMode {
+    Station,
+    Ap,
+    P2p,
+}
+
+impl ViewMode {
+    /// The picker order.
+    pub const ALL: [ViewMode; 3] = [ViewMode::Station, ViewMode::Ap, ViewMode::P2p];
+
+    /// The iwd mode the device is set to.
+    pub fn iwd_mode(self) -> Mode {
+        match self {
+            ViewMode::Station | ViewMode::P2p => Mode::Station,
+            ViewMode::Ap => Mode::Ap,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ViewMode::Station => "Station",
+            ViewMode::Ap => "Access Point",
+            ViewMode::P2p => "Wi-Fi Direct",
+        }
+    }
+}
+
+impl TryFrom<&str> for ViewMode {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        match value {
+            "station" => Ok(ViewMode::Station),
+            "ap" => Ok(ViewMode::Ap),
+            "p2p" => Ok(ViewMode::P2p),
+            _ => Err(anyhow!(
+                "Unknown mode {}, expected station, ap or p2p",
+                value
+            )),
+        }
+    }
+}
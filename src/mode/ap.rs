@@ -1,25 +1,28 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use std::sync::{Arc, atomic::AtomicBool};
 
-use iwdrs::session::Session;
 use tokio::sync::mpsc::UnboundedSender;
 
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Direction, Flex, Layout},
-    style::{Color, Style, Stylize},
+    layout::{Alignment, Constraint, Direction, Flex, Layout, Rect},
+    style::{Style, Stylize},
     text::{Line, Span, Text},
     widgets::{
-        Block, BorderType, Borders, Clear, List, Padding, Paragraph, Row, Table, TableState,
+        Block, BorderType, Borders, Clear, List, Padding, Paragraph, Row, Table, TableState, Wrap,
     },
 };
 
 use crate::{
     app::FocusedBlock,
+    backend::{AccessPointInfo, WifiBackend},
     config::Config,
     device::Device,
     event::Event,
+    keymap::Action,
+    layout::{is_compact, popup_area},
     notification::{Notification, NotificationLevel},
+    theme::Theme,
 };
 use tui_input::Input;
 
@@ -31,7 +34,8 @@ pub enum APFocusedSection {
 
 #[derive(Debug, Clone)]
 pub struct AccessPoint {
-    session: Arc<Session>,
+    backend: Arc<dyn WifiBackend>,
+    pub device_name: String,
     pub has_started: bool,
     pub name: Option<String>,
     pub frequency: Option<u32>,
@@ -43,45 +47,36 @@ pub struct AccessPoint {
     pub psk: Input,
     pub focused_section: APFocusedSection,
     pub connected_devices: Vec<String>,
+    /// Where the blocks were last drawn, to find the one under the mouse
+    pub device_area: Rect,
+    pub access_point_area: Rect,
+    pub connected_devices_area: Rect,
 }
 
 impl AccessPoint {
-    pub async fn new(session: Arc<Session>) -> Result<Self> {
-        let iwd_access_point = session
-            .access_points()
-            .await?
-            .pop()
-            .context("no ap found")?;
-        let iwd_access_point_diagnostic = session.access_points_diagnostics().await?.pop();
-
-        let has_started = iwd_access_point.has_started().await?;
-        let name = iwd_access_point.name().await?;
-        let frequency = iwd_access_point.frequency().await?;
-        let is_scanning = iwd_access_point.is_scanning().await.ok();
-        let supported_ciphers = iwd_access_point.pairwise_ciphers().await?;
-        let used_cipher = iwd_access_point.group_cipher().await?;
+    pub async fn new(backend: Arc<dyn WifiBackend>, device_name: String) -> Result<Self> {
+        let AccessPointInfo {
+            has_started,
+            name,
+            frequency,
+            is_scanning,
+            supported_ciphers,
+            used_cipher,
+        } = backend.access_point(&device_name).await?;
         let ap_start = Arc::new(AtomicBool::new(false));
 
         let ssid = Input::default();
         let psk = Input::default();
         let focused_section = APFocusedSection::SSID;
 
-        let connected_devices = {
-            if let Some(diagnostic) = iwd_access_point_diagnostic {
-                match diagnostic.get().await {
-                    Ok(diagnostic) => diagnostic
-                        .iter()
-                        .map(|v| v["Address"].clone().trim_matches('"').to_string())
-                        .collect(),
-                    Err(_) => Vec::new(),
-                }
-            } else {
-                Vec::new()
-            }
-        };
+        let connected_devices = backend
+            .access_point_clients(&device_name)
+            .await
+            .unwrap_or(Vec::new());
 
         Ok(Self {
-            session,
+            backend,
+            device_name,
             has_started,
             name,
             frequency,
@@ -93,33 +88,14 @@ impl AccessPoint {
             psk,
             focused_section,
             connected_devices,
+            device_area: Rect::default(),
+            access_point_area: Rect::default(),
+            connected_devices_area: Rect::default(),
         })
     }
 
-    pub fn render_input(&self, frame: &mut Frame) {
-        let popup_layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(
-                [
-                    Constraint::Percentage(45),
-                    Constraint::Min(7),
-                    Constraint::Percentage(45),
-                ]
-                .as_ref(),
-            )
-            .split(frame.area());
-
-        let area = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(
-                [
-                    Constraint::Length((frame.area().width - 80) / 2),
-                    Constraint::Min(80),
-                    Constraint::Length((frame.area().width - 80) / 2),
-                ]
-                .as_ref(),
-            )
-            .split(popup_layout[1])[1];
+    pub fn render_input(&self, frame: &mut Frame, theme: &Theme) {
+        let area = popup_area(frame.area(), 80, 7);
 
         let ((ssid_msg_area, ssid_input_area), (psk_msg_area, psk_input_area)) = {
             let chunks = Layout::default()
@@ -180,23 +156,23 @@ impl AccessPoint {
 
         let ssid_msg = Paragraph::new(ssid_text)
             .alignment(Alignment::Left)
-            .style(Style::default().fg(Color::White))
+            .style(Style::default().fg(theme.text))
             .block(Block::new().padding(Padding::left(2)));
 
         let ssid_input = Paragraph::new(self.ssid.value())
             .alignment(Alignment::Center)
-            .style(Style::default().fg(Color::White))
-            .block(Block::new().style(Style::default().bg(Color::DarkGray)));
+            .style(Style::default().fg(theme.text))
+            .block(Block::new().style(Style::default().bg(theme.input)));
 
         let psk_msg = Paragraph::new(psk_text)
             .alignment(Alignment::Left)
-            .style(Style::default().fg(Color::White))
+            .style(Style::default().fg(theme.text))
             .block(Block::new().padding(Padding::left(2)));
 
         let psk_input = Paragraph::new(self.psk.value())
             .alignment(Alignment::Center)
-            .style(Style::default().fg(Color::White))
-            .block(Block::new().style(Style::default().bg(Color::DarkGray)));
+            .style(Style::default().fg(theme.text))
+            .block(Block::new().style(Style::default().bg(theme.input)));
 
         frame.render_widget(Clear, area);
 
@@ -204,8 +180,8 @@ impl AccessPoint {
             Block::new()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Thick)
-                .style(Style::default().green())
-                .border_style(Style::default().fg(Color::Green)),
+                .style(Style::default().fg(theme.border_focused))
+                .border_style(theme.border(true)),
             area,
         );
         frame.render_widget(ssid_msg, ssid_msg_area);
@@ -216,43 +192,29 @@ impl AccessPoint {
     }
 
     pub async fn refresh(&mut self) -> Result<()> {
-        let iwd_access_point = self
-            .session
-            .access_points()
-            .await?
-            .pop()
-            .context("No AP found")?;
-        let iwd_access_point_diagnostic = self.session.access_points_diagnostics().await?.pop();
-
-        self.has_started = iwd_access_point.has_started().await?;
-        self.name = iwd_access_point.name().await?;
-        self.frequency = iwd_access_point.frequency().await?;
-        self.is_scanning = iwd_access_point.is_scanning().await.ok();
-        self.supported_ciphers = iwd_access_point.pairwise_ciphers().await?;
-        self.used_cipher = iwd_access_point.group_cipher().await?;
-
-        if let Some(diagnostic) = iwd_access_point_diagnostic {
-            if let Ok(diagnostic) = diagnostic.get().await {
-                self.connected_devices = diagnostic
-                    .iter()
-                    .map(|v| v["Address"].clone().trim_matches('"').to_string())
-                    .collect();
-            }
-        } else {
-            self.connected_devices = Vec::new()
-        };
+        let access_point = self.backend.access_point(&self.device_name).await?;
+
+        self.has_started = access_point.has_started;
+        self.name = access_point.name;
+        self.frequency = access_point.frequency;
+        self.is_scanning = access_point.is_scanning;
+        self.supported_ciphers = access_point.supported_ciphers;
+        self.used_cipher = access_point.used_cipher;
+
+        self.refresh_connected_devices().await
+    }
+
+    // iwd doesn't emit any signal when a client joins or leaves the access point
+    pub async fn refresh_connected_devices(&mut self) -> Result<()> {
+        if let Ok(connected_devices) = self.backend.access_point_clients(&self.device_name).await {
+            self.connected_devices = connected_devices;
+        }
 
         Ok(())
     }
 
     pub async fn scan(&self, sender: UnboundedSender<Event>) -> Result<()> {
-        let iwd_access_point = self
-            .session
-            .access_points()
-            .await?
-            .pop()
-            .context("No AP found")?;
-        match iwd_access_point.scan().await {
+        match self.backend.scan_access_point(&self.device_name).await {
             Ok(()) => Notification::send(
                 "Start Scanning".to_string(),
                 NotificationLevel::Info,
@@ -265,14 +227,9 @@ impl AccessPoint {
     }
 
     pub async fn start(&self, sender: UnboundedSender<Event>) -> Result<()> {
-        let iwd_access_point = self
-            .session
-            .access_points()
-            .await?
-            .pop()
-            .context("No AP found")?;
-        match iwd_access_point
-            .start(self.ssid.value(), self.psk.value())
+        match self
+            .backend
+            .start_access_point(&self.device_name, self.ssid.value(), self.psk.value())
             .await
         {
             Ok(()) => Notification::send(
@@ -289,13 +246,7 @@ impl AccessPoint {
     }
 
     pub async fn stop(&self, sender: UnboundedSender<Event>) -> Result<()> {
-        let iwd_access_point = self
-            .session
-            .access_points()
-            .await?
-            .pop()
-            .context("No AP found")?;
-        match iwd_access_point.stop().await {
+        match self.backend.stop_access_point(&self.device_name).await {
             Ok(()) => {
                 Notification::send("AP Stopped".to_string(), NotificationLevel::Info, &sender)?
             }
@@ -306,28 +257,31 @@ impl AccessPoint {
     }
 
     pub fn render(
-        &self,
+        &mut self,
         frame: &mut Frame,
         focused_block: FocusedBlock,
         device: &Device,
         config: Arc<Config>,
     ) {
+        let theme = config.theme;
+        let help_height = if is_compact(frame.area()) { 2 } else { 1 };
+
         let (access_point_block, connected_devices_block, device_block, help_block) = {
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints(if !self.connected_devices.is_empty() {
-                    &[
+                    [
                         Constraint::Length(5),
                         Constraint::Fill(1),
                         Constraint::Length(5),
-                        Constraint::Length(1),
+                        Constraint::Length(help_height),
                     ]
                 } else {
-                    &[
+                    [
                         Constraint::Fill(1),
                         Constraint::Length(0),
                         Constraint::Length(5),
-                        Constraint::Length(1),
+                        Constraint::Length(help_height),
                     ]
                 })
                 .margin(1)
@@ -335,6 +289,10 @@ impl AccessPoint {
             (chunks[0], chunks[1], chunks[2], chunks[3])
         };
 
+        self.device_area = device_block;
+        self.access_point_area = access_point_block;
+        self.connected_devices_area = connected_devices_block;
+
         // Device
         let row = Row::new(vec![
             Line::from(device.name.clone()).centered(),
@@ -360,10 +318,10 @@ impl AccessPoint {
             .header({
                 if focused_block == FocusedBlock::Device {
                     Row::new(vec![
-                        Line::from("Name").yellow().centered(),
-                        Line::from("Mode").yellow().centered(),
-                        Line::from("Powered").yellow().centered(),
-                        Line::from("Address").yellow().centered(),
+                        Line::from("Name").fg(theme.header).centered(),
+                        Line::from("Mode").fg(theme.header).centered(),
+                        Line::from("Powered").fg(theme.header).centered(),
+                        Line::from("Address").fg(theme.header).centered(),
                     ])
                     .style(Style::new().bold())
                     .bottom_margin(1)
@@ -389,13 +347,7 @@ impl AccessPoint {
                     })
                     .borders(Borders::ALL)
                     .padding(Padding::horizontal(1))
-                    .border_style({
-                        if focused_block == FocusedBlock::Device {
-                            Style::default().fg(Color::Green)
-                        } else {
-                            Style::default()
-                        }
-                    })
+                    .border_style(theme.border(focused_block == FocusedBlock::Device))
                     .border_type({
                         if focused_block == FocusedBlock::Device {
                             BorderType::Thick
@@ -407,7 +359,7 @@ impl AccessPoint {
             .column_spacing(2)
             .flex(Flex::SpaceAround)
             .row_highlight_style(if focused_block == FocusedBlock::Device {
-                Style::default().bg(Color::DarkGray).fg(Color::White)
+                theme.highlight()
             } else {
                 Style::default()
             });
@@ -463,11 +415,11 @@ impl AccessPoint {
             .header({
                 if focused_block == FocusedBlock::AccessPoint {
                     Row::new(vec![
-                        Line::from("Started").yellow().centered(),
-                        Line::from("SSID").yellow().centered(),
-                        Line::from("Frequency").yellow().centered(),
-                        Line::from("Cipher").yellow().centered(),
-                        Line::from("Scanning").yellow().centered(),
+                        Line::from("Started").fg(theme.header).centered(),
+                        Line::from("SSID").fg(theme.header).centered(),
+                        Line::from("Frequency").fg(theme.header).centered(),
+                        Line::from("Cipher").fg(theme.header).centered(),
+                        Line::from("Scanning").fg(theme.header).centered(),
                     ])
                     .style(Style::new().bold())
                     .bottom_margin(1)
@@ -493,13 +445,7 @@ impl AccessPoint {
                         }
                     })
                     .borders(Borders::ALL)
-                    .border_style({
-                        if focused_block == FocusedBlock::AccessPoint {
-                            Style::default().fg(Color::Green)
-                        } else {
-                            Style::default()
-                        }
-                    })
+                    .border_style(theme.border(focused_block == FocusedBlock::AccessPoint))
                     .border_type({
                         if focused_block == FocusedBlock::AccessPoint {
                             BorderType::Thick
@@ -512,7 +458,7 @@ impl AccessPoint {
             .column_spacing(2)
             .flex(Flex::SpaceAround)
             .row_highlight_style(if focused_block == FocusedBlock::AccessPoint {
-                Style::default().bg(Color::DarkGray).fg(Color::White)
+                theme.highlight()
             } else {
                 Style::default()
             });
@@ -538,13 +484,9 @@ impl AccessPoint {
                         }
                     })
                     .borders(Borders::ALL)
-                    .border_style({
-                        if focused_block == FocusedBlock::AccessPointConnectedDevices {
-                            Style::default().fg(Color::Green)
-                        } else {
-                            Style::default()
-                        }
-                    })
+                    .border_style(
+                        theme.border(focused_block == FocusedBlock::AccessPointConnectedDevices),
+                    )
                     .border_type({
                         if focused_block == FocusedBlock::AccessPointConnectedDevices {
                             BorderType::Thick
@@ -560,42 +502,80 @@ impl AccessPoint {
 
         let help_message = match focused_block {
             FocusedBlock::Device => Line::from(vec![
-                Span::from(config.device.infos.to_string()).bold(),
+                Span::from(config.keymap.key(Action::DeviceInfos)).bold(),
                 Span::from(" Infos"),
                 Span::from(" | "),
-                Span::from(config.device.toggle_power.to_string()).bold(),
+                Span::from(config.keymap.key(Action::TogglePower)).bold(),
                 Span::from(" Toggle Power"),
                 Span::from(" | "),
-                Span::from("ctrl+r").bold(),
+                Span::from(config.keymap.key(Action::SelectDevice)).bold(),
+                Span::from(" Select Device"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::ShowTraffic)).bold(),
+                Span::from(" Traffic"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::SwitchMode)).bold(),
                 Span::from(" Switch Mode"),
                 Span::from(" | "),
-                Span::from("⇄").bold(),
+                Span::from(config.keymap.key(Action::FocusNext)).bold(),
                 Span::from(" Nav"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Help)).bold(),
+                Span::from(" Help"),
+            ]),
+            FocusedBlock::DeviceSelector => Line::from(vec![
+                Span::from(config.keymap.key(Action::Up)).bold(),
+                Span::from("  Up"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Down)).bold(),
+                Span::from("  Down"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Submit)).bold(),
+                Span::from(" Select"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Close)).bold(),
+                Span::from(" Discard"),
             ]),
             FocusedBlock::AdapterInfos | FocusedBlock::AccessPointInput => Line::from(vec![
-                Span::from("󱊷 ").bold(),
+                Span::from(config.keymap.key(Action::Close)).bold(),
                 Span::from(" Discard"),
                 Span::from(" | "),
-                Span::from("⇄").bold(),
+                Span::from(config.keymap.key(Action::NextField)).bold(),
                 Span::from(" Nav"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::PopupHelp)).bold(),
+                Span::from(" Help"),
             ]),
             FocusedBlock::AccessPoint => Line::from(vec![
-                Span::from(config.ap.start.to_string()).bold(),
+                Span::from(config.keymap.key(Action::StartAccessPoint)).bold(),
                 Span::from(" New AP"),
                 Span::from(" | "),
-                Span::from(config.ap.stop.to_string()).bold(),
+                Span::from(config.keymap.key(Action::StopAccessPoint)).bold(),
                 Span::from(" Stop AP"),
                 Span::from(" | "),
-                Span::from("ctrl+r").bold(),
+                Span::from(config.keymap.key(Action::SwitchMode)).bold(),
                 Span::from(" Switch Mode"),
                 Span::from(" | "),
-                Span::from("⇄").bold(),
+                Span::from(config.keymap.key(Action::FocusNext)).bold(),
                 Span::from(" Nav"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Help)).bold(),
+                Span::from(" Help"),
+            ]),
+            FocusedBlock::Traffic => Line::from(vec![
+                Span::from(config.keymap.key(Action::Close)).bold(),
+                Span::from(" Close"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::PopupHelp)).bold(),
+                Span::from(" Help"),
             ]),
             _ => Line::from(""),
         };
 
-        let help_message = help_message.centered().blue();
+        let help_message = Paragraph::new(help_message)
+            .centered()
+            .wrap(Wrap { trim: true })
+            .fg(theme.help);
         frame.render_widget(help_message, help_block);
     }
 }
@@ -0,0 +1,549 @@
+use anyhow::{Context, Result};
+use std::{sync::Arc, time::Instant};
+
+use tokio::sync::mpsc::UnboundedSender;
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Flex, Layout, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Padding, Paragraph, Row, Table, TableState, Wrap},
+};
+
+use crate::{
+    app::FocusedBlock,
+    backend::{P2pPeerInfo, WifiBackend, WpsError},
+    config::Config,
+    device::Device,
+    event::Event,
+    keymap::Action,
+    layout::is_compact,
+    mode::station::{
+        network::signal_percentage,
+        wps::{Wps, WpsMethod},
+    },
+    notification::{Notification, NotificationLevel},
+};
+
+/// The Wi-Fi Direct view: the peers found around, and the connection to one of them.
+#[derive(Debug, Clone)]
+pub struct P2p {
+    backend: Arc<dyn WifiBackend>,
+    pub device_name: String,
+    /// The name shown to the peers
+    pub name: String,
+    pub available_connections: u16,
+    /// The discovered peers with their signal strength, strongest first
+    pub peers: Vec<(P2pPeerInfo, i16)>,
+    pub peers_state: TableState,
+    /// The connection being made with a peer, by push button or PIN
+    pub wps: Option<Wps>,
+    /// Where the blocks were last drawn, to find the one under the mouse
+    pub device_area: Rect,
+    pub peers_area: Rect,
+}
+
+impl P2p {
+    /// Enables the P2P device of the adapter if needed, and starts looking for peers.
+    pub async fn new(backend: Arc<dyn WifiBackend>, device_name: String) -> Result<Self> {
+        let info = backend.p2p_device(&device_name).await?.with_context(|| {
+            format!(
+                "The adapter of {} does not support Wi-Fi Direct",
+                device_name
+            )
+        })?;
+        if !info.is_enabled {
+            backend.set_p2p_enabled(&device_name, true).await?;
+        }
+        backend.start_p2p_discovery(&device_name).await?;
+
+        let mut p2p = Self {
+            backend,
+            device_name,
+            name: info.name,
+            available_connections: info.available_connections,
+            peers: Vec::new(),
+            peers_state: TableState::default(),
+            wps: None,
+            device_area: Rect::default(),
+            peers_area: Rect::default(),
+        };
+        p2p.refresh().await?;
+
+        Ok(p2p)
+    }
+
+    // The monitor does not follow the peers, they are polled on every tick
+    pub async fn refresh(&mut self) -> Result<()> {
+        if let Some(info) = self.backend.p2p_device(&self.device_name).await? {
+            self.name = info.name;
+            self.available_connections = info.available_connections;
+        }
+
+        let selected = self.selected_peer().map(|peer| peer.address.clone());
+        self.peers = self.backend.p2p_peers(&self.device_name).await?;
+
+        // The order follows the signals, the selection stays on the same peer
+        let index = selected
+            .and_then(|address| {
+                self.peers
+                    .iter()
+                    .position(|(peer, _)| peer.address == address)
+            })
+            .or(if self.peers.is_empty() { None } else { Some(0) });
+        self.peers_state.select(index);
+
+        Ok(())
+    }
+
+    pub fn selected_peer(&self) -> Option<&P2pPeerInfo> {
+        self.peers_state
+            .selected()
+            .and_then(|index| self.peers.get(index))
+            .map(|(peer, _)| peer)
+    }
+
+    pub fn connected_peer(&self) -> Option<&P2pPeerInfo> {
+        self.peers
+            .iter()
+            .map(|(peer, _)| peer)
+            .find(|peer| peer.is_connected)
+    }
+
+    /// Lets the adapter stop looking for peers once the view is left.
+    pub async fn stop_discovery(&self) {
+        let _ = self.backend.stop_p2p_discovery(&self.device_name).await;
+    }
+
+    pub async fn start_wps(&mut self, sender: UnboundedSender<Event>) -> Result<()> {
+        let Some(wps) = &mut self.wps else {
+            return Ok(());
+        };
+        let Some(peer) = wps.peer.clone() else {
+            return Ok(());
+        };
+        if wps.started_at.is_some() {
+            return Ok(());
+        }
+
+        if wps.method == WpsMethod::Pin {
+            match self
+                .backend
+                .p2p_generate_pin(&self.device_name, &peer.address)
+                .await
+            {
+                Ok(pin) => wps.pin = Some(pin),
+                Err(e) => {
+                    Notification::send(e.to_string(), NotificationLevel::Error, &sender)?;
+                    return Ok(());
+                }
+            }
+        }
+        wps.started_at = Some(Instant::now());
+
+        tokio::spawn({
+            let backend = self.backend.clone();
+            let device_name = self.device_name.clone();
+            let pin = wps.pin.clone();
+            async move {
+                let result = match pin {
+                    Some(pin) => {
+                        backend
+                            .p2p_start_pin(&device_name, &peer.address, &pin)
+                            .await
+                    }
+                    None => backend.p2p_push_button(&device_name, &peer.address).await,
+                };
+                let _ = match result {
+                    Ok(()) => Notification::send(
+                        format!("Connected to {}", peer.name),
+                        NotificationLevel::Info,
+                        &sender,
+                    ),
+                    Err(e) => match e.downcast_ref::<WpsError>() {
+                        Some(WpsError::Canceled) => Notification::send(
+                            "Connection canceled".to_string(),
+                            NotificationLevel::Info,
+                            &sender,
+                        ),
+                        Some(WpsError::Timeout) => Notification::send(
+                            format!("{} did not accept the connection in time", peer.name),
+                            NotificationLevel::Error,
+                            &sender,
+                        ),
+                        _ => Notification::send(e.to_string(), NotificationLevel::Error, &sender),
+                    },
+                };
+                let _ = sender.send(Event::WpsEnded);
+            }
+        });
+
+        Ok(())
+    }
+
+    pub async fn cancel_wps(&mut self) {
+        if let Some(wps) = self.wps.take()
+            && wps.started_at.is_some()
+            && let Some(peer) = wps.peer
+        {
+            // The connection may be over already
+            let _ = self
+                .backend
+                .p2p_cancel(&self.device_name, &peer.address)
+                .await;
+        }
+    }
+
+    pub async fn disconnect(
+        &self,
+        peer: &P2pPeerInfo,
+        sender: UnboundedSender<Event>,
+    ) -> Result<()> {
+        match self
+            .backend
+            .p2p_disconnect(&self.device_name, &peer.address)
+            .await
+        {
+            Ok(()) => Notification::send(
+                format!("Disconnected from {}", peer.name),
+                NotificationLevel::Info,
+                &sender,
+            )?,
+            Err(e) => Notification::send(e.to_string(), NotificationLevel::Error, &sender)?,
+        }
+        Ok(())
+    }
+
+    pub fn render(
+        &mut self,
+        frame: &mut Frame,
+        focused_block: FocusedBlock,
+        device: &Device,
+        config: Arc<Config>,
+    ) {
+        let theme = config.theme;
+        let help_height = if is_compact(frame.area()) { 2 } else { 1 };
+
+        let [p2p_block, peers_block, device_block, help_block] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(5),
+                Constraint::Fill(1),
+                Constraint::Length(5),
+                Constraint::Length(help_height),
+            ])
+            .margin(1)
+            .areas(frame.area());
+
+        self.device_area = device_block;
+        self.peers_area = peers_block;
+
+        //
+        // Wi-Fi Direct
+        //
+        let connected_peer = self.connected_peer();
+        let row = Row::new(vec![
+            Line::from(self.name.clone()).centered(),
+            Line::from(
+                connected_peer
+                    .map(|peer| peer.name.clone())
+                    .unwrap_or("-".to_string()),
+            )
+            .centered(),
+            Line::from(
+                connected_peer
+                    .and_then(|peer| peer.interface.clone())
+                    .unwrap_or("-".to_string()),
+            )
+            .centered(),
+            Line::from(
+                connected_peer
+                    .and_then(|peer| peer.ip.clone())
+                    .unwrap_or("-".to_string()),
+            )
+            .centered(),
+            Line::from(self.available_connections.to_string()).centered(),
+        ]);
+
+        let widths = [
+            Constraint::Length(20),
+            Constraint::Length(20),
+            Constraint::Length(14),
+            Constraint::Length(15),
+            Constraint::Length(9),
+        ];
+
+        let p2p_table = Table::new(vec![row], widths)
+            .header(
+                Row::new(vec![
+                    Line::from("Name").centered(),
+                    Line::from("Connected To").centered(),
+                    Line::from("Interface").centered(),
+                    Line::from("Peer IP").centered(),
+                    Line::from("Available").centered(),
+                ])
+                .bottom_margin(1),
+            )
+            .block(
+                Block::default()
+                    .title(" Wi-Fi Direct ")
+                    .borders(Borders::ALL)
+                    .border_style(theme.border(false))
+                    .padding(Padding::horizontal(1)),
+            )
+            .column_spacing(2)
+            .flex(Flex::SpaceAround);
+
+        frame.render_widget(p2p_table, p2p_block);
+
+        //
+        // Peers
+        //
+        let rows: Vec<Row> = self
+            .peers
+            .iter()
+            .map(|(peer, signal)| {
+                let category = match &peer.subcategory {
+                    Some(subcategory) => format!("{} ({})", peer.category, subcategory),
+                    None => peer.category.clone(),
+                };
+                Row::new(vec![
+                    Line::from(if peer.is_connected {
+                        config.icons.connected()
+                    } else {
+                        ""
+                    })
+                    .centered(),
+                    Line::from(peer.name.clone()).centered(),
+                    Line::from(category).centered(),
+                    Line::from({
+                        let signal = signal_percentage(*signal);
+                        format!("{signal:3}% {}", config.icons.signal(signal))
+                    })
+                    .centered(),
+                ])
+            })
+            .collect();
+
+        // The percentage followed by the signal icon
+        let signal_width = 5 + config.icons.signal(100).chars().count() as u16;
+
+        let widths = [
+            Constraint::Length(2),
+            Constraint::Length(25),
+            Constraint::Length(32),
+            Constraint::Length(signal_width.max(8)),
+        ];
+
+        let peers_table = Table::new(rows, widths)
+            .header({
+                if focused_block == FocusedBlock::P2pPeers {
+                    Row::new(vec![
+                        Line::from(""),
+                        Line::from("Name").fg(theme.header).centered(),
+                        Line::from("Category").fg(theme.header).centered(),
+                        Line::from("Signal").fg(theme.header).centered(),
+                    ])
+                    .style(Style::new().bold())
+                    .bottom_margin(1)
+                } else {
+                    Row::new(vec![
+                        Line::from(""),
+                        Line::from("Name").centered(),
+                        Line::from("Category").centered(),
+                        Line::from("Signal").centered(),
+                    ])
+                    .bottom_margin(1)
+                }
+            })
+            .block(
+                Block::default()
+                    .title(format!(" Peers ({}) ", self.peers.len()))
+                    .title_style({
+                        if focused_block == FocusedBlock::P2pPeers {
+                            Style::default().bold()
+                        } else {
+                            Style::default()
+                        }
+                    })
+                    .borders(Borders::ALL)
+                    .border_style(theme.border(focused_block == FocusedBlock::P2pPeers))
+                    .border_type({
+                        if focused_block == FocusedBlock::P2pPeers {
+                            BorderType::Thick
+                        } else {
+                            BorderType::default()
+                        }
+                    })
+                    .padding(Padding::horizontal(1)),
+            )
+            .column_spacing(1)
+            .flex(Flex::SpaceAround)
+            .row_highlight_style(if focused_block == FocusedBlock::P2pPeers {
+                theme.highlight()
+            } else {
+                Style::default()
+            });
+
+        frame.render_stateful_widget(peers_table, peers_block, &mut self.peers_state);
+
+        //
+        // Device
+        //
+        let row = Row::new(vec![
+            Line::from(device.name.clone()).centered(),
+            Line::from("Wi-Fi Direct").centered(),
+            Line::from(if device.is_powered { "On" } else { "Off" }).centered(),
+            Line::from(device.address.clone()).centered(),
+        ]);
+
+        let widths = [
+            Constraint::Length(15),
+            Constraint::Length(12),
+            Constraint::Length(7),
+            Constraint::Length(17),
+        ];
+
+        let device_table = Table::new(vec![row], widths)
+            .header({
+                if focused_block == FocusedBlock::Device {
+                    Row::new(vec![
+                        Line::from("Name").fg(theme.header).centered(),
+                        Line::from("Mode").fg(theme.header).centered(),
+                        Line::from("Powered").fg(theme.header).centered(),
+                        Line::from("Address").fg(theme.header).centered(),
+                    ])
+                    .style(Style::new().bold())
+                    .bottom_margin(1)
+                } else {
+                    Row::new(vec![
+                        Line::from("Name").centered(),
+                        Line::from("Mode").centered(),
+                        Line::from("Powered").centered(),
+                        Line::from("Address").centered(),
+                    ])
+                    .bottom_margin(1)
+                }
+            })
+            .block(
+                Block::default()
+                    .title(" Device ")
+                    .title_style({
+                        if focused_block == FocusedBlock::Device {
+                            Style::default().bold()
+                        } else {
+                            Style::default()
+                        }
+                    })
+                    .borders(Borders::ALL)
+                    .padding(Padding::horizontal(1))
+                    .border_style(theme.border(focused_block == FocusedBlock::Device))
+                    .border_type({
+                        if focused_block == FocusedBlock::Device {
+                            BorderType::Thick
+                        } else {
+                            BorderType::default()
+                        }
+                    }),
+            )
+            .column_spacing(2)
+            .flex(Flex::SpaceAround)
+            .row_highlight_style(if focused_block == FocusedBlock::Device {
+                theme.highlight()
+            } else {
+                Style::default()
+            });
+
+        let mut device_state = TableState::default().with_selected(0);
+        frame.render_stateful_widget(device_table, device_block, &mut device_state);
+
+        let help_message = match focused_block {
+            FocusedBlock::Device => Line::from(vec![
+                Span::from(config.keymap.key(Action::DeviceInfos)).bold(),
+                Span::from(" Infos"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::TogglePower)).bold(),
+                Span::from(" Toggle Power"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::SelectDevice)).bold(),
+                Span::from(" Select Device"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::ShowTraffic)).bold(),
+                Span::from(" Traffic"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::SwitchMode)).bold(),
+                Span::from(" Switch Mode"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::FocusNext)).bold(),
+                Span::from(" Nav"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Help)).bold(),
+                Span::from(" Help"),
+            ]),
+            FocusedBlock::P2pPeers => Line::from(vec![
+                Span::from(config.keymap.key(Action::Up)).bold(),
+                Span::from("  Up"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Down)).bold(),
+                Span::from("  Down"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::ConnectPeer)).bold(),
+                Span::from(" Dis/Connect"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::SwitchMode)).bold(),
+                Span::from(" Switch Mode"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::FocusNext)).bold(),
+                Span::from(" Nav"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Help)).bold(),
+                Span::from(" Help"),
+            ]),
+            FocusedBlock::Wps => Line::from(vec![
+                Span::from(config.keymap.key(Action::Up)).bold(),
+                Span::from("  Up"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Down)).bold(),
+                Span::from("  Down"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Submit)).bold(),
+                Span::from(" Start"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Close)).bold(),
+                Span::from(" Cancel"),
+            ]),
+            FocusedBlock::DeviceSelector => Line::from(vec![
+                Span::from(config.keymap.key(Action::Up)).bold(),
+                Span::from("  Up"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Down)).bold(),
+                Span::from("  Down"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Submit)).bold(),
+                Span::from(" Select"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Close)).bold(),
+                Span::from(" Discard"),
+            ]),
+            FocusedBlock::AdapterInfos | FocusedBlock::Traffic => Line::from(vec![
+                Span::from(config.keymap.key(Action::Close)).bold(),
+                Span::from(" Close"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::PopupHelp)).bold(),
+                Span::from(" Help"),
+            ]),
+            _ => Line::from(""),
+        };
+
+        let help_message = Paragraph::new(help_message)
+            .centered()
+            .wrap(Wrap { trim: true })
+            .fg(theme.help);
+        frame.render_widget(help_message, help_block);
+
+        if let Some(wps) = &self.wps {
+            wps.render(frame, &theme);
+        }
+    }
+}
@@ -1,42 +1,59 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 pub mod auth;
+pub mod details;
+pub mod dpp;
+pub mod filter;
 pub mod hidden_network;
 pub mod known_network;
+pub mod link_quality;
 pub mod network;
 pub mod share;
+pub mod sort;
+pub mod wps;
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 
-use futures::future::join_all;
 use iwdrs::{
     error::{IWDError, station::ScanError},
     hidden_network::HiddenNetwork,
-    session::Session,
     station::{State, diagnostics::ActiveStationDiagnostics},
 };
 use ratatui::{
     Frame,
-    layout::{Constraint, Direction, Flex, Layout},
-    style::{Color, Style, Stylize},
+    layout::{Constraint, Direction, Flex, Layout, Rect},
+    style::{Style, Stylize},
     text::{Line, Span},
-    widgets::{Block, BorderType, Borders, Padding, Paragraph, Row, Table, TableState},
+    widgets::{Block, BorderType, Borders, Padding, Paragraph, Row, Table, TableState, Wrap},
 };
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::{
     app::FocusedBlock,
+    backend::{DeveloperModeError, DppRole, SignalLevel, WifiBackend, WpsError},
     config::Config,
     device::Device,
     event::Event,
-    mode::station::{known_network::KnownNetwork, share::Share},
+    keymap::Action,
+    layout::is_compact,
+    mode::station::{
+        details::NetworkDetails,
+        dpp::Dpp,
+        filter::Filter,
+        known_network::KnownNetwork,
+        link_quality::LinkQuality,
+        share::Share,
+        sort::{SortKey, sort_hidden_networks, sort_known_networks, sort_networks},
+        wps::{Wps, WpsMethod},
+    },
     notification::{Notification, NotificationLevel},
 };
 
-use network::Network;
+use network::{Network, signal_percentage};
 
 #[derive(Clone)]
 pub struct Station {
-    pub session: Arc<Session>,
+    backend: Arc<dyn WifiBackend>,
+    pub device_name: String,
     pub state: State,
     pub is_scanning: bool,
     pub connected_network: Option<Network>,
@@ -47,84 +64,85 @@ pub struct Station {
     pub known_networks_state: TableState,
     pub new_networks_state: TableState,
     pub diagnostic: Option<ActiveStationDiagnostics>,
+    pub link_quality: LinkQuality,
+    /// The level of the signal of the connection, given by the signal level agent
+    pub signal_level: Option<SignalLevel>,
+    /// Whether iwd sends the signal levels of this station
+    pub has_signal_level_agent: bool,
+    /// Why the signal level agent could not be registered, to report it only once
+    pub signal_level_agent_error: Option<String>,
     pub show_unavailable_known_networks: bool,
     pub show_hidden_networks: bool,
     pub share: Option<Share>,
+    pub dpp: Option<Dpp>,
+    pub wps: Option<Wps>,
+    pub details: Option<NetworkDetails>,
     pub connct_hidden_network: Option<hidden_network::ConnectHiddenNetwork>,
+    pub filter: Filter,
+    pub known_networks_sort: SortKey,
+    pub new_networks_sort: SortKey,
+    /// Where the blocks were last drawn, to find the one under the mouse
+    pub device_area: Rect,
+    pub known_networks_area: Rect,
+    pub new_networks_area: Rect,
 }
 
 impl Station {
-    pub async fn new(session: Arc<Session>) -> Result<Self> {
-        let iwd_station = session
-            .stations()
+    pub async fn new(
+        backend: Arc<dyn WifiBackend>,
+        device_name: String,
+        config: &Config,
+    ) -> Result<Self> {
+        let station = backend.station(&device_name).await?;
+
+        let connected_network = station
+            .connected_network
+            .map(|net| Network::new(backend.clone(), device_name.clone(), net));
+
+        let networks: Vec<(Network, i16)> = backend
+            .networks(&device_name)
             .await?
-            .pop()
-            .context("no station found")?;
-
-        let iwd_station_diagnostic = session.stations_diagnostics().await?.pop();
-
-        let state = iwd_station.state().await?;
-        let connected_network = {
-            if let Some(n) = iwd_station.connected_network().await? {
-                let network = Network::new(n.clone()).await?;
-                Some(network)
-            } else {
-                None
-            }
-        };
+            .into_iter()
+            .map(|(net, signal)| {
+                (
+                    Network::new(backend.clone(), device_name.clone(), net),
+                    signal,
+                )
+            })
+            .collect();
 
-        let is_scanning = iwd_station.is_scanning().await?;
-        let discovered_networks = iwd_station.discovered_networks().await?;
-        let networks = {
-            let collected_futures = discovered_networks
-                .iter()
-                .map(|(n, signal)| async {
-                    match Network::new(n.clone()).await {
-                        Ok(network) => Ok((network, signal.to_owned())),
-                        Err(e) => Err(e),
-                    }
-                })
-                .collect::<Vec<_>>();
-            let results = join_all(collected_futures).await;
-            results
-                .into_iter()
-                .filter_map(Result::ok)
-                .collect::<Vec<(Network, i16)>>()
-        };
+        let known_networks_sort = config.station.known_network.sort;
+        let new_networks_sort = config.station.new_network.sort;
 
-        let new_networks: Vec<(Network, i16)> = networks
+        let mut new_networks: Vec<(Network, i16)> = networks
             .clone()
             .into_iter()
             .filter(|(net, _signal)| net.known_network.is_none())
             .collect();
+        sort_networks(&mut new_networks, new_networks_sort);
 
-        let new_hidden_networks = iwd_station
-            .get_hidden_networks()
+        let mut new_hidden_networks = backend
+            .hidden_networks(&device_name)
             .await
             .unwrap_or(Vec::new());
+        sort_hidden_networks(&mut new_hidden_networks, new_networks_sort);
 
-        let known_networks: Vec<(Network, i16)> = networks
+        let mut known_networks: Vec<(Network, i16)> = networks
             .into_iter()
             .filter(|(net, _signal)| net.known_network.is_some())
             .collect();
+        sort_networks(&mut known_networks, known_networks_sort);
 
         let available_networks_names: Vec<String> =
             known_networks.iter().map(|(n, _)| n.name.clone()).collect();
 
-        let unavailable_known_networks =
-            if let Ok(iwd_known_networks) = session.known_networks().await {
-                let mut unavailable_known_networks = Vec::new();
-                for iwd_network in iwd_known_networks {
-                    if let Ok(known_network) = KnownNetwork::new(iwd_network).await
-                        && !available_networks_names.contains(&known_network.name)
-                    {
-                        unavailable_known_networks.push(known_network);
-                    }
-                }
-                unavailable_known_networks
-            } else {
-                Vec::new()
-            };
+        let mut unavailable_known_networks: Vec<KnownNetwork> = KnownNetwork::all(backend.clone())
+            .await
+            .unwrap_or(Vec::new())
+            .into_iter()
+            .filter(|known_network| !available_networks_names.contains(&known_network.name))
+            .collect();
+        sort_known_networks(&mut unavailable_known_networks, known_networks_sort);
 
         let mut new_networks_state = TableState::default();
         if new_networks.is_empty() {
@@ -141,16 +159,13 @@ impl Station {
             known_networks_state.select(Some(0));
         }
 
-        let diagnostic = if let Some(diagnostic) = iwd_station_diagnostic {
-            diagnostic.get().await.ok()
-        } else {
-            None
-        };
+        let diagnostic = backend.diagnostic(&device_name).await.ok().flatten();
 
         Ok(Self {
-            session,
-            state,
-            is_scanning,
+            backend,
+            device_name,
+            state: station.state,
+            is_scanning: station.is_scanning,
             connected_network,
             new_networks,
             new_hidden_networks,
@@ -159,120 +174,131 @@ impl Station {
             known_networks_state,
             new_networks_state,
             diagnostic,
+            link_quality: LinkQuality::default(),
+            signal_level: None,
+            has_signal_level_agent: false,
+            signal_level_agent_error: None,
             show_unavailable_known_networks: false,
             show_hidden_networks: false,
             share: None,
+            dpp: None,
+            wps: None,
+            details: None,
             connct_hidden_network: None,
+            filter: Filter::default(),
+            known_networks_sort,
+            new_networks_sort,
+            device_area: Rect::default(),
+            known_networks_area: Rect::default(),
+            new_networks_area: Rect::default(),
         })
     }
 
     pub async fn connect_hidden_network(&self, ssid: String) -> Result<()> {
-        let iwd_station = self
-            .session
-            .stations()
-            .await?
-            .pop()
-            .context("No station found")?;
-        iwd_station.connect_hidden_network(ssid).await?;
-        Ok(())
+        self.backend
+            .connect_hidden_network(&self.device_name, &ssid)
+            .await
     }
 
     pub async fn refresh(&mut self) -> Result<()> {
-        let iwd_station = self
-            .session
-            .stations()
-            .await?
-            .pop()
-            .context("No station found")?;
+        self.refresh_state().await?;
+        self.refresh_networks().await
+    }
 
-        self.state = iwd_station.state().await?;
-        self.is_scanning = iwd_station.is_scanning().await?;
+    /// Refreshes the state of the station and of its connection.
+    pub async fn refresh_state(&mut self) -> Result<()> {
+        let station = self.backend.station(&self.device_name).await?;
 
-        let connected_network = {
-            if let Some(n) = iwd_station.connected_network().await? {
-                let network = Network::new(n.clone()).await?;
-                Some(network)
-            } else {
-                None
-            }
-        };
+        self.state = station.state;
+        self.is_scanning = station.is_scanning;
+        self.connected_network = station
+            .connected_network
+            .map(|net| Network::new(self.backend.clone(), self.device_name.clone(), net));
 
-        let discovered_networks = iwd_station.discovered_networks().await?;
-        let networks = {
-            let collected_futures = discovered_networks
-                .iter()
-                .map(|(n, signal)| async {
-                    match Network::new(n.clone()).await {
-                        Ok(network) => Ok((network, signal.to_owned())),
-                        Err(e) => Err(e),
-                    }
-                })
-                .collect::<Vec<_>>();
-            let results = join_all(collected_futures).await;
-            results
-                .into_iter()
-                .filter_map(Result::ok)
-                .collect::<Vec<(Network, i16)>>()
+        self.refresh_diagnostic().await;
+        if let Some(details) = &mut self.details {
+            details.refresh_connection(self.connected_network.as_ref(), self.diagnostic.as_ref());
+        }
+
+        Ok(())
+    }
+
+    pub async fn refresh_diagnostic(&mut self) {
+        self.diagnostic = self
+            .backend
+            .diagnostic(&self.device_name)
+            .await
+            .ok()
+            .flatten();
+    }
+
+    /// Records the link every tick, the diagnostics do not send any change.
+    pub async fn sample_link_quality(&mut self) {
+        if self.state == State::Connected {
+            self.refresh_diagnostic().await;
+        } else {
+            self.diagnostic = None;
+        }
+        self.link_quality.push(self.diagnostic.as_ref());
+    }
+
+    /// Updates the signal of the connected network from the diagnostics, without waiting for the
+    /// networks to be refreshed.
+    pub async fn refresh_connected_signal(&mut self) {
+        self.refresh_diagnostic().await;
+        let Some(rssi) = self
+            .diagnostic
+            .as_ref()
+            .and_then(|diagnostic| diagnostic.rssi)
+        else {
+            return;
         };
 
-        let new_networks: Vec<(Network, i16)> = networks
+        let selected_networks = self.selected_networks();
+        for (net, signal) in self
+            .known_networks
+            .iter_mut()
+            .chain(self.new_networks.iter_mut())
+        {
+            if net.is_connected {
+                *signal = rssi * 100;
+            }
+        }
+        self.sort_networks();
+        self.select_networks(selected_networks);
+    }
+
+    /// Refreshes the discovered, hidden and known networks.
+    pub async fn refresh_networks(&mut self) -> Result<()> {
+        let selected_networks = self.selected_networks();
+
+        let networks: Vec<(Network, i16)> = self
+            .backend
+            .networks(&self.device_name)
+            .await?
+            .into_iter()
+            .map(|(net, signal)| {
+                (
+                    Network::new(self.backend.clone(), self.device_name.clone(), net),
+                    signal,
+                )
+            })
+            .collect();
+
+        self.new_networks = networks
             .clone()
             .into_iter()
             .filter(|(net, _signal)| net.known_network.is_none())
             .collect();
 
-        let known_networks: Vec<(Network, i16)> = networks
+        self.known_networks = networks
             .into_iter()
             .filter(|(net, _signal)| net.known_network.is_some())
             .collect();
 
-        if self.new_networks.len() == new_networks.len() {
-            self.new_networks.iter_mut().for_each(|(net, signal)| {
-                let n = new_networks
-                    .iter()
-                    .find(|(refreshed_net, _signal)| refreshed_net.name == net.name);
-
-                if let Some((_, refreshed_signal)) = n {
-                    *signal = *refreshed_signal;
-                }
-            });
-        } else {
-            let mut new_networks_state = TableState::default();
-            if new_networks.is_empty() {
-                new_networks_state.select(None);
-            } else {
-                new_networks_state.select(Some(0));
-            }
-
-            self.new_networks_state = new_networks_state;
-            self.new_networks = new_networks;
-        }
-
-        if self.known_networks.len() == known_networks.len() {
-            self.known_networks.iter_mut().for_each(|(net, signal)| {
-                let n = known_networks
-                    .iter()
-                    .find(|(refreshed_net, _signal)| refreshed_net.name == net.name);
-
-                if let Some((refreshed_net, refreshed_signal)) = n {
-                    net.known_network.as_mut().unwrap().is_autoconnect =
-                        refreshed_net.known_network.as_ref().unwrap().is_autoconnect;
-                    *signal = *refreshed_signal;
-                }
-            });
-        } else {
-            let mut known_networks_state = TableState::default();
-            if known_networks.is_empty() {
-                known_networks_state.select(None);
-            } else {
-                known_networks_state.select(Some(0));
-            }
-            self.known_networks_state = known_networks_state;
-            self.known_networks = known_networks;
-        }
-
-        self.new_hidden_networks = iwd_station
-            .get_hidden_networks()
+        self.new_hidden_networks = self
+            .backend
+            .hidden_networks(&self.device_name)
             .await
             .unwrap_or(Vec::new());
 
@@ -282,54 +308,304 @@ impl Station {
             .map(|(n, _)| n.name.clone())
             .collect();
 
-        let unavailable_known_networks =
-            if let Ok(iwd_known_networks) = self.session.known_networks().await {
-                let mut unavailable_known_networks = Vec::new();
-                for iwd_network in iwd_known_networks {
-                    if let Ok(known_network) = KnownNetwork::new(iwd_network).await
-                        && !available_networks_names.contains(&known_network.name)
-                    {
-                        unavailable_known_networks.push(known_network);
-                    }
-                }
-                unavailable_known_networks
-            } else {
-                Vec::new()
-            };
+        let unavailable_known_networks = KnownNetwork::all(self.backend.clone())
+            .await
+            .unwrap_or(Vec::new())
+            .into_iter()
+            .filter(|known_network| !available_networks_names.contains(&known_network.name))
+            .collect();
 
         self.unavailable_known_networks = unavailable_known_networks;
 
-        self.connected_network = connected_network;
+        self.sort_networks();
+        self.select_networks(selected_networks);
 
-        let iwd_station_diagnostic = self.session.stations_diagnostics().await.unwrap().pop();
-        self.diagnostic = if let Some(diagnostic) = iwd_station_diagnostic {
-            diagnostic.get().await.ok()
-        } else {
-            None
+        Ok(())
+    }
+
+    /// Selects the next sort key of the known networks, the selected network stays selected.
+    pub fn cycle_known_networks_sort(&mut self) {
+        let selected_networks = self.selected_networks();
+        self.known_networks_sort = self.known_networks_sort.next();
+        self.sort_networks();
+        self.select_networks(selected_networks);
+    }
+
+    /// Selects the next sort key of the new networks, the selected network stays selected.
+    pub fn cycle_new_networks_sort(&mut self) {
+        let selected_networks = self.selected_networks();
+        self.new_networks_sort = self.new_networks_sort.next();
+        self.sort_networks();
+        self.select_networks(selected_networks);
+    }
+
+    fn sort_networks(&mut self) {
+        sort_networks(&mut self.known_networks, self.known_networks_sort);
+        sort_known_networks(
+            &mut self.unavailable_known_networks,
+            self.known_networks_sort,
+        );
+        sort_networks(&mut self.new_networks, self.new_networks_sort);
+        sort_hidden_networks(&mut self.new_hidden_networks, self.new_networks_sort);
+    }
+
+    /// Names of the selected known and new networks, to select them again once the lists have
+    /// changed.
+    fn selected_networks(&self) -> (Option<String>, Option<String>) {
+        (
+            self.known_networks_state
+                .selected()
+                .and_then(|index| self.known_network_name(index)),
+            self.new_networks_state
+                .selected()
+                .and_then(|index| self.new_network_name(index)),
+        )
+    }
+
+    /// Selects the networks by name, or the first row when they are gone.
+    fn select_networks(&mut self, (known_network, new_network): (Option<String>, Option<String>)) {
+        let known_networks_rows = self.known_networks_rows();
+        let index = known_networks_rows
+            .iter()
+            .find(|index| self.known_network_name(**index) == known_network)
+            .or(known_networks_rows.first())
+            .copied();
+        self.known_networks_state.select(index);
+
+        let new_networks_rows = self.new_networks_rows();
+        let index = new_networks_rows
+            .iter()
+            .find(|index| self.new_network_name(**index) == new_network)
+            .or(new_networks_rows.first())
+            .copied();
+        self.new_networks_state.select(index);
+    }
+
+    /// Details of the known network at the index, the unavailable ones have no access point.
+    pub async fn known_network_details(&self, index: usize) -> Option<NetworkDetails> {
+        let details = match self.known_networks.get(index) {
+            Some((net, signal)) => {
+                Some(NetworkDetails::from_network(net, *signal, self.diagnostic.as_ref()).await)
+            }
+            None => self
+                .unavailable_known_networks
+                .get(index - self.known_networks.len())
+                .map(NetworkDetails::from_known_network),
         };
+        self.with_developer_mode(details).await
+    }
 
+    /// Details of the new network at the index, the hidden ones come after the others.
+    pub async fn new_network_details(&self, index: usize) -> Option<NetworkDetails> {
+        let details = match self.new_networks.get(index) {
+            Some((net, signal)) => {
+                Some(NetworkDetails::from_network(net, *signal, self.diagnostic.as_ref()).await)
+            }
+            None => self
+                .new_hidden_networks
+                .get(index - self.new_networks.len())
+                .map(NetworkDetails::from_hidden_network),
+        };
+        self.with_developer_mode(details).await
+    }
+
+    async fn with_developer_mode(&self, details: Option<NetworkDetails>) -> Option<NetworkDetails> {
+        let mut details = details?;
+        details.is_developer_mode = self
+            .backend
+            .is_developer_mode(&self.device_name)
+            .await
+            .unwrap_or(false);
+        Some(details)
+    }
+
+    /// Connects through the access point selected in the details, or roams to it when its
+    /// network is the connected one.
+    pub fn connect_selected_bss(&self, sender: UnboundedSender<Event>) -> Result<()> {
+        let Some(details) = &self.details else {
+            return Ok(());
+        };
+        // The hidden networks are only known by their access point, there is nothing to pick
+        let (Some(name), Some(bss)) = (details.name.clone(), details.selected_bss()) else {
+            return Ok(());
+        };
+
+        if !details.is_developer_mode {
+            return Notification::send(
+                DeveloperModeError.to_string(),
+                NotificationLevel::Warning,
+                &sender,
+            );
+        }
+        if details
+            .connected_bss
+            .as_ref()
+            .is_some_and(|address| address.eq_ignore_ascii_case(&bss.address))
+        {
+            return Notification::send(
+                format!("Already connected through {}", bss.address),
+                NotificationLevel::Info,
+                &sender,
+            );
+        }
+
+        let backend = self.backend.clone();
+        let device_name = self.device_name.clone();
+        let address = bss.address.clone();
+        let is_roaming = details.is_connected;
+
+        // Connecting waits for the agent when the network asks for a passphrase
+        tokio::spawn(async move {
+            let (result, message) = if is_roaming {
+                (
+                    backend.roam(&device_name, &address).await,
+                    format!("Roamed to {}", address),
+                )
+            } else {
+                (
+                    backend.connect_bss(&device_name, &address).await,
+                    format!("Connected to {} through {}", name, address),
+                )
+            };
+            let _ = match result {
+                Ok(()) => Notification::send(message, NotificationLevel::Info, &sender),
+                Err(e) => Notification::send(e.to_string(), NotificationLevel::Error, &sender),
+            };
+        });
         Ok(())
     }
 
+    /// Name of the known network at the index, the unavailable ones come after the others.
+    fn known_network_name(&self, index: usize) -> Option<String> {
+        match self.known_networks.get(index) {
+            Some((net, _)) => Some(net.name.clone()),
+            None => self
+                .unavailable_known_networks
+                .get(index - self.known_networks.len())
+                .map(|net| net.name.clone()),
+        }
+    }
+
+    /// Name of the new network at the index, the hidden ones come after the others.
+    fn new_network_name(&self, index: usize) -> Option<String> {
+        match self.new_networks.get(index) {
+            Some((net, _)) => Some(net.name.clone()),
+            None => self
+                .new_hidden_networks
+                .get(index - self.new_networks.len())
+                .map(|net| net.address.clone()),
+        }
+    }
+
+    /// Indices of the known networks shown in the table, followed by the unavailable ones when
+    /// they are shown.
+    pub fn known_networks_rows(&self) -> Vec<usize> {
+        let available = self.known_networks.iter().map(|(net, signal)| {
+            self.filter.matches(
+                &net.name,
+                net.network_type,
+                Some(signal_percentage(*signal)),
+            )
+        });
+        let unavailable = self
+            .unavailable_known_networks
+            .iter()
+            .filter(|_| self.show_unavailable_known_networks)
+            .map(|net| self.filter.matches(&net.name, net.network_type, None));
+
+        available
+            .chain(unavailable)
+            .enumerate()
+            .filter(|(_, is_shown)| *is_shown)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Indices of the new networks shown in the table, followed by the hidden ones when they
+    /// are shown.
+    pub fn new_networks_rows(&self) -> Vec<usize> {
+        let visible = self.new_networks.iter().map(|(net, signal)| {
+            self.filter.matches(
+                &net.name,
+                net.network_type,
+                Some(signal_percentage(*signal)),
+            )
+        });
+        let hidden = self
+            .new_hidden_networks
+            .iter()
+            .filter(|_| self.show_hidden_networks)
+            .map(|net| {
+                self.filter.matches(
+                    &net.address,
+                    net.network_type,
+                    Some(signal_percentage(net.signal_strength)),
+                )
+            });
+
+        visible
+            .chain(hidden)
+            .enumerate()
+            .filter(|(_, is_shown)| *is_shown)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Selects the first network matching the filter when the selected one does not.
+    pub fn apply_filter(&mut self) {
+        let rows = self.known_networks_rows();
+        if !self
+            .known_networks_state
+            .selected()
+            .is_some_and(|selected| rows.contains(&selected))
+        {
+            self.known_networks_state.select(rows.first().copied());
+        }
+
+        let rows = self.new_networks_rows();
+        if !self
+            .new_networks_state
+            .selected()
+            .is_some_and(|selected| rows.contains(&selected))
+        {
+            self.new_networks_state.select(rows.first().copied());
+        }
+    }
+
+    /// Title of a network table, with the query and the number of matches when filtered.
+    fn title(&self, name: &str, matches: usize, count: usize) -> String {
+        if self.filter.is_active() {
+            format!(
+                " {} /{} ({}/{}) ",
+                name,
+                self.filter.query(),
+                matches,
+                count
+            )
+        } else {
+            format!(" {} ", name)
+        }
+    }
+
+    /// Shown on the right of the title of a network table.
+    fn sort_title(sort: SortKey) -> Line<'static> {
+        Line::from(format!(" Sorted by {} ", sort.label())).right_aligned()
+    }
+
     pub async fn scan(&self, sender: UnboundedSender<Event>) -> Result<()> {
-        let iwd_station = self.session.stations().await.unwrap().pop().unwrap();
-        match iwd_station.scan().await {
+        match self.backend.scan(&self.device_name).await {
             Ok(()) => Notification::send(
                 "Start Scanning".to_string(),
                 NotificationLevel::Info,
                 &sender,
             )?,
-            Err(e) => match e {
-                IWDError::OperationError(e) => match e {
-                    ScanError::Busy => {
-                        Notification::send(e.to_string(), NotificationLevel::Info, &sender.clone())?
-                    }
-                    _ => Notification::send(
-                        e.to_string(),
-                        NotificationLevel::Error,
-                        &sender.clone(),
-                    )?,
-                },
+            Err(e) => match e.downcast_ref::<IWDError<ScanError>>() {
+                Some(IWDError::OperationError(e @ ScanError::Busy)) => {
+                    Notification::send(e.to_string(), NotificationLevel::Info, &sender.clone())?
+                }
+                Some(IWDError::OperationError(e)) => {
+                    Notification::send(e.to_string(), NotificationLevel::Error, &sender.clone())?
+                }
                 _ => Notification::send(e.to_string(), NotificationLevel::Error, &sender.clone())?,
             },
         }
@@ -337,9 +613,116 @@ impl Station {
         Ok(())
     }
 
+    /// Starts a DPP exchange, as an enrollee to receive a network or as a configurator to hand
+    /// over the connected one.
+    pub async fn start_dpp(&mut self, role: DppRole, sender: UnboundedSender<Event>) -> Result<()> {
+        let network_name = match (role, &self.connected_network) {
+            (DppRole::Enrollee, _) => None,
+            (DppRole::Configurator, Some(network)) => Some(network.name.clone()),
+            (DppRole::Configurator, None) => {
+                Notification::send(
+                    "Connect to a network to share it".to_string(),
+                    NotificationLevel::Info,
+                    &sender,
+                )?;
+                return Ok(());
+            }
+        };
+
+        match self
+            .backend
+            .start_dpp(&self.device_name, role)
+            .await
+            .and_then(|uri| Dpp::new(role, uri, network_name))
+        {
+            Ok(dpp) => self.dpp = Some(dpp),
+            Err(e) => Notification::send(
+                format!("Can not start DPP: {}", e),
+                NotificationLevel::Error,
+                &sender,
+            )?,
+        }
+
+        Ok(())
+    }
+
+    pub async fn stop_dpp(&mut self) {
+        if self.dpp.take().is_some() {
+            // iwd may have stopped it already, once the network was handed over
+            let _ = self.backend.stop_dpp(&self.device_name).await;
+        }
+    }
+
+    /// Forgets the DPP exchange once iwd has stopped it, and returns it.
+    pub async fn refresh_dpp(&mut self) -> Option<Dpp> {
+        self.dpp.as_ref()?;
+        match self.backend.dpp(&self.device_name).await {
+            Ok(None) => self.dpp.take(),
+            _ => None,
+        }
+    }
+
+    /// Starts the WPS connection with the chosen method, its result is notified once an access
+    /// point answers or the walk time is over.
+    pub async fn start_wps(&mut self, sender: UnboundedSender<Event>) -> Result<()> {
+        let Some(wps) = &mut self.wps else {
+            return Ok(());
+        };
+        if wps.started_at.is_some() {
+            return Ok(());
+        }
+
+        if wps.method == WpsMethod::Pin {
+            match self.backend.wps_generate_pin(&self.device_name).await {
+                Ok(pin) => wps.pin = Some(pin),
+                Err(e) => {
+                    Notification::send(e.to_string(), NotificationLevel::Error, &sender)?;
+                    return Ok(());
+                }
+            }
+        }
+        wps.started_at = Some(Instant::now());
+
+        tokio::spawn({
+            let backend = self.backend.clone();
+            let device_name = self.device_name.clone();
+            let pin = wps.pin.clone();
+            async move {
+                let result = match pin {
+                    Some(pin) => backend.wps_start_pin(&device_name, &pin).await,
+                    None => backend.wps_push_button(&device_name).await,
+                };
+                let _ = match result {
+                    Ok(()) => Notification::send(
+                        "Connected with WPS".to_string(),
+                        NotificationLevel::Info,
+                        &sender,
+                    ),
+                    Err(e) => match e.downcast_ref::<WpsError>() {
+                        Some(e @ WpsError::Canceled) => {
+                            Notification::send(e.to_string(), NotificationLevel::Info, &sender)
+                        }
+                        _ => Notification::send(e.to_string(), NotificationLevel::Error, &sender),
+                    },
+                };
+                let _ = sender.send(Event::WpsEnded);
+            }
+        });
+
+        Ok(())
+    }
+
+    pub async fn cancel_wps(&mut self) {
+        if let Some(wps) = self.wps.take()
+            && wps.started_at.is_some()
+        {
+            // The connection may be over already
+            let _ = self.backend.wps_cancel(&self.device_name).await;
+        }
+    }
+
     pub async fn disconnect(&self, sender: UnboundedSender<Event>) -> Result<()> {
-        let iwd_station = self.session.stations().await.unwrap().pop().unwrap();
-        match iwd_station.disconnect().await {
+        match self.backend.disconnect(&self.device_name).await {
             Ok(()) => Notification::send(
                 format!(
                     "Disconnected from {}",
@@ -360,6 +743,9 @@ impl Station {
         device: &Device,
         config: Arc<Config>,
     ) {
+        let theme = config.theme;
+        let compact = is_compact(frame.area());
+
         let (known_networks_block, new_networks_block, device_block, help_block) = {
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
@@ -374,6 +760,10 @@ impl Station {
             (chunks[0], chunks[1], chunks[2], chunks[3])
         };
 
+        self.device_area = device_block;
+        self.known_networks_area = known_networks_block;
+        self.new_networks_area = new_networks_block;
+
         //
         // Device
         //
@@ -407,13 +797,14 @@ impl Station {
             .centered(),
         ]);
 
+        // The columns with a zero width are hidden on small terminals
         let widths = [
             Constraint::Length(10),
-            Constraint::Length(8),
+            Constraint::Length(if compact { 0 } else { 8 }),
             Constraint::Length(10),
             Constraint::Length(12),
-            Constraint::Length(10),
-            Constraint::Length(10),
+            Constraint::Length(if compact { 0 } else { 10 }),
+            Constraint::Length(if compact { 0 } else { 10 }),
             Constraint::Length(15),
         ];
 
@@ -421,13 +812,13 @@ impl Station {
             .header({
                 if focused_block == FocusedBlock::Device {
                     Row::new(vec![
-                        Line::from("Name").yellow().centered(),
-                        Line::from("Mode").yellow().centered(),
-                        Line::from("Powered").yellow().centered(),
-                        Line::from("State").yellow().centered(),
-                        Line::from("Scanning").yellow().centered(),
-                        Line::from("Frequency").yellow().centered(),
-                        Line::from("Security").yellow().centered(),
+                        Line::from("Name").fg(theme.header).centered(),
+                        Line::from("Mode").fg(theme.header).centered(),
+                        Line::from("Powered").fg(theme.header).centered(),
+                        Line::from("State").fg(theme.header).centered(),
+                        Line::from("Scanning").fg(theme.header).centered(),
+                        Line::from("Frequency").fg(theme.header).centered(),
+                        Line::from("Security").fg(theme.header).centered(),
                     ])
                     .style(Style::new().bold())
                     .bottom_margin(1)
@@ -455,13 +846,7 @@ impl Station {
                         }
                     })
                     .borders(Borders::ALL)
-                    .border_style({
-                        if focused_block == FocusedBlock::Device {
-                            Style::default().fg(Color::Green)
-                        } else {
-                            Style::default()
-                        }
-                    })
+                    .border_style(theme.border(focused_block == FocusedBlock::Device))
                     .border_type({
                         if focused_block == FocusedBlock::Device {
                             BorderType::Thick
@@ -474,7 +859,7 @@ impl Station {
             .column_spacing(1)
             .flex(Flex::SpaceAround)
             .row_highlight_style(if focused_block == FocusedBlock::Device {
-                Style::default().bg(Color::DarkGray).fg(Color::White)
+                theme.highlight()
             } else {
                 Style::default()
             });
@@ -490,18 +875,12 @@ impl Station {
             .iter()
             .map(|(net, signal)| {
                 let net = net.known_network.as_ref().unwrap();
-                let signal = format!("{}%", {
-                    if *signal / 100 >= -50 {
-                        100
-                    } else {
-                        2 * (100 + signal / 100)
-                    }
-                });
+                let signal = format!("{}%", signal_percentage(*signal));
 
                 if let Some(connected_net) = &self.connected_network {
                     if connected_net.name == net.name {
                         let row = vec![
-                            Line::from("󰖩 ").centered(),
+                            Line::from(config.icons.connected()).centered(),
                             Line::from(net.name.clone()).centered(),
                             Line::from(net.network_type.to_string()).centered(),
                             Line::from(if net.is_hidden { "Yes" } else { "No" }).centered(),
@@ -547,18 +926,27 @@ impl Station {
                     Line::from(""),
                     Line::from(""),
                 ])
-                .fg(Color::DarkGray);
+                .fg(theme.dimmed);
 
                 rows.push(row);
             });
         }
 
+        let known_networks_rows = self.known_networks_rows();
+        let known_networks_count = rows.len();
+        let rows: Vec<Row> = rows
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| known_networks_rows.contains(index))
+            .map(|(_, row)| row)
+            .collect();
+
         let widths = [
             Constraint::Length(2),
             Constraint::Length(25),
             Constraint::Length(8),
-            Constraint::Length(6),
-            Constraint::Length(12),
+            Constraint::Length(if compact { 0 } else { 6 }),
+            Constraint::Length(if compact { 0 } else { 12 }),
             Constraint::Length(6),
         ];
 
@@ -567,11 +955,11 @@ impl Station {
                 if focused_block == FocusedBlock::KnownNetworks {
                     Row::new(vec![
                         Line::from(""),
-                        Line::from("Name").yellow().centered(),
-                        Line::from("Security").yellow().centered(),
-                        Line::from("Hidden").yellow().centered(),
-                        Line::from("Auto Connect").yellow().centered(),
-                        Line::from("Signal").yellow().centered(),
+                        Line::from("Name").fg(theme.header).centered(),
+                        Line::from("Security").fg(theme.header).centered(),
+                        Line::from("Hidden").fg(theme.header).centered(),
+                        Line::from("Auto Connect").fg(theme.header).centered(),
+                        Line::from("Signal").fg(theme.header).centered(),
                     ])
                     .style(Style::new().bold())
                     .bottom_margin(1)
@@ -589,7 +977,12 @@ impl Station {
             })
             .block(
                 Block::default()
-                    .title(" Known Networks ")
+                    .title(self.title(
+                        "Known Networks",
+                        known_networks_rows.len(),
+                        known_networks_count,
+                    ))
+                    .title(Self::sort_title(self.known_networks_sort))
                     .title_style({
                         if focused_block == FocusedBlock::KnownNetworks {
                             Style::default().bold()
@@ -598,13 +991,7 @@ impl Station {
                         }
                     })
                     .borders(Borders::ALL)
-                    .border_style({
-                        if focused_block == FocusedBlock::KnownNetworks {
-                            Style::default().fg(Color::Green)
-                        } else {
-                            Style::default()
-                        }
-                    })
+                    .border_style(theme.border(focused_block == FocusedBlock::KnownNetworks))
                     .border_type({
                         if focused_block == FocusedBlock::KnownNetworks {
                             BorderType::Thick
@@ -617,16 +1004,25 @@ impl Station {
             .column_spacing(1)
             .flex(Flex::SpaceAround)
             .row_highlight_style(if focused_block == FocusedBlock::KnownNetworks {
-                Style::default().bg(Color::DarkGray).fg(Color::White)
+                theme.highlight()
             } else {
                 Style::default()
             });
 
+        // The selection is the index of the network, the table only has the rows that match
+        let mut known_networks_state = TableState::default()
+            .with_offset(self.known_networks_state.offset())
+            .with_selected(self.known_networks_state.selected().and_then(|selected| {
+                known_networks_rows
+                    .iter()
+                    .position(|index| *index == selected)
+            }));
         frame.render_stateful_widget(
             known_networks_table,
             known_networks_block,
-            &mut self.known_networks_state,
+            &mut known_networks_state,
         );
+        *self.known_networks_state.offset_mut() = known_networks_state.offset();
 
         //
         // New networks
@@ -639,19 +1035,8 @@ impl Station {
                     Line::from(net.name.clone()).centered(),
                     Line::from(net.network_type.to_string().clone()).centered(),
                     Line::from({
-                        let signal = {
-                            if *signal / 100 >= -50 {
-                                100
-                            } else {
-                                2 * (100 + signal / 100)
-                            }
-                        };
-                        match signal {
-                            n if n >= 75 => format!("{signal:3}% 󰤨"),
-                            n if (50..75).contains(&n) => format!("{signal:3}% 󰤥"),
-                            n if (25..50).contains(&n) => format!("{signal:3}% 󰤢"),
-                            _ => format!("{signal:3}% 󰤟"),
-                        }
+                        let signal = signal_percentage(*signal);
+                        format!("{signal:3}% {}", config.icons.signal(signal))
                     })
                     .centered(),
                 ])
@@ -665,40 +1050,41 @@ impl Station {
                         Line::from(net.address.clone()).centered(),
                         Line::from(net.network_type.to_string().clone()).centered(),
                         Line::from({
-                            let signal = {
-                                if net.signal_strength / 100 >= -50 {
-                                    100
-                                } else {
-                                    2 * (100 + net.signal_strength / 100)
-                                }
-                            };
-                            match signal {
-                                n if n >= 75 => format!("{signal:3}% 󰤨"),
-                                n if (50..75).contains(&n) => format!("{signal:3}% 󰤥"),
-                                n if (25..50).contains(&n) => format!("{signal:3}% 󰤢"),
-                                _ => format!("{signal:3}% 󰤟"),
-                            }
+                            let signal = signal_percentage(net.signal_strength);
+                            format!("{signal:3}% {}", config.icons.signal(signal))
                         })
                         .centered(),
                     ])
-                    .dark_gray(),
+                    .fg(theme.dimmed),
                 )
             })
         };
 
+        let new_networks_rows = self.new_networks_rows();
+        let new_networks_count = rows.len();
+        let rows: Vec<Row> = rows
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| new_networks_rows.contains(index))
+            .map(|(_, row)| row)
+            .collect();
+
+        // The percentage followed by the signal icon
+        let signal_width = 5 + config.icons.signal(100).chars().count() as u16;
+
         let widths = [
             Constraint::Length(25),
             Constraint::Length(15),
-            Constraint::Length(8),
+            Constraint::Length(signal_width.max(8)),
         ];
 
         let new_networks_table = Table::new(rows, widths)
             .header({
                 if focused_block == FocusedBlock::NewNetworks {
                     Row::new(vec![
-                        Line::from("Name").yellow().centered(),
-                        Line::from("Security").yellow().centered(),
-                        Line::from("Signal").yellow().centered(),
+                        Line::from("Name").fg(theme.header).centered(),
+                        Line::from("Security").fg(theme.header).centered(),
+                        Line::from("Signal").fg(theme.header).centered(),
                     ])
                     .style(Style::new().bold())
                     .bottom_margin(1)
@@ -713,7 +1099,8 @@ impl Station {
             })
             .block(
                 Block::default()
-                    .title(" New Networks ")
+                    .title(self.title("New Networks", new_networks_rows.len(), new_networks_count))
+                    .title(Self::sort_title(self.new_networks_sort))
                     .title_style({
                         if focused_block == FocusedBlock::NewNetworks {
                             Style::default().bold()
@@ -722,13 +1109,7 @@ impl Station {
                         }
                     })
                     .borders(Borders::ALL)
-                    .border_style({
-                        if focused_block == FocusedBlock::NewNetworks {
-                            Style::default().fg(Color::Green)
-                        } else {
-                            Style::default()
-                        }
-                    })
+                    .border_style(theme.border(focused_block == FocusedBlock::NewNetworks))
                     .border_type({
                         if focused_block == FocusedBlock::NewNetworks {
                             BorderType::Thick
@@ -741,213 +1122,367 @@ impl Station {
             .column_spacing(1)
             .flex(Flex::SpaceAround)
             .row_highlight_style(if focused_block == FocusedBlock::NewNetworks {
-                Style::default().bg(Color::DarkGray).fg(Color::White)
+                theme.highlight()
             } else {
                 Style::default()
             });
 
+        let mut new_networks_state = TableState::default()
+            .with_offset(self.new_networks_state.offset())
+            .with_selected(self.new_networks_state.selected().and_then(|selected| {
+                new_networks_rows
+                    .iter()
+                    .position(|index| *index == selected)
+            }));
         frame.render_stateful_widget(
             new_networks_table,
             new_networks_block,
-            &mut self.new_networks_state,
+            &mut new_networks_state,
         );
+        *self.new_networks_state.offset_mut() = new_networks_state.offset();
 
         let help_message = match focused_block {
             FocusedBlock::Device => vec![Line::from(vec![
-                Span::from(config.station.start_scanning.to_string()).bold(),
+                Span::from(config.keymap.key(Action::Scan)).bold(),
                 Span::from(" Scan"),
                 Span::from(" | "),
-                Span::from(config.device.infos.to_string()).bold(),
+                Span::from(config.keymap.key(Action::DeviceInfos)).bold(),
                 Span::from(" Infos"),
                 Span::from(" | "),
-                Span::from(config.device.toggle_power.to_string()).bold(),
+                Span::from(config.keymap.key(Action::TogglePower)).bold(),
                 Span::from(" Toggle Power"),
                 Span::from(" | "),
-                Span::from("ctrl+r").bold(),
+                Span::from(config.keymap.key(Action::SelectDevice)).bold(),
+                Span::from(" Select Device"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::ShowTraffic)).bold(),
+                Span::from(" Traffic"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::ShowLinkQuality)).bold(),
+                Span::from(" Link Quality"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::SwitchMode)).bold(),
                 Span::from(" Switch Mode"),
                 Span::from(" | "),
-                Span::from("tab").bold(),
+                Span::from(config.keymap.key(Action::FocusNext)).bold(),
                 Span::from(" Nav"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Help)).bold(),
+                Span::from(" Help"),
             ])],
             FocusedBlock::KnownNetworks => {
-                if frame.area().width <= 130 {
+                if frame.area().width <= 180 {
                     vec![
                         Line::from(vec![
-                            Span::from("󱁐  or ↵ ").bold(),
+                            Span::from(config.keymap.key(Action::ConnectKnownNetwork)).bold(),
                             Span::from(" Dis/connect"),
                             Span::from(" | "),
-                            Span::from(config.station.known_network.show_all.to_string()).bold(),
+                            Span::from(config.keymap.key(Action::ShowAllKnownNetworks)).bold(),
                             Span::from(" Show All"),
                             Span::from(" | "),
-                            Span::from(config.station.known_network.remove.to_string()).bold(),
+                            Span::from(config.keymap.key(Action::RemoveKnownNetwork)).bold(),
                             Span::from(" Remove"),
                             Span::from(" | "),
-                            Span::from(config.station.known_network.share.to_string()).bold(),
+                            Span::from(config.keymap.key(Action::ShareKnownNetwork)).bold(),
                             Span::from(" Share"),
                             Span::from(" | "),
-                            Span::from(config.station.start_scanning.to_string()).bold(),
+                            Span::from(config.keymap.key(Action::SortKnownNetworks)).bold(),
+                            Span::from(" Sort"),
+                            Span::from(" | "),
+                            Span::from(config.keymap.key(Action::ShowKnownNetworkDetails)).bold(),
+                            Span::from(" Details"),
+                            Span::from(" | "),
+                            Span::from(config.keymap.key(Action::Scan)).bold(),
                             Span::from(" Scan"),
                         ]),
                         Line::from(vec![
-                            Span::from("k,").bold(),
+                            Span::from(config.keymap.key(Action::Up)).bold(),
                             Span::from("  Up"),
                             Span::from(" | "),
-                            Span::from("j,").bold(),
+                            Span::from(config.keymap.key(Action::Down)).bold(),
                             Span::from("  Down"),
                             Span::from(" | "),
-                            Span::from("tab").bold(),
+                            Span::from(config.keymap.key(Action::FocusNext)).bold(),
                             Span::from(" Nav"),
                             Span::from(" | "),
-                            Span::from("ctrl+r").bold(),
+                            Span::from(config.keymap.key(Action::SwitchMode)).bold(),
                             Span::from(" Switch Mode"),
                             Span::from(" | "),
-                            Span::from(config.station.known_network.toggle_autoconnect.to_string())
-                                .bold(),
+                            Span::from(config.keymap.key(Action::ToggleAutoconnect)).bold(),
                             Span::from(" Autoconnect"),
+                            Span::from(" | "),
+                            Span::from(config.keymap.key(Action::Filter)).bold(),
+                            Span::from(" Filter"),
+                            Span::from(" | "),
+                            Span::from(config.keymap.key(Action::Help)).bold(),
+                            Span::from(" Help"),
                         ]),
                     ]
                 } else {
                     vec![Line::from(vec![
-                        Span::from("k,").bold(),
+                        Span::from(config.keymap.key(Action::Up)).bold(),
                         Span::from("  Up"),
                         Span::from(" | "),
-                        Span::from("j,").bold(),
+                        Span::from(config.keymap.key(Action::Down)).bold(),
                         Span::from("  Down"),
                         Span::from(" | "),
-                        Span::from("󱁐  or ↵ ").bold(),
+                        Span::from(config.keymap.key(Action::ConnectKnownNetwork)).bold(),
                         Span::from(" Dis/connect"),
                         Span::from(" | "),
-                        Span::from(config.station.known_network.show_all.to_string()).bold(),
+                        Span::from(config.keymap.key(Action::ShowAllKnownNetworks)).bold(),
                         Span::from(" Show All"),
                         Span::from(" | "),
-                        Span::from(config.station.known_network.remove.to_string()).bold(),
+                        Span::from(config.keymap.key(Action::RemoveKnownNetwork)).bold(),
                         Span::from(" Remove"),
                         Span::from(" | "),
-                        Span::from(config.station.known_network.toggle_autoconnect.to_string())
-                            .bold(),
+                        Span::from(config.keymap.key(Action::ToggleAutoconnect)).bold(),
                         Span::from(" Autoconnect"),
                         Span::from(" | "),
-                        Span::from(config.station.start_scanning.to_string()).bold(),
+                        Span::from(config.keymap.key(Action::Scan)).bold(),
                         Span::from(" Scan"),
                         Span::from(" | "),
-                        Span::from(config.station.known_network.share.to_string()).bold(),
+                        Span::from(config.keymap.key(Action::ShareKnownNetwork)).bold(),
                         Span::from(" Share"),
                         Span::from(" | "),
-                        Span::from("ctrl+r").bold(),
+                        Span::from(config.keymap.key(Action::SortKnownNetworks)).bold(),
+                        Span::from(" Sort"),
+                        Span::from(" | "),
+                        Span::from(config.keymap.key(Action::ShowKnownNetworkDetails)).bold(),
+                        Span::from(" Details"),
+                        Span::from(" | "),
+                        Span::from(config.keymap.key(Action::SwitchMode)).bold(),
                         Span::from(" Switch Mode"),
                         Span::from(" | "),
-                        Span::from("tab").bold(),
+                        Span::from(config.keymap.key(Action::FocusNext)).bold(),
                         Span::from(" Nav"),
+                        Span::from(" | "),
+                        Span::from(config.keymap.key(Action::Filter)).bold(),
+                        Span::from(" Filter"),
+                        Span::from(" | "),
+                        Span::from(config.keymap.key(Action::Help)).bold(),
+                        Span::from(" Help"),
                     ])]
                 }
             }
             FocusedBlock::NewNetworks => {
-                if frame.area().width < 108 {
+                if frame.area().width < 155 {
                     vec![
                         Line::from(vec![
-                            Span::from("󱁐  or ↵ ").bold(),
+                            Span::from(config.keymap.key(Action::ConnectNewNetwork)).bold(),
                             Span::from(" Connect"),
                             Span::from(" | "),
-                            Span::from(config.station.start_scanning.to_string()).bold(),
+                            Span::from(config.keymap.key(Action::Scan)).bold(),
                             Span::from(" Scan"),
                             Span::from(" | "),
-                            Span::from(config.station.new_network.connect_hidden.to_string())
-                                .bold(),
+                            Span::from(config.keymap.key(Action::ConnectHiddenNetwork)).bold(),
                             Span::from(" Connect Hidden"),
+                            Span::from(" | "),
+                            Span::from(config.keymap.key(Action::SortNewNetworks)).bold(),
+                            Span::from(" Sort"),
+                            Span::from(" | "),
+                            Span::from(config.keymap.key(Action::ShowNewNetworkDetails)).bold(),
+                            Span::from(" Details"),
                         ]),
                         Line::from(vec![
-                            Span::from("k,").bold(),
+                            Span::from(config.keymap.key(Action::Up)).bold(),
                             Span::from("  Up"),
                             Span::from(" | "),
-                            Span::from("j,").bold(),
+                            Span::from(config.keymap.key(Action::Down)).bold(),
                             Span::from("  Down"),
                             Span::from(" | "),
-                            Span::from("ctrl+r").bold(),
+                            Span::from(config.keymap.key(Action::SwitchMode)).bold(),
                             Span::from(" Switch Mode"),
                             Span::from(" | "),
-                            Span::from("tab").bold(),
+                            Span::from(config.keymap.key(Action::FocusNext)).bold(),
                             Span::from(" Nav"),
+                            Span::from(" | "),
+                            Span::from(config.keymap.key(Action::Filter)).bold(),
+                            Span::from(" Filter"),
+                            Span::from(" | "),
+                            Span::from(config.keymap.key(Action::Help)).bold(),
+                            Span::from(" Help"),
                         ]),
                     ]
                 } else {
                     vec![Line::from(vec![
-                        Span::from("k,").bold(),
+                        Span::from(config.keymap.key(Action::Up)).bold(),
                         Span::from("  Up"),
                         Span::from(" | "),
-                        Span::from("j,").bold(),
+                        Span::from(config.keymap.key(Action::Down)).bold(),
                         Span::from("  Down"),
                         Span::from(" | "),
-                        Span::from("󱁐  or ↵ ").bold(),
+                        Span::from(config.keymap.key(Action::ConnectNewNetwork)).bold(),
                         Span::from(" Connect"),
                         Span::from(" | "),
-                        Span::from(config.station.new_network.connect_hidden.to_string()).bold(),
+                        Span::from(config.keymap.key(Action::ConnectHiddenNetwork)).bold(),
                         Span::from(" Connect Hidden"),
                         Span::from(" | "),
-                        Span::from(config.station.new_network.show_all.to_string()).bold(),
+                        Span::from(config.keymap.key(Action::SortNewNetworks)).bold(),
+                        Span::from(" Sort"),
+                        Span::from(" | "),
+                        Span::from(config.keymap.key(Action::ShowNewNetworkDetails)).bold(),
+                        Span::from(" Details"),
+                        Span::from(" | "),
+                        Span::from(config.keymap.key(Action::ShowAllNewNetworks)).bold(),
                         Span::from(" Show All"),
                         Span::from(" | "),
-                        Span::from(config.station.start_scanning.to_string()).bold(),
+                        Span::from(config.keymap.key(Action::Scan)).bold(),
                         Span::from(" Scan"),
                         Span::from(" | "),
-                        Span::from("ctrl+r").bold(),
+                        Span::from(config.keymap.key(Action::SwitchMode)).bold(),
                         Span::from(" Switch Mode"),
                         Span::from(" | "),
-                        Span::from("tab").bold(),
+                        Span::from(config.keymap.key(Action::FocusNext)).bold(),
                         Span::from(" Nav"),
+                        Span::from(" | "),
+                        Span::from(config.keymap.key(Action::Filter)).bold(),
+                        Span::from(" Filter"),
+                        Span::from(" | "),
+                        Span::from(config.keymap.key(Action::Help)).bold(),
+                        Span::from(" Help"),
                     ])]
                 }
             }
             FocusedBlock::AdapterInfos => {
                 vec![Line::from(vec![
-                    Span::from("󱊷 ").bold(),
+                    Span::from(config.keymap.key(Action::Close)).bold(),
                     Span::from(" Discard"),
                 ])]
             }
+            FocusedBlock::LinkQuality | FocusedBlock::Traffic => vec![Line::from(vec![
+                Span::from(config.keymap.key(Action::Close)).bold(),
+                Span::from(" Close"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::PopupHelp)).bold(),
+                Span::from(" Help"),
+            ])],
+            FocusedBlock::NetworkDetails => vec![Line::from(vec![
+                Span::from(config.keymap.key(Action::Up)).bold(),
+                Span::from("  Up"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Down)).bold(),
+                Span::from("  Down"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Submit)).bold(),
+                Span::from(" Connect"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Close)).bold(),
+                Span::from(" Close"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::PopupHelp)).bold(),
+                Span::from(" Help"),
+            ])],
+            FocusedBlock::Wps => vec![Line::from(vec![
+                Span::from(config.keymap.key(Action::Up)).bold(),
+                Span::from("  Up"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Down)).bold(),
+                Span::from("  Down"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Submit)).bold(),
+                Span::from(" Start"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Close)).bold(),
+                Span::from(" Cancel"),
+            ])],
+            FocusedBlock::DeviceSelector => vec![Line::from(vec![
+                Span::from(config.keymap.key(Action::Up)).bold(),
+                Span::from("  Up"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Down)).bold(),
+                Span::from("  Down"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Submit)).bold(),
+                Span::from(" Select"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::Close)).bold(),
+                Span::from(" Discard"),
+            ])],
             FocusedBlock::PskAuthKey => vec![Line::from(vec![
-                Span::from(" ↵ ").bold(),
+                Span::from(config.keymap.key(Action::Submit)).bold(),
                 Span::from(" Apply"),
                 Span::from(" | "),
-                Span::from("tab").bold(),
+                Span::from(config.keymap.key(Action::NextField)).bold(),
                 Span::from(" Hide/Show password"),
                 Span::from(" | "),
-                Span::from("󱊷 ").bold(),
+                Span::from(config.keymap.key(Action::Close)).bold(),
                 Span::from(" Discard"),
             ])],
             FocusedBlock::WpaEntrepriseAuth => vec![Line::from(vec![
-                Span::from(" ↵ ").bold(),
+                Span::from(format!(" {}", config.icons.enter())).bold(),
                 Span::from(" Apply"),
                 Span::from(" | "),
-                Span::from("h,l,←,→").bold(),
+                Span::from(format!(
+                    "h,l,{},{}",
+                    config.icons.left(),
+                    config.icons.right()
+                ))
+                .bold(),
                 Span::from(" Switch EAP/Method"),
                 Span::from(" | "),
-                Span::from("󱊷 ").bold(),
+                Span::from(config.keymap.key(Action::Close)).bold(),
                 Span::from(" Discard"),
                 Span::from(" | "),
                 Span::from("tab").bold(),
                 Span::from(" Nav"),
+                Span::from(" | "),
+                Span::from(config.keymap.key(Action::PopupHelp)).bold(),
+                Span::from(" Help"),
             ])],
             FocusedBlock::ConnectHiddenNetwork => {
                 vec![Line::from(vec![
-                    Span::from(" ↵ ").bold(),
+                    Span::from(format!(" {}", config.icons.enter())).bold(),
                     Span::from(" Connect"),
                     Span::from(" | "),
-                    Span::from("󱊷 ").bold(),
+                    Span::from(config.keymap.key(Action::Close)).bold(),
                     Span::from(" Discard"),
                 ])]
             }
             _ => vec![Line::from(vec![
-                Span::from("󱊷 ").bold(),
+                Span::from(config.keymap.key(Action::Close)).bold(),
                 Span::from(" Discard"),
             ])],
         };
 
-        let help_message = Paragraph::new(help_message).centered().blue();
+        if self.filter.is_editing {
+            let query = self.filter.input.value();
+            let filter =
+                Paragraph::new(Line::from(vec![Span::from("/").bold(), Span::from(query)]))
+                    .fg(theme.text);
+            frame.render_widget(filter, help_block);
+            frame.set_cursor_position((
+                help_block.x + 1 + self.filter.input.visual_cursor() as u16,
+                help_block.y,
+            ));
+        } else {
+            let help_message = Paragraph::new(help_message)
+                .centered()
+                .wrap(Wrap { trim: true })
+                .fg(theme.help);
 
-        frame.render_widget(help_message, help_block);
+            frame.render_widget(help_message, help_block);
+        }
 
         // Share
         if let Some(share) = &self.share {
-            share.render(frame);
+            share.render(frame, &theme);
+        }
+
+        if let Some(dpp) = &self.dpp {
+            dpp.render(frame, &theme);
+        }
+
+        if let Some(wps) = &self.wps {
+            wps.render(frame, &theme);
+        }
+
+        if let Some(details) = &mut self.details {
+            details.render(frame, &config);
+        }
+
+        if focused_block == FocusedBlock::LinkQuality {
+            self.link_quality
+                .render(frame, self.diagnostic.as_ref(), &config);
         }
     }
 }
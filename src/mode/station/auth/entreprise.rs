@@ -3,14 +3,16 @@ use tokio::sync::mpsc::UnboundedSender;
 
 use ratatui::{
     Frame,
-    layout::{Constraint, Direction, Layout, Margin},
-    style::{Style, Stylize},
+    layout::{Constraint, Direction, Layout, Margin, Position, Rect},
+    style::Stylize,
     text::Text,
     widgets::{Block, Borders, Clear},
 };
 use tui_input::Input;
 
 use crate::event::Event;
+use crate::layout::popup_area;
+use crate::theme::Theme;
 
 pub mod eduroam;
 pub mod peap;
@@ -30,6 +32,14 @@ fn pad_string(input: &str, length: usize) -> String {
     }
 }
 
+/// Index of the item drawn at the position in a list without borders.
+fn list_item_at(area: Rect, offset: usize, position: Position) -> Option<usize> {
+    if !area.contains(position) {
+        return None;
+    }
+    Some(offset + (position.y - area.y) as usize)
+}
+
 #[derive(Debug, Clone, Default)]
 struct UserInputField {
     field: Input,
@@ -58,6 +68,8 @@ pub struct WPAEntreprise {
     pub eap: Eap,
     pub network_name: String,
     focused_section: FocusedSection,
+    eap_choice_area: Rect,
+    apply_area: Rect,
 }
 
 #[derive(Debug)]
@@ -78,6 +90,26 @@ impl Eap {
     pub fn new() -> Self {
         Self::TLS(tls::TLS::new())
     }
+
+    fn deselect(&mut self) {
+        match self {
+            Eap::TLS(v) => v.deselect(),
+            Eap::TTLS(v) => v.deselect(),
+            Eap::PEAP(v) => v.deselect(),
+            Eap::PWD(v) => v.deselect(),
+            Eap::Eduroam(v) => v.deselect(),
+        }
+    }
+
+    fn click(&mut self, position: Position) -> bool {
+        match self {
+            Eap::TLS(v) => v.click(position),
+            Eap::TTLS(v) => v.click(position),
+            Eap::PEAP(v) => v.click(position),
+            Eap::PWD(v) => v.click(position),
+            Eap::Eduroam(v) => v.click(position),
+        }
+    }
 }
 
 impl WPAEntreprise {
@@ -86,6 +118,30 @@ impl WPAEntreprise {
             eap: Eap::new(),
             network_name,
             focused_section: FocusedSection::EapChoice,
+            eap_choice_area: Rect::default(),
+            apply_area: Rect::default(),
+        }
+    }
+
+    /// Clicking the sides of the EAP choice switches the method, clicking APPLY applies.
+    pub fn click(&mut self, position: Position, sender: UnboundedSender<Event>) {
+        if self.eap_choice_area.contains(position) {
+            self.eap.deselect();
+            self.focused_section = FocusedSection::EapChoice;
+
+            let center = self.eap_choice_area.x + self.eap_choice_area.width / 2;
+            let code = if position.x < center {
+                KeyCode::Left
+            } else {
+                KeyCode::Right
+            };
+            self.handle_key_events(KeyEvent::from(code), sender);
+        } else if self.apply_area.contains(position) {
+            self.eap.deselect();
+            self.focused_section = FocusedSection::Apply;
+            self.handle_key_events(KeyEvent::from(KeyCode::Enter), sender);
+        } else if self.eap.click(position) {
+            self.focused_section = FocusedSection::Eap;
         }
     }
     pub fn handle_key_events(&mut self, key_event: KeyEvent, sender: UnboundedSender<Event>) {
@@ -448,26 +504,8 @@ impl WPAEntreprise {
         }
     }
 
-    pub fn render(&mut self, frame: &mut Frame) {
-        let layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Fill(1),
-                Constraint::Length(30),
-                Constraint::Fill(1),
-            ])
-            .flex(ratatui::layout::Flex::SpaceBetween)
-            .split(frame.area());
-
-        let block = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Fill(1),
-                Constraint::Max(80),
-                Constraint::Fill(1),
-            ])
-            .flex(ratatui::layout::Flex::SpaceBetween)
-            .split(layout[1])[1];
+    pub fn render(&mut self, frame: &mut Frame, theme: &Theme) {
+        let block = popup_area(frame.area(), 80, 30);
 
         frame.render_widget(Clear, block);
 
@@ -480,7 +518,7 @@ impl WPAEntreprise {
                     Constraint::Length(2),
                     Constraint::Length(1), // Eap choice
                     Constraint::Length(2),
-                    Constraint::Length(30), // Form
+                    Constraint::Fill(1), // Form
                     Constraint::Length(2),
                     Constraint::Length(1), // Submit
                     Constraint::Length(2),
@@ -490,11 +528,14 @@ impl WPAEntreprise {
             (chunks[1], chunks[3], chunks[5], chunks[7])
         };
 
+        self.eap_choice_area = eap_choice_block;
+        self.apply_area = apply_block;
+
         frame.render_widget(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(ratatui::widgets::BorderType::Thick)
-                .border_style(Style::default().green()),
+                .border_style(theme.border(true)),
             block,
         );
 
@@ -512,7 +553,7 @@ impl WPAEntreprise {
         };
 
         let choice = if self.focused_section == FocusedSection::EapChoice {
-            choice.bold().green()
+            choice.bold().fg(theme.border_focused)
         } else {
             choice
         };
@@ -527,24 +568,27 @@ impl WPAEntreprise {
 
         match &mut self.eap {
             Eap::TLS(v) => {
-                v.render(frame, eap_block);
+                v.render(frame, eap_block, theme);
             }
             Eap::PWD(v) => {
-                v.render(frame, eap_block);
+                v.render(frame, eap_block, theme);
             }
             Eap::TTLS(v) => {
-                v.render(frame, eap_block);
+                v.render(frame, eap_block, theme);
             }
             Eap::PEAP(v) => {
-                v.render(frame, eap_block);
+                v.render(frame, eap_block, theme);
             }
             Eap::Eduroam(v) => {
-                v.render(frame, eap_block);
+                v.render(frame, eap_block, theme);
             }
         }
 
         let text = if self.focused_section == FocusedSection::Apply {
-            Text::from("APPLY").centered().green().bold()
+            Text::from("APPLY")
+                .centered()
+                .fg(theme.border_focused)
+                .bold()
         } else {
             Text::from("APPLY").centered()
         };
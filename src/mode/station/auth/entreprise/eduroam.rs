@@ -4,15 +4,16 @@ use std::{fs::OpenOptions, io::Write};
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     Frame,
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Stylize},
+    layout::{Constraint, Direction, Layout, Position, Rect},
+    style::Stylize,
     text::{Line, Span},
     widgets::{HighlightSpacing, List, ListState},
 };
 
 use tui_input::{Input, backend::crossterm::EventHandler};
 
-use crate::mode::station::auth::entreprise::ERROR_PADDING;
+use crate::mode::station::auth::entreprise::{ERROR_PADDING, list_item_at};
+use crate::theme::Theme;
 
 fn pad_string(input: &str, length: usize) -> String {
     let current_length = input.chars().count();
@@ -31,6 +32,13 @@ pub enum FocusedInput {
     Phase2Password,
 }
 
+/// The fields in the order they are drawn, every field is followed by its error line.
+const FIELDS: [FocusedInput; 3] = [
+    FocusedInput::Identity,
+    FocusedInput::Phase2Identity,
+    FocusedInput::Phase2Password,
+];
+
 #[derive(Debug, Clone, Default)]
 pub struct Eduroam {
     identity: UserInputField,
@@ -38,6 +46,7 @@ pub struct Eduroam {
     phase2_password: UserInputField,
     pub focused_input: FocusedInput,
     state: ListState,
+    area: Rect,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -107,6 +116,10 @@ impl Eduroam {
         self.state.select(Some(4));
     }
 
+    pub fn deselect(&mut self) {
+        self.state.select(None);
+    }
+
     pub fn selected(&self) -> bool {
         self.state.selected().is_some()
     }
@@ -166,7 +179,20 @@ AutoConnect=true",
         }
     }
 
-    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+    /// Focuses the field drawn at the position, returns whether there is one.
+    pub fn click(&mut self, position: Position) -> bool {
+        let Some(index) = list_item_at(self.area, self.state.offset(), position) else {
+            return false;
+        };
+        let Some(focused_input) = FIELDS.get(index / 2) else {
+            return false;
+        };
+        self.focused_input = focused_input.clone();
+        self.state.select(Some(index - index % 2));
+        true
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
         let layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
@@ -191,9 +217,9 @@ AutoConnect=true",
             Line::from(vec![
                 Span::from(pad_string(" Identity", 20))
                     .bold()
-                    .bg(Color::DarkGray),
+                    .bg(theme.input),
                 Span::from("  "),
-                Span::from(pad_string(self.identity.field.value(), 50)).bg(Color::DarkGray),
+                Span::from(pad_string(self.identity.field.value(), 50)).bg(theme.input),
             ]),
             Line::from(vec![Span::from(ERROR_PADDING), {
                 if let Some(error) = &self.identity.error {
@@ -202,13 +228,13 @@ AutoConnect=true",
                     Span::from("")
                 }
             }])
-            .red(),
+            .fg(theme.error),
             Line::from(vec![
                 Span::from(pad_string(" Phase2 Identity", 20))
                     .bold()
-                    .bg(Color::DarkGray),
+                    .bg(theme.input),
                 Span::from("  "),
-                Span::from(pad_string(self.phase2_identity.field.value(), 50)).bg(Color::DarkGray),
+                Span::from(pad_string(self.phase2_identity.field.value(), 50)).bg(theme.input),
             ]),
             Line::from(vec![Span::from(ERROR_PADDING), {
                 if let Some(error) = &self.phase2_identity.error {
@@ -217,13 +243,13 @@ AutoConnect=true",
                     Span::from("")
                 }
             }])
-            .red(),
+            .fg(theme.error),
             Line::from(vec![
                 Span::from(pad_string(" Phase2 Password", 20))
                     .bold()
-                    .bg(Color::DarkGray),
+                    .bg(theme.input),
                 Span::from("  "),
-                Span::from(pad_string(self.phase2_password.field.value(), 50)).bg(Color::DarkGray),
+                Span::from(pad_string(self.phase2_password.field.value(), 50)).bg(theme.input),
             ]),
             Line::from(vec![Span::from(ERROR_PADDING), {
                 if let Some(error) = &self.phase2_password.error {
@@ -232,13 +258,14 @@ AutoConnect=true",
                     Span::from("")
                 }
             }])
-            .red(),
+            .fg(theme.error),
         ];
 
         let list = List::new(items)
             .highlight_symbol("> ")
             .highlight_spacing(HighlightSpacing::Always);
 
+        self.area = block;
         frame.render_stateful_widget(list, block, &mut self.state);
     }
 }
@@ -4,8 +4,8 @@ use std::{fs::OpenOptions, io::Write, path::Path};
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     Frame,
-    layout::{Margin, Rect},
-    style::{Color, Stylize},
+    layout::{Margin, Position, Rect},
+    style::Stylize,
     text::{Line, Span},
     widgets::{HighlightSpacing, List, ListState},
 };
@@ -14,7 +14,8 @@ use tui_input::backend::crossterm::EventHandler;
 
 use crate::{
     iwd_network_name,
-    mode::station::auth::entreprise::{ERROR_PADDING, UserInputField},
+    mode::station::auth::entreprise::{ERROR_PADDING, UserInputField, list_item_at},
+    theme::Theme,
 };
 
 fn pad_string(input: &str, length: usize) -> String {
@@ -49,6 +50,19 @@ pub enum FocusedInput {
     Phase2Password,
 }
 
+/// The fields in the order they are drawn, every field is followed by its error line.
+const FIELDS: [FocusedInput; 9] = [
+    FocusedInput::Identity,
+    FocusedInput::ServerDomainMask,
+    FocusedInput::CaCert,
+    FocusedInput::ClientCert,
+    FocusedInput::ClientKey,
+    FocusedInput::KeyPassphrase,
+    FocusedInput::Phase2Method,
+    FocusedInput::Phase2Identity,
+    FocusedInput::Phase2Password,
+];
+
 #[derive(Debug, Clone, Default)]
 pub struct PEAP {
     identity: UserInputField,
@@ -62,6 +76,7 @@ pub struct PEAP {
     phase2_password: UserInputField,
     pub focused_input: FocusedInput,
     state: ListState,
+    area: Rect,
 }
 
 impl PEAP {
@@ -179,6 +194,10 @@ impl PEAP {
         self.state.select(Some(16));
     }
 
+    pub fn deselect(&mut self) {
+        self.state.select(None);
+    }
+
     pub fn selected(&self) -> bool {
         self.state.selected().is_some()
     }
@@ -335,14 +354,27 @@ EAP-PEAP-Phase2-Password={}
         }
     }
 
-    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+    /// Focuses the field drawn at the position, returns whether there is one.
+    pub fn click(&mut self, position: Position) -> bool {
+        let Some(index) = list_item_at(self.area, self.state.offset(), position) else {
+            return false;
+        };
+        let Some(focused_input) = FIELDS.get(index / 2) else {
+            return false;
+        };
+        self.focused_input = focused_input.clone();
+        self.state.select(Some(index - index % 2));
+        true
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
         let items = [
             Line::from(vec![
                 Span::from(pad_string(" Identity", 20))
                     .bold()
-                    .bg(Color::DarkGray),
+                    .bg(theme.input),
                 Span::from("  "),
-                Span::from(pad_string(self.identity.field.value(), 50)).bg(Color::DarkGray),
+                Span::from(pad_string(self.identity.field.value(), 50)).bg(theme.input),
             ]),
             Line::from(vec![Span::from(ERROR_PADDING), {
                 if let Some(error) = &self.identity.error {
@@ -351,14 +383,13 @@ EAP-PEAP-Phase2-Password={}
                     Span::from("")
                 }
             }])
-            .red(),
+            .fg(theme.error),
             Line::from(vec![
                 Span::from(pad_string(" Server Domain Mask", 20))
                     .bold()
-                    .bg(Color::DarkGray),
+                    .bg(theme.input),
                 Span::from("  "),
-                Span::from(pad_string(self.server_domain_mask.field.value(), 50))
-                    .bg(Color::DarkGray),
+                Span::from(pad_string(self.server_domain_mask.field.value(), 50)).bg(theme.input),
             ]),
             Line::from(vec![Span::from(ERROR_PADDING), {
                 if let Some(error) = &self.server_domain_mask.error {
@@ -367,13 +398,13 @@ EAP-PEAP-Phase2-Password={}
                     Span::from("")
                 }
             }])
-            .red(),
+            .fg(theme.error),
             Line::from(vec![
                 Span::from(pad_string(" CA Cert", 20))
                     .bold()
-                    .bg(Color::DarkGray),
+                    .bg(theme.input),
                 Span::from("  "),
-                Span::from(pad_string(self.ca_cert.field.value(), 50)).bg(Color::DarkGray),
+                Span::from(pad_string(self.ca_cert.field.value(), 50)).bg(theme.input),
             ]),
             Line::from(vec![Span::from(ERROR_PADDING), {
                 if let Some(error) = &self.ca_cert.error {
@@ -382,13 +413,13 @@ EAP-PEAP-Phase2-Password={}
                     Span::from("")
                 }
             }])
-            .red(),
+            .fg(theme.error),
             Line::from(vec![
                 Span::from(pad_string(" Client Cert", 20))
                     .bold()
-                    .bg(Color::DarkGray),
+                    .bg(theme.input),
                 Span::from("  "),
-                Span::from(pad_string(self.client_cert.field.value(), 50)).bg(Color::DarkGray),
+                Span::from(pad_string(self.client_cert.field.value(), 50)).bg(theme.input),
             ]),
             Line::from(vec![Span::from(ERROR_PADDING), {
                 if let Some(error) = &self.client_cert.error {
@@ -397,13 +428,13 @@ EAP-PEAP-Phase2-Password={}
                     Span::from("")
                 }
             }])
-            .red(),
+            .fg(theme.error),
             Line::from(vec![
                 Span::from(pad_string(" Client Key", 20))
                     .bold()
-                    .bg(Color::DarkGray),
+                    .bg(theme.input),
                 Span::from("  "),
-                Span::from(pad_string(self.client_key.field.value(), 50)).bg(Color::DarkGray),
+                Span::from(pad_string(self.client_key.field.value(), 50)).bg(theme.input),
             ]),
             Line::from(vec![Span::from(ERROR_PADDING), {
                 if let Some(error) = &self.client_key.error {
@@ -412,13 +443,13 @@ EAP-PEAP-Phase2-Password={}
                     Span::from("")
                 }
             }])
-            .red(),
+            .fg(theme.error),
             Line::from(vec![
                 Span::from(pad_string(" Key Passphrase", 20))
                     .bold()
-                    .bg(Color::DarkGray),
+                    .bg(theme.input),
                 Span::from("  "),
-                Span::from(pad_string(self.key_passphrase.field.value(), 50)).bg(Color::DarkGray),
+                Span::from(pad_string(self.key_passphrase.field.value(), 50)).bg(theme.input),
             ]),
             Line::from(vec![Span::from(ERROR_PADDING), {
                 if let Some(error) = &self.key_passphrase.error {
@@ -427,21 +458,21 @@ EAP-PEAP-Phase2-Password={}
                     Span::from("")
                 }
             }])
-            .red(),
+            .fg(theme.error),
             Line::from(vec![
                 Span::from(pad_string(" Phase2 Method", 20))
                     .bold()
-                    .bg(Color::DarkGray),
+                    .bg(theme.input),
                 Span::from("  "),
                 Span::from(format!("< {} >", self.phase2_method)),
             ]),
-            Line::from(vec![Span::from(ERROR_PADDING), Span::from("")]).red(),
+            Line::from(vec![Span::from(ERROR_PADDING), Span::from("")]).fg(theme.error),
             Line::from(vec![
                 Span::from(pad_string(" Phase2 Identity", 20))
                     .bold()
-                    .bg(Color::DarkGray),
+                    .bg(theme.input),
                 Span::from("  "),
-                Span::from(pad_string(self.phase2_identity.field.value(), 50)).bg(Color::DarkGray),
+                Span::from(pad_string(self.phase2_identity.field.value(), 50)).bg(theme.input),
             ]),
             Line::from(vec![Span::from(ERROR_PADDING), {
                 if let Some(error) = &self.phase2_identity.error {
@@ -450,13 +481,13 @@ EAP-PEAP-Phase2-Password={}
                     Span::from("")
                 }
             }])
-            .red(),
+            .fg(theme.error),
             Line::from(vec![
                 Span::from(pad_string(" Phase2 Password", 20))
                     .bold()
-                    .bg(Color::DarkGray),
+                    .bg(theme.input),
                 Span::from("  "),
-                Span::from(pad_string(self.phase2_password.field.value(), 50)).bg(Color::DarkGray),
+                Span::from(pad_string(self.phase2_password.field.value(), 50)).bg(theme.input),
             ]),
             Line::from(vec![Span::from(ERROR_PADDING), {
                 if let Some(error) = &self.phase2_password.error {
@@ -465,20 +496,17 @@ EAP-PEAP-Phase2-Password={}
                     Span::from("")
                 }
             }])
-            .red(),
+            .fg(theme.error),
         ];
 
         let list = List::new(items)
             .highlight_symbol("> ")
             .highlight_spacing(HighlightSpacing::Always);
 
-        frame.render_stateful_widget(
-            list,
-            area.inner(Margin {
-                horizontal: 2,
-                vertical: 0,
-            }),
-            &mut self.state,
-        );
+        self.area = area.inner(Margin {
+            horizontal: 2,
+            vertical: 0,
+        });
+        frame.render_stateful_widget(list, self.area, &mut self.state);
     }
 }
@@ -4,15 +4,19 @@ use std::{fs::OpenOptions, io::Write};
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     Frame,
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Stylize},
+    layout::{Constraint, Direction, Layout, Position, Rect},
+    style::Stylize,
     text::{Line, Span},
     widgets::{HighlightSpacing, List, ListState},
 };
 
 use tui_input::{Input, backend::crossterm::EventHandler};
 
-use crate::{iwd_network_name, mode::station::auth::entreprise::ERROR_PADDING};
+use crate::theme::Theme;
+use crate::{
+    iwd_network_name,
+    mode::station::auth::entreprise::{ERROR_PADDING, list_item_at},
+};
 
 fn pad_string(input: &str, length: usize) -> String {
     let current_length = input.chars().count();
@@ -30,12 +34,16 @@ pub enum FocusedInput {
     Password,
 }
 
+/// The fields in the order they are drawn, every field is followed by its error line.
+const FIELDS: [FocusedInput; 2] = [FocusedInput::Identity, FocusedInput::Password];
+
 #[derive(Debug, Clone, Default)]
 pub struct PWD {
     identity: UserInputField,
     password: UserInputField,
     pub focused_input: FocusedInput,
     state: ListState,
+    area: Rect,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -95,6 +103,10 @@ impl PWD {
         self.state.select(Some(2));
     }
 
+    pub fn deselect(&mut self) {
+        self.state.select(None);
+    }
+
     pub fn selected(&self) -> bool {
         self.state.selected().is_some()
     }
@@ -147,7 +159,20 @@ AutoConnect=true",
         }
     }
 
-    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+    /// Focuses the field drawn at the position, returns whether there is one.
+    pub fn click(&mut self, position: Position) -> bool {
+        let Some(index) = list_item_at(self.area, self.state.offset(), position) else {
+            return false;
+        };
+        let Some(focused_input) = FIELDS.get(index / 2) else {
+            return false;
+        };
+        self.focused_input = focused_input.clone();
+        self.state.select(Some(index - index % 2));
+        true
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
         let layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
@@ -172,9 +197,9 @@ AutoConnect=true",
             Line::from(vec![
                 Span::from(pad_string(" Identity", 20))
                     .bold()
-                    .bg(Color::DarkGray),
+                    .bg(theme.input),
                 Span::from("  "),
-                Span::from(pad_string(self.identity.field.value(), 50)).bg(Color::DarkGray),
+                Span::from(pad_string(self.identity.field.value(), 50)).bg(theme.input),
             ]),
             Line::from(vec![Span::from(ERROR_PADDING), {
                 if let Some(error) = &self.identity.error {
@@ -183,13 +208,13 @@ AutoConnect=true",
                     Span::from("")
                 }
             }])
-            .red(),
+            .fg(theme.error),
             Line::from(vec![
                 Span::from(pad_string(" Password", 20))
                     .bold()
-                    .bg(Color::DarkGray),
+                    .bg(theme.input),
                 Span::from("  "),
-                Span::from(pad_string(self.password.field.value(), 50)).bg(Color::DarkGray),
+                Span::from(pad_string(self.password.field.value(), 50)).bg(theme.input),
             ]),
             Line::from(vec![Span::from(ERROR_PADDING), {
                 if let Some(error) = &self.password.error {
@@ -198,13 +223,14 @@ AutoConnect=true",
                     Span::from("")
                 }
             }])
-            .red(),
+            .fg(theme.error),
         ];
 
         let list = List::new(items)
             .highlight_symbol("> ")
             .highlight_spacing(HighlightSpacing::Always);
 
+        self.area = block;
         frame.render_stateful_widget(list, block, &mut self.state);
     }
 }
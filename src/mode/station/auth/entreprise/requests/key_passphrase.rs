@@ -1,10 +1,12 @@
 use crate::agent::AuthAgent;
+use crate::layout::popup_area;
+use crate::{icons::Icons, theme::Theme};
 use anyhow::Result;
 
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Style, Stylize},
+    style::{Style, Stylize},
     text::{Line, Span, Text},
     widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph},
 };
@@ -43,26 +45,8 @@ impl RequestKeyPassphrase {
         self.passphrase.reset();
         Ok(())
     }
-    pub fn render(&self, frame: &mut Frame) {
-        let popup_layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Fill(1),
-                Constraint::Length(8),
-                Constraint::Fill(1),
-            ])
-            .flex(ratatui::layout::Flex::SpaceBetween)
-            .split(frame.area());
-
-        let area = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Fill(1),
-                Constraint::Length(80),
-                Constraint::Fill(1),
-            ])
-            .flex(ratatui::layout::Flex::SpaceBetween)
-            .split(popup_layout[1])[1];
+    pub fn render(&self, frame: &mut Frame, theme: &Theme, icons: Icons) {
+        let area = popup_area(frame.area(), 80, 8);
 
         let (text_area, passkey_area, show_password_area) = {
             let chunks = Layout::default()
@@ -106,7 +90,7 @@ impl RequestKeyPassphrase {
 
         let text = Paragraph::new(text.centered())
             .alignment(Alignment::Center)
-            .style(Style::default().fg(Color::White))
+            .style(Style::default().fg(theme.text))
             .block(Block::new().padding(Padding::uniform(1)));
 
         let passkey = Paragraph::new({
@@ -117,14 +101,11 @@ impl RequestKeyPassphrase {
             }
         })
         .alignment(Alignment::Center)
-        .style(Style::default().fg(Color::White))
-        .block(Block::new().style(Style::default().bg(Color::DarkGray)));
+        .style(Style::default().fg(theme.text))
+        .block(Block::new().style(Style::default().bg(theme.input)));
 
-        let show_password_icon = if self.show_password {
-            Text::from(" ").centered()
-        } else {
-            Text::from(" ").centered()
-        };
+        let show_password_icon =
+            Text::from(icons.password_visibility(self.show_password)).centered();
 
         frame.render_widget(Clear, area);
 
@@ -132,8 +113,8 @@ impl RequestKeyPassphrase {
             Block::new()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Thick)
-                .style(Style::default().green())
-                .border_style(Style::default().fg(Color::Green)),
+                .style(Style::default().fg(theme.border_focused))
+                .border_style(theme.border(true)),
             area,
         );
         frame.render_widget(text, text_area);
@@ -1,10 +1,12 @@
 use crate::agent::AuthAgent;
+use crate::layout::popup_area;
+use crate::{icons::Icons, theme::Theme};
 use anyhow::Result;
 
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Margin},
-    style::{Color, Style, Stylize},
+    style::Stylize,
     text::{Line, Span, Text},
     widgets::{Block, BorderType, Borders, Clear, List},
 };
@@ -45,26 +47,8 @@ impl RequestPassword {
         self.password.reset();
         Ok(())
     }
-    pub fn render(&self, frame: &mut Frame) {
-        let popup_layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Fill(1),
-                Constraint::Length(12),
-                Constraint::Fill(1),
-            ])
-            .flex(ratatui::layout::Flex::SpaceBetween)
-            .split(frame.area());
-
-        let area = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Fill(1),
-                Constraint::Length(80),
-                Constraint::Fill(1),
-            ])
-            .flex(ratatui::layout::Flex::SpaceBetween)
-            .split(popup_layout[1])[1];
+    pub fn render(&self, frame: &mut Frame, theme: &Theme, icons: Icons) {
+        let area = popup_area(frame.area(), 80, 12);
 
         let (title_area, form_area, show_password_area) = {
             let chunks = Layout::default()
@@ -96,7 +80,7 @@ impl RequestPassword {
             {
                 if let Some(user_name) = &self.user_name {
                     Line::from(vec![
-                        Span::raw(" Username ").bold().bg(Color::DarkGray),
+                        Span::raw(" Username ").bold().bg(theme.input),
                         Span::from("  "),
                         Span::from(user_name),
                     ])
@@ -106,7 +90,7 @@ impl RequestPassword {
             },
             Line::from(""),
             Line::from(vec![
-                Span::raw(" Password ").bold().bg(Color::DarkGray),
+                Span::raw(" Password ").bold().bg(theme.input),
                 Span::from("  "),
                 Span::from({
                     if self.show_password {
@@ -120,11 +104,11 @@ impl RequestPassword {
 
         let form = List::new(items);
 
-        let show_password_icon = if self.show_password {
-            Text::from("\n\n ").centered()
-        } else {
-            Text::from("\n\n ").centered()
-        };
+        let show_password_icon = Text::from(format!(
+            "\n\n{}",
+            icons.password_visibility(self.show_password)
+        ))
+        .centered();
 
         frame.render_widget(Clear, area);
 
@@ -132,7 +116,7 @@ impl RequestPassword {
             Block::new()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Thick)
-                .border_style(Style::default().fg(Color::Green)),
+                .border_style(theme.border(true)),
             area,
         );
         frame.render_widget(
@@ -4,15 +4,19 @@ use std::{fs::OpenOptions, io::Write, path::Path};
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     Frame,
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Stylize},
+    layout::{Constraint, Direction, Layout, Position, Rect},
+    style::Stylize,
     text::{Line, Span},
     widgets::{HighlightSpacing, List, ListState},
 };
 
 use tui_input::{Input, backend::crossterm::EventHandler};
 
-use crate::{iwd_network_name, mode::station::auth::entreprise::ERROR_PADDING};
+use crate::theme::Theme;
+use crate::{
+    iwd_network_name,
+    mode::station::auth::entreprise::{ERROR_PADDING, list_item_at},
+};
 
 fn pad_string(input: &str, length: usize) -> String {
     let current_length = input.chars().count();
@@ -33,6 +37,15 @@ pub enum FocusedInput {
     KeyPassphrase,
 }
 
+/// The fields in the order they are drawn, every field is followed by its error line.
+const FIELDS: [FocusedInput; 5] = [
+    FocusedInput::CaCert,
+    FocusedInput::Identity,
+    FocusedInput::ClientCert,
+    FocusedInput::ClientKey,
+    FocusedInput::KeyPassphrase,
+];
+
 #[derive(Debug, Clone, Default)]
 pub struct TLS {
     ca_cert: UserInputField,
@@ -42,6 +55,7 @@ pub struct TLS {
     key_passphrase: UserInputField,
     pub focused_input: FocusedInput,
     state: ListState,
+    area: Rect,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -242,7 +256,20 @@ AutoConnect=true",
         }
     }
 
-    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+    /// Focuses the field drawn at the position, returns whether there is one.
+    pub fn click(&mut self, position: Position) -> bool {
+        let Some(index) = list_item_at(self.area, self.state.offset(), position) else {
+            return false;
+        };
+        let Some(focused_input) = FIELDS.get(index / 2) else {
+            return false;
+        };
+        self.focused_input = focused_input.clone();
+        self.state.select(Some(index - index % 2));
+        true
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
         let layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
@@ -267,9 +294,9 @@ AutoConnect=true",
             Line::from(vec![
                 Span::from(pad_string(" CA Cert", 20))
                     .bold()
-                    .bg(Color::DarkGray),
+                    .bg(theme.input),
                 Span::from("  "),
-                Span::from(pad_string(self.ca_cert.field.value(), 50)).bg(Color::DarkGray),
+                Span::from(pad_string(self.ca_cert.field.value(), 50)).bg(theme.input),
             ]),
             Line::from(vec![Span::from(ERROR_PADDING), {
                 if let Some(error) = &self.ca_cert.error {
@@ -278,13 +305,13 @@ AutoConnect=true",
                     Span::from("")
                 }
             }])
-            .red(),
+            .fg(theme.error),
             Line::from(vec![
                 Span::from(pad_string(" Identity", 20))
                     .bold()
-                    .bg(Color::DarkGray),
+                    .bg(theme.input),
                 Span::from("  "),
-                Span::from(pad_string(self.identity.field.value(), 50)).bg(Color::DarkGray),
+                Span::from(pad_string(self.identity.field.value(), 50)).bg(theme.input),
             ]),
             Line::from(vec![Span::from(ERROR_PADDING), {
                 if let Some(error) = &self.identity.error {
@@ -293,13 +320,13 @@ AutoConnect=true",
                     Span::from("")
                 }
             }])
-            .red(),
+            .fg(theme.error),
             Line::from(vec![
                 Span::from(pad_string(" Client Cert", 20))
                     .bold()
-                    .bg(Color::DarkGray),
+                    .bg(theme.input),
                 Span::from("  "),
-                Span::from(pad_string(self.client_cert.field.value(), 50)).bg(Color::DarkGray),
+                Span::from(pad_string(self.client_cert.field.value(), 50)).bg(theme.input),
             ]),
             Line::from(vec![Span::from(ERROR_PADDING), {
                 if let Some(error) = &self.client_cert.error {
@@ -308,13 +335,13 @@ AutoConnect=true",
                     Span::from("")
                 }
             }])
-            .red(),
+            .fg(theme.error),
             Line::from(vec![
                 Span::from(pad_string(" Client Key", 20))
                     .bold()
-                    .bg(Color::DarkGray),
+                    .bg(theme.input),
                 Span::from("  "),
-                Span::from(pad_string(self.client_key.field.value(), 50)).bg(Color::DarkGray),
+                Span::from(pad_string(self.client_key.field.value(), 50)).bg(theme.input),
             ]),
             Line::from(vec![Span::from(ERROR_PADDING), {
                 if let Some(error) = &self.client_key.error {
@@ -323,13 +350,13 @@ AutoConnect=true",
                     Span::from("")
                 }
             }])
-            .red(),
+            .fg(theme.error),
             Line::from(vec![
                 Span::from(pad_string(" Key Passphrase", 20))
                     .bold()
-                    .bg(Color::DarkGray),
+                    .bg(theme.input),
                 Span::from("  "),
-                Span::from(pad_string(self.key_passphrase.field.value(), 50)).bg(Color::DarkGray),
+                Span::from(pad_string(self.key_passphrase.field.value(), 50)).bg(theme.input),
             ]),
             Line::from(vec![Span::from(ERROR_PADDING), {
                 if let Some(error) = &self.key_passphrase.error {
@@ -338,13 +365,14 @@ AutoConnect=true",
                     Span::from("")
                 }
             }])
-            .red(),
+            .fg(theme.error),
         ];
 
         let list = List::new(items)
             .highlight_symbol("> ")
             .highlight_spacing(HighlightSpacing::Always);
 
+        self.area = block;
         frame.render_stateful_widget(list, block, &mut self.state);
     }
 }
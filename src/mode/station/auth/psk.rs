@@ -1,10 +1,12 @@
 use crate::agent::AuthAgent;
+use crate::layout::popup_area;
+use crate::{icons::Icons, theme::Theme};
 use anyhow::Result;
 
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Style, Stylize},
+    layout::{Alignment, Constraint, Direction, Layout, Position, Rect},
+    style::{Style, Stylize},
     text::{Line, Span, Text},
     widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph},
 };
@@ -14,6 +16,8 @@ use tui_input::Input;
 pub struct Psk {
     pub passphrase: Input,
     pub show_password: bool,
+    passphrase_area: Rect,
+    show_password_area: Rect,
 }
 
 impl Default for Psk {
@@ -21,6 +25,8 @@ impl Default for Psk {
         Self {
             passphrase: Input::default(),
             show_password: true,
+            passphrase_area: Rect::default(),
+            show_password_area: Rect::default(),
         }
     }
 }
@@ -44,26 +50,29 @@ impl Psk {
         self.passphrase.reset();
         Ok(())
     }
-    pub fn render(&self, frame: &mut Frame, network_name: Option<String>) {
-        let popup_layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Fill(1),
-                Constraint::Length(8),
-                Constraint::Fill(1),
-            ])
-            .flex(ratatui::layout::Flex::SpaceBetween)
-            .split(frame.area());
-
-        let area = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Fill(1),
-                Constraint::Length(80),
-                Constraint::Fill(1),
-            ])
-            .flex(ratatui::layout::Flex::SpaceBetween)
-            .split(popup_layout[1])[1];
+
+    /// Shows or hides the password, or moves the cursor where the passphrase is clicked.
+    pub fn click(&mut self, position: Position) {
+        if self.show_password_area.contains(position) {
+            self.show_password = !self.show_password;
+        } else if self.passphrase_area.contains(position) {
+            let pass_len = self.passphrase.value().chars().count();
+            let inner_width = self.passphrase_area.width.saturating_sub(2) as usize;
+            let pad_left = inner_width.saturating_sub(pass_len) / 2;
+            let start = self.passphrase_area.x + 1 + pad_left as u16;
+            let cursor = position.x.saturating_sub(start) as usize;
+            self.passphrase = self.passphrase.clone().with_cursor(cursor);
+        }
+    }
+
+    pub fn render(
+        &mut self,
+        frame: &mut Frame,
+        network_name: Option<String>,
+        theme: &Theme,
+        icons: Icons,
+    ) {
+        let area = popup_area(frame.area(), 80, 8);
 
         let (text_area, passkey_area, show_password_area) = {
             let chunks = Layout::default()
@@ -100,6 +109,9 @@ impl Psk {
             (area1[1], area2[1], area2[2])
         };
 
+        self.passphrase_area = passkey_area;
+        self.show_password_area = show_password_area;
+
         let text = if let Some(name) = network_name {
             Line::from(vec![
                 Span::raw("Enter the password for "),
@@ -111,7 +123,7 @@ impl Psk {
 
         let text = Paragraph::new(text.centered())
             .alignment(Alignment::Center)
-            .style(Style::default().fg(Color::White))
+            .style(Style::default().fg(theme.text))
             .block(Block::new().padding(Padding::uniform(1)));
 
         let passphrase_str = if self.show_password {
@@ -124,14 +136,11 @@ impl Psk {
 
         let passkey = Paragraph::new(passphrase_str)
             .alignment(Alignment::Center)
-            .style(Style::default().fg(Color::White))
-            .block(Block::new().style(Style::default().bg(Color::DarkGray)));
+            .style(Style::default().fg(theme.text))
+            .block(Block::new().style(Style::default().bg(theme.input)));
 
-        let show_password_icon = if self.show_password {
-            Text::from("󰈈 ").centered()
-        } else {
-            Text::from("󰈉 ").centered()
-        };
+        let show_password_icon =
+            Text::from(icons.password_visibility(self.show_password)).centered();
 
         frame.render_widget(Clear, area);
 
@@ -139,8 +148,8 @@ impl Psk {
             Block::new()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Thick)
-                .style(Style::default().green())
-                .border_style(Style::default().fg(Color::Green)),
+                .style(Style::default().fg(theme.border_focused))
+                .border_style(theme.border(true)),
             area,
         );
         frame.render_widget(text, text_area);
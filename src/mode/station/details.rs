@@ -0,0 +1,319 @@
+use iwdrs::{
+    hidden_network::HiddenNetwork, network::NetworkType,
+    station::diagnostics::ActiveStationDiagnostics,
+};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{
+        Block, BorderType, Borders, Cell, Clear, Padding, Paragraph, Row, Table, TableState, Wrap,
+    },
+};
+
+use crate::{
+    backend::BssInfo,
+    config::Config,
+    iwd_network_file,
+    layout::popup_area,
+    mode::station::{
+        known_network::KnownNetwork,
+        network::{Network, frequency_band, signal_percentage},
+    },
+};
+
+// The rows of access points shown at once
+const BSS_ROWS: usize = 8;
+
+/// Everything known about a network, to find out why the station picks an access point.
+#[derive(Debug, Clone)]
+pub struct NetworkDetails {
+    /// `None` for the hidden networks, which are only known by their address
+    pub name: Option<String>,
+    pub network_type: NetworkType,
+    /// Signal strength in 100 * dBm, `None` for the networks out of reach
+    pub signal: Option<i16>,
+    pub is_connected: bool,
+    pub known_network: Option<KnownNetwork>,
+    pub basic_service_sets: Vec<BssInfo>,
+    /// The address of the access point the station is connected to
+    pub connected_bss: Option<String>,
+    pub bss_state: TableState,
+    /// iwd runs with `-E`, the access point to connect through can be picked
+    pub is_developer_mode: bool,
+}
+
+impl NetworkDetails {
+    /// A network in reach, the signal of the connected access point comes from the diagnostics
+    /// when iwd does not give it.
+    pub async fn from_network(
+        network: &Network,
+        signal: i16,
+        diagnostic: Option<&ActiveStationDiagnostics>,
+    ) -> Self {
+        let mut basic_service_sets = network.basic_service_sets().await.unwrap_or_default();
+
+        let connected_bss = diagnostic
+            .filter(|_| network.is_connected)
+            .map(|diagnostic| {
+                if let Some(bss) = basic_service_sets
+                    .iter_mut()
+                    .find(|bss| bss.address.eq_ignore_ascii_case(&diagnostic.connected_bss))
+                {
+                    bss.signal = bss.signal.or(diagnostic.rssi.map(|rssi| rssi * 100));
+                    bss.frequency_mhz = bss.frequency_mhz.or(Some(diagnostic.frequency_mhz));
+                }
+                diagnostic.connected_bss.clone()
+            });
+
+        Self {
+            name: Some(network.name.clone()),
+            network_type: network.network_type,
+            signal: Some(signal),
+            is_connected: network.is_connected,
+            known_network: network.known_network.clone(),
+            basic_service_sets,
+            connected_bss,
+            bss_state: TableState::default().with_selected(0),
+            is_developer_mode: false,
+        }
+    }
+
+    /// A known network out of reach, without any access point.
+    pub fn from_known_network(network: &KnownNetwork) -> Self {
+        Self {
+            name: Some(network.name.clone()),
+            network_type: network.network_type,
+            signal: None,
+            is_connected: false,
+            known_network: Some(network.clone()),
+            basic_service_sets: Vec::new(),
+            connected_bss: None,
+            bss_state: TableState::default(),
+            is_developer_mode: false,
+        }
+    }
+
+    pub fn from_hidden_network(network: &HiddenNetwork) -> Self {
+        Self {
+            name: None,
+            network_type: network.network_type,
+            signal: Some(network.signal_strength),
+            is_connected: false,
+            known_network: None,
+            basic_service_sets: vec![BssInfo {
+                address: network.address.clone(),
+                signal: Some(network.signal_strength),
+                frequency_mhz: None,
+            }],
+            connected_bss: None,
+            bss_state: TableState::default().with_selected(0),
+            is_developer_mode: false,
+        }
+    }
+
+    /// Follows the connection while the details are shown, e.g. after a roam.
+    pub fn refresh_connection(
+        &mut self,
+        connected_network: Option<&Network>,
+        diagnostic: Option<&ActiveStationDiagnostics>,
+    ) {
+        self.is_connected =
+            connected_network.is_some_and(|network| self.name.as_ref() == Some(&network.name));
+        self.connected_bss = diagnostic
+            .filter(|_| self.is_connected)
+            .map(|diagnostic| diagnostic.connected_bss.clone());
+    }
+
+    pub fn selected_bss(&self) -> Option<&BssInfo> {
+        self.bss_state
+            .selected()
+            .and_then(|index| self.basic_service_sets.get(index))
+    }
+
+    pub fn scroll_down(&mut self) {
+        if self.basic_service_sets.is_empty() {
+            return;
+        }
+        let i = self
+            .bss_state
+            .selected()
+            .map_or(0, |i| (i + 1).min(self.basic_service_sets.len() - 1));
+        self.bss_state.select(Some(i));
+    }
+
+    pub fn scroll_up(&mut self) {
+        let i = self.bss_state.selected().map_or(0, |i| i.saturating_sub(1));
+        self.bss_state.select(Some(i));
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, config: &Config) {
+        let theme = config.theme;
+        let yes_no = |value: bool| if value { "Yes" } else { "No" }.to_string();
+        let row = |key: &'static str, value: String| {
+            Row::new(vec![
+                Cell::from(key).style(Style::default().bold().fg(theme.header)),
+                Cell::from(value),
+            ])
+        };
+
+        let mut rows = match &self.name {
+            Some(name) => vec![
+                row("SSID", name.clone()),
+                row("SSID (hex)", hex::encode(name)),
+            ],
+            None => vec![row("SSID", "Hidden".to_string())],
+        };
+        rows.push(row("Security", self.network_type.to_string()));
+        rows.push(row(
+            "Signal",
+            self.signal
+                .map(|signal| format!("{}% ({} dBm)", signal_percentage(signal), signal / 100))
+                .unwrap_or("Out of reach".to_string()),
+        ));
+        rows.push(row("Connected", yes_no(self.is_connected)));
+
+        match &self.known_network {
+            Some(known_network) => {
+                rows.push(row("Hidden", yes_no(known_network.is_hidden)));
+                rows.push(row("Autoconnect", yes_no(known_network.is_autoconnect)));
+                rows.push(row(
+                    "Last connected",
+                    known_network
+                        .last_connected
+                        .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+                        .unwrap_or("Never".to_string()),
+                ));
+                rows.push(row(
+                    "File",
+                    iwd_network_file(&known_network.name, known_network.network_type)
+                        .unwrap_or("-".to_string()),
+                ));
+            }
+            None => rows.push(row("Known", yes_no(false))),
+        }
+
+        let bss_rows: Vec<Row> = self
+            .basic_service_sets
+            .iter()
+            .map(|bss| {
+                let is_connected = self
+                    .connected_bss
+                    .as_ref()
+                    .is_some_and(|address| address.eq_ignore_ascii_case(&bss.address));
+
+                Row::new(vec![
+                    Line::from(if is_connected {
+                        config.icons.connected()
+                    } else {
+                        ""
+                    })
+                    .centered(),
+                    Line::from(bss.address.clone()),
+                    Line::from(
+                        bss.signal
+                            .map(|signal| {
+                                format!("{}% ({} dBm)", signal_percentage(signal), signal / 100)
+                            })
+                            .unwrap_or("-".to_string()),
+                    ),
+                    Line::from(
+                        bss.frequency_mhz
+                            .map(|frequency| format!("{} MHz", frequency))
+                            .unwrap_or("-".to_string()),
+                    ),
+                    Line::from(
+                        bss.frequency_mhz
+                            .map(frequency_band)
+                            .unwrap_or("-")
+                            .to_string(),
+                    ),
+                ])
+            })
+            .collect();
+
+        // iwd only gives the signal of every access point and lets one be picked in developer mode
+        let hint = (!self.is_developer_mode && !self.basic_service_sets.is_empty()).then_some(
+            "iwd does not run in developer mode (iwd -E): the access point to connect through \
+             can not be picked and only the signal of the connected one is known",
+        );
+
+        let info_height = rows.len() as u16;
+        let bss_height = match bss_rows.len() {
+            0 => 1,
+            n => n.min(BSS_ROWS) as u16 + 2,
+        };
+        let hint_height = if hint.is_some() { 3 } else { 0 };
+
+        // The borders and the padding take two lines on each side
+        let area = popup_area(
+            frame.area(),
+            80,
+            info_height + 1 + bss_height + hint_height + 4,
+        );
+
+        let block = Block::default()
+            .title(" Network Details ")
+            .title_style(Style::default().bold())
+            .title_alignment(Alignment::Center)
+            .padding(Padding::uniform(1))
+            .borders(Borders::ALL)
+            .border_style(theme.border(true))
+            .border_type(BorderType::Thick);
+
+        let [info_area, _, bss_area, hint_area] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(info_height),
+                Constraint::Length(1),
+                Constraint::Min(1),
+                Constraint::Length(hint_height),
+            ])
+            .areas(block.inner(area));
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let info = Table::new(rows, [Constraint::Length(16), Constraint::Fill(1)])
+            .column_spacing(3)
+            .style(Style::default().fg(theme.text));
+        frame.render_widget(info, info_area);
+
+        if bss_rows.is_empty() {
+            frame.render_widget(
+                Paragraph::new("No access point in reach").fg(theme.dimmed),
+                bss_area,
+            );
+        } else {
+            let bss_table = Table::new(
+                bss_rows,
+                [
+                    Constraint::Length(2),
+                    Constraint::Length(17),
+                    Constraint::Length(14),
+                    Constraint::Length(9),
+                    Constraint::Length(7),
+                ],
+            )
+            .header(
+                Row::new(vec!["", "Access point", "Signal", "Frequency", "Band"])
+                    .style(Style::default().bold().fg(theme.header))
+                    .bottom_margin(1),
+            )
+            .column_spacing(2)
+            .style(Style::default().fg(theme.text))
+            .row_highlight_style(theme.highlight());
+            frame.render_stateful_widget(bss_table, bss_area, &mut self.bss_state);
+        }
+
+        if let Some(hint) = hint {
+            frame.render_widget(
+                Paragraph::new(vec![Line::from(""), Line::from(hint)])
+                    .wrap(Wrap { trim: true })
+                    .fg(theme.warning),
+                hint_area,
+            );
+        }
+    }
+}
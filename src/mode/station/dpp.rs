@@ -0,0 +1,116 @@
+use anyhow::Result;
+use qrcode::QrCode;
+use std::cmp;
+use tui_qrcode::{Colors, QrCodeWidget};
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Flex, Layout, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+};
+
+use crate::{app::FocusedBlock, backend::DppRole, layout::popup_area, theme::Theme};
+
+/// A running Wi-Fi Easy Connect (DPP) exchange, its URI is shown as a QR code for the other
+/// side to scan.
+#[derive(Clone)]
+pub struct Dpp {
+    pub role: DppRole,
+    /// The network handed over by the configurator
+    pub network_name: Option<String>,
+    uri: String,
+    qr_code: QrCode,
+}
+
+impl Dpp {
+    pub fn new(role: DppRole, uri: String, network_name: Option<String>) -> Result<Self> {
+        let qr_code = QrCode::new(&uri)?;
+        Ok(Self {
+            role,
+            network_name,
+            uri,
+            qr_code,
+        })
+    }
+
+    /// The block the exchange was started from.
+    pub fn parent_block(&self) -> FocusedBlock {
+        match self.role {
+            DppRole::Enrollee => FocusedBlock::Device,
+            DppRole::Configurator => FocusedBlock::KnownNetworks,
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let widget = QrCodeWidget::new(self.qr_code.clone()).colors(Colors::Inverted);
+        let size = widget.size(Rect::new(0, 0, 80, 80));
+
+        let (title, hint) = match (self.role, &self.network_name) {
+            (DppRole::Configurator, Some(network_name)) => (
+                format!("Share {}", network_name),
+                "Scan this code from the device to join the network, the passphrase stays hidden",
+            ),
+            _ => (
+                "Provision this device".to_string(),
+                "Scan this code from a configurator, e.g. a phone, to receive a network",
+            ),
+        };
+
+        let block_width = cmp::max(size.width, 60) + 6;
+        let block = popup_area(frame.area(), block_width, size.height + 12);
+
+        let [title_block, qr_block, hint_block, uri_block] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Fill(1),
+                Constraint::Length(3),
+                Constraint::Length(3),
+            ])
+            .margin(2)
+            .areas(block);
+
+        frame.render_widget(Clear, block);
+        frame.render_widget(
+            Block::new()
+                .borders(Borders::all())
+                .border_type(BorderType::Thick)
+                .border_style(theme.border(true)),
+            block,
+        );
+        frame.render_widget(Line::from(title).centered().bold(), title_block);
+
+        let [qr_block] = Layout::horizontal([Constraint::Length(size.width)])
+            .flex(Flex::Center)
+            .areas(qr_block);
+
+        // A truncated QR code can not be scanned
+        if qr_block.width < size.width || qr_block.height < size.height {
+            frame.render_widget(
+                Paragraph::new("Enlarge the terminal to show the QR code")
+                    .centered()
+                    .wrap(Wrap { trim: true }),
+                qr_block,
+            );
+        } else {
+            frame.render_widget(widget, qr_block);
+        }
+
+        frame.render_widget(
+            Paragraph::new(vec![Line::from(""), Line::from(hint)])
+                .centered()
+                .wrap(Wrap { trim: true })
+                .fg(theme.text),
+            hint_block,
+        );
+        frame.render_widget(
+            Paragraph::new(self.uri.as_str())
+                .centered()
+                .wrap(Wrap { trim: false })
+                .fg(theme.dimmed),
+            uri_block,
+        );
+    }
+}
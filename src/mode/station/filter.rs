@@ -0,0 +1,67 @@
+use iwdrs::network::NetworkType;
+use tui_input::Input;
+
+/// Narrows the network tables down to the networks matching every term of the query.
+///
+/// A term matches a part of the SSID, a security type (`open`, `wep`, `psk` or `8021x`) or,
+/// written `>N`, a signal of at least N percent.
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub input: Input,
+    /// Whether the keys are typed in the query
+    pub is_editing: bool,
+}
+
+impl Filter {
+    pub fn is_active(&self) -> bool {
+        !self.input.value().trim().is_empty()
+    }
+
+    pub fn query(&self) -> &str {
+        self.input.value().trim()
+    }
+
+    /// Whether the network matches, `signal` is the percentage and is missing for the networks
+    /// that are out of reach.
+    pub fn matches(&self, name: &str, network_type: NetworkType, signal: Option<i16>) -> bool {
+        let name = name.to_lowercase();
+        let security = network_type.to_string().to_lowercase();
+
+        self.query().split_whitespace().all(|term| {
+            let term = term.to_lowercase();
+
+            if let Some(min_signal) = term
+                .strip_prefix(">=")
+                .or(term.strip_prefix('>'))
+                .and_then(|value| value.trim_end_matches('%').parse::<i16>().ok())
+            {
+                return signal.is_some_and(|signal| signal >= min_signal);
+            }
+
+            term == security
+                || (term == "eap" && network_type == NetworkType::Eap)
+                || name.contains(&term)
+        })
+    }
+}
+
+/// The row after the selected one, or the first one when `wrap` is set and the last one is
+/// selected. `rows` are the indices of the networks shown in the table.
+pub fn next_row(rows: &[usize], selected: Option<usize>, wrap: bool) -> Option<usize> {
+    match selected.and_then(|selected| rows.iter().position(|row| *row == selected)) {
+        Some(position) if position + 1 < rows.len() => Some(rows[position + 1]),
+        Some(_) if wrap => rows.first().copied(),
+        Some(position) => Some(rows[position]),
+        None => rows.first().copied(),
+    }
+}
+
+/// The row before the selected one, or the last one when `wrap` is set and the first one is
+/// selected.
+pub fn previous_row(rows: &[usize], selected: Option<usize>, wrap: bool) -> Option<usize> {
+    match selected.and_then(|selected| rows.iter().position(|row| *row == selected)) {
+        Some(0) if wrap => rows.last().copied(),
+        Some(position) => Some(rows[position.saturating_sub(1)]),
+        None => rows.first().copied(),
+    }
+}
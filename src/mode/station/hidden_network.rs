@@ -1,12 +1,14 @@
 use crate::event::Event;
+use crate::layout::popup_area;
+use crate::theme::Theme;
 
 use crossterm::event::{KeyCode, KeyEvent};
 use tokio::sync::mpsc::UnboundedSender;
 
 use ratatui::{
     Frame,
-    layout::{Constraint, Direction, Layout, Margin},
-    style::{Color, Style, Stylize},
+    layout::{Constraint, Direction, Layout, Margin, Position, Rect},
+    style::Stylize,
     text::Text,
     widgets::{Block, BorderType, Borders, Clear},
 };
@@ -35,6 +37,7 @@ impl UserInputField {
 #[derive(Clone, Default)]
 pub struct ConnectHiddenNetwork {
     ssid: UserInputField,
+    ssid_area: Rect,
 }
 
 impl ConnectHiddenNetwork {
@@ -66,26 +69,20 @@ impl ConnectHiddenNetwork {
         }
     }
 
-    pub fn render(&self, frame: &mut Frame) {
-        let popup_layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Fill(1),
-                Constraint::Length(10),
-                Constraint::Fill(1),
-            ])
-            .flex(ratatui::layout::Flex::SpaceBetween)
-            .split(frame.area());
-
-        let area = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Fill(1),
-                Constraint::Length(80),
-                Constraint::Fill(1),
-            ])
-            .flex(ratatui::layout::Flex::SpaceBetween)
-            .split(popup_layout[1])[1];
+    /// Moves the cursor where the SSID is clicked.
+    pub fn click(&mut self, position: Position) {
+        if !self.ssid_area.contains(position) {
+            return;
+        }
+        let inner_width = self.ssid_area.width.saturating_sub(2) as usize;
+        let pad_left = inner_width.saturating_sub(self.ssid.len()) / 2;
+        let start = self.ssid_area.x + 1 + pad_left as u16;
+        let cursor = position.x.saturating_sub(start) as usize;
+        self.ssid.field = self.ssid.field.clone().with_cursor(cursor);
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, theme: &Theme) {
+        let area = popup_area(frame.area(), 80, 10);
 
         let (message_area, ssid_area, error_area) = {
             let chunks = Layout::default()
@@ -104,11 +101,13 @@ impl ConnectHiddenNetwork {
             (chunks[1], chunks[3], chunks[5])
         };
 
+        self.ssid_area = ssid_area;
+
         let message = Text::from("Enter the SSID of the hidden network").centered();
 
-        let ssid = Text::from(self.ssid.value()).bg(Color::DarkGray).centered();
+        let ssid = Text::from(self.ssid.value()).bg(theme.input).centered();
         let error = Text::from(self.ssid.error.clone().unwrap_or_default())
-            .red()
+            .fg(theme.error)
             .centered();
 
         frame.render_widget(Clear, area);
@@ -117,7 +116,7 @@ impl ConnectHiddenNetwork {
             Block::new()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Thick)
-                .border_style(Style::default().fg(Color::Green)),
+                .border_style(theme.border(true)),
             area,
         );
         frame.render_widget(
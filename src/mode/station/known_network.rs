@@ -1,18 +1,20 @@
 use anyhow::Result;
 use chrono::{DateTime, FixedOffset};
+use std::sync::Arc;
 
-use iwdrs::{known_network::KnownNetwork as iwdKnownNetwork, network::NetworkType};
+use iwdrs::network::NetworkType;
 
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::{
+    backend::{KnownNetworkInfo, WifiBackend},
     event::Event,
     notification::{Notification, NotificationLevel},
 };
 
 #[derive(Debug, Clone)]
 pub struct KnownNetwork {
-    pub n: iwdKnownNetwork,
+    backend: Arc<dyn WifiBackend>,
     pub name: String,
     pub network_type: NetworkType,
     pub is_autoconnect: bool,
@@ -21,28 +23,29 @@ pub struct KnownNetwork {
 }
 
 impl KnownNetwork {
-    pub async fn new(n: iwdKnownNetwork) -> Result<Self> {
-        let name = n.name().await?;
-        let network_type = n.network_type().await?;
-        let is_autoconnect = n.get_autoconnect().await?;
-        let is_hidden = n.hidden().await?;
-        let last_connected = match n.last_connected_time().await {
-            Ok(v) => DateTime::parse_from_rfc3339(&v).ok(),
-            Err(_) => None,
-        };
+    pub fn new(backend: Arc<dyn WifiBackend>, network: KnownNetworkInfo) -> Self {
+        Self {
+            backend,
+            name: network.name,
+            network_type: network.network_type,
+            is_autoconnect: network.is_autoconnect,
+            is_hidden: network.is_hidden,
+            last_connected: network.last_connected,
+        }
+    }
 
-        Ok(Self {
-            n,
-            name,
-            network_type,
-            is_autoconnect,
-            is_hidden,
-            last_connected,
-        })
+    /// Returns all the known networks.
+    pub async fn all(backend: Arc<dyn WifiBackend>) -> Result<Vec<Self>> {
+        Ok(backend
+            .known_networks()
+            .await?
+            .into_iter()
+            .map(|net| Self::new(backend.clone(), net))
+            .collect())
     }
 
     pub async fn forget(&self, sender: UnboundedSender<Event>) -> Result<()> {
-        match self.n.forget().await {
+        match self.backend.forget(&self.name).await {
             Ok(()) => {
                 let _ = Notification::send(
                     format!("The Network {} is removed", self.name),
@@ -61,7 +64,7 @@ impl KnownNetwork {
 
     pub async fn toggle_autoconnect(&self, sender: UnboundedSender<Event>) -> Result<()> {
         if self.is_autoconnect {
-            match self.n.set_autoconnect(false).await {
+            match self.backend.set_autoconnect(&self.name, false).await {
                 Ok(()) => {
                     Notification::send(
                         format!("Disable Autoconnect for: {}", self.name),
@@ -74,7 +77,7 @@ impl KnownNetwork {
                 }
             }
         } else {
-            match self.n.set_autoconnect(true).await {
+            match self.backend.set_autoconnect(&self.name, true).await {
                 Ok(()) => {
                     Notification::send(
                         format!("Enable Autoconnect for: {}", self.name),
@@ -0,0 +1,327 @@
+use std::{collections::VecDeque, time::Duration};
+
+use iwdrs::station::diagnostics::ActiveStationDiagnostics;
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::Line,
+    widgets::{
+        Block, BorderType, Borders, Cell, Clear, Padding, Paragraph, RenderDirection, Row,
+        Sparkline, Table,
+    },
+};
+
+use crate::{
+    config::Config,
+    layout::popup_area,
+    mode::station::network::{frequency_band, frequency_channel, signal_percentage},
+    theme::Theme,
+};
+
+// One sample is taken every tick, i.e. every second, so this is five minutes of history
+const HISTORY_LENGTH: usize = 300;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Sample {
+    /// In dBm
+    rssi: Option<i16>,
+    rx_rate_kbps: Option<u64>,
+    tx_rate_kbps: Option<u64>,
+}
+
+/// The history of the connection, to see how the link changes while moving around.
+#[derive(Debug, Clone, Default)]
+pub struct LinkQuality {
+    samples: VecDeque<Sample>,
+}
+
+fn format_rate(rate_kbps: Option<u64>) -> String {
+    rate_kbps
+        .map(|rate| format!("{:.1} Mbit/s", rate as f64 / 1000.))
+        .unwrap_or("-".to_string())
+}
+
+fn format_rssi(rssi: Option<i16>) -> String {
+    rssi.map(|rssi| format!("{} dBm", rssi))
+        .unwrap_or("-".to_string())
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match (seconds / 3600, seconds / 60 % 60, seconds % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {:02}s", m, s),
+        (h, m, s) => format!("{}h {:02}m {:02}s", h, m, s),
+    }
+}
+
+impl LinkQuality {
+    /// Records the link at this tick, `None` while disconnected which leaves a gap in the graphs.
+    pub fn push(&mut self, diagnostic: Option<&ActiveStationDiagnostics>) {
+        if self.samples.len() == HISTORY_LENGTH {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(Sample {
+            rssi: diagnostic.and_then(|diagnostic| diagnostic.rssi),
+            rx_rate_kbps: diagnostic.and_then(|diagnostic| diagnostic.rx_rate_kbps),
+            tx_rate_kbps: diagnostic.and_then(|diagnostic| diagnostic.tx_rate_kbps),
+        });
+    }
+
+    pub fn render(
+        &self,
+        frame: &mut Frame,
+        diagnostic: Option<&ActiveStationDiagnostics>,
+        config: &Config,
+    ) {
+        let theme = config.theme;
+
+        // The borders and the padding take two lines on each side
+        let area = popup_area(frame.area(), 90, 8 + 1 + 3 * 5 + 4);
+
+        let block = Block::default()
+            .title(" Link Quality ")
+            .title_style(Style::default().bold())
+            .title_alignment(Alignment::Center)
+            .title_bottom(Line::from(" One bar per second, the latest on the right ").centered())
+            .padding(Padding::uniform(1))
+            .borders(Borders::ALL)
+            .border_style(theme.border(true))
+            .border_type(BorderType::Thick);
+
+        let [infos_area, _, signal_area, rx_area, tx_area] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(8),
+                Constraint::Length(1),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ])
+            .areas(block.inner(area));
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        match diagnostic {
+            Some(diagnostic) => render_infos(frame, infos_area, diagnostic, &theme),
+            None => frame.render_widget(
+                Paragraph::new("Not connected").centered().fg(theme.dimmed),
+                infos_area,
+            ),
+        }
+
+        // Only the latest samples fit in the graphs
+        let width = signal_area.width.saturating_sub(2) as usize;
+        let samples: Vec<Sample> = self
+            .samples
+            .iter()
+            .skip(self.samples.len().saturating_sub(width))
+            .copied()
+            .collect();
+
+        let signals: Vec<Option<i16>> = samples.iter().map(|sample| sample.rssi).collect();
+        render_graph(
+            frame,
+            signal_area,
+            graph_title("Signal", &signals, |rssi| format_rssi(Some(rssi))),
+            signals
+                .iter()
+                .map(|rssi| rssi.map(|rssi| signal_percentage(rssi * 100) as u64))
+                .collect(),
+            Some(100),
+            theme.info,
+            &theme,
+        );
+
+        let rx_rates: Vec<Option<u64>> = samples.iter().map(|sample| sample.rx_rate_kbps).collect();
+        let tx_rates: Vec<Option<u64>> = samples.iter().map(|sample| sample.tx_rate_kbps).collect();
+        for (title, rates, area) in [
+            ("Receive bitrate", rx_rates, rx_area),
+            ("Transmit bitrate", tx_rates, tx_area),
+        ] {
+            render_graph(
+                frame,
+                area,
+                graph_title(title, &rates, |rate| format_rate(Some(rate))),
+                rates,
+                None,
+                theme.header,
+                &theme,
+            );
+        }
+    }
+}
+
+fn render_infos(
+    frame: &mut Frame,
+    area: Rect,
+    diagnostic: &ActiveStationDiagnostics,
+    theme: &Theme,
+) {
+    let key = |key: &'static str| Cell::from(key).style(Style::default().bold().fg(theme.header));
+
+    let channel = diagnostic
+        .channel
+        .map(u32::from)
+        .or(frequency_channel(diagnostic.frequency_mhz));
+
+    let link = Table::new(
+        vec![
+            Row::new(vec![
+                key("Access point"),
+                Cell::from(diagnostic.connected_bss.clone()),
+            ]),
+            Row::new(vec![
+                key("Channel"),
+                Cell::from(
+                    channel
+                        .map(|channel| channel.to_string())
+                        .unwrap_or("-".to_string()),
+                ),
+            ]),
+            Row::new(vec![
+                key("Frequency"),
+                Cell::from(format!(
+                    "{} MHz ({})",
+                    diagnostic.frequency_mhz,
+                    frequency_band(diagnostic.frequency_mhz)
+                )),
+            ]),
+            Row::new(vec![
+                key("Security"),
+                Cell::from(diagnostic.security.to_string()),
+            ]),
+            Row::new(vec![
+                key("Cipher"),
+                Cell::from(
+                    diagnostic
+                        .pairwise_cipher
+                        .as_ref()
+                        .map(|cipher| cipher.to_string())
+                        .unwrap_or("-".to_string()),
+                ),
+            ]),
+            Row::new(vec![
+                key("Signal"),
+                Cell::from(
+                    diagnostic
+                        .rssi
+                        .map(|rssi| format!("{} dBm ({}%)", rssi, signal_percentage(rssi * 100)))
+                        .unwrap_or("-".to_string()),
+                ),
+            ]),
+            Row::new(vec![
+                key("Average signal"),
+                Cell::from(format_rssi(diagnostic.average_rssi)),
+            ]),
+            Row::new(vec![
+                key("Connected for"),
+                Cell::from(
+                    diagnostic
+                        .connected_time
+                        .map(format_duration)
+                        .unwrap_or("-".to_string()),
+                ),
+            ]),
+        ],
+        [Constraint::Length(16), Constraint::Fill(1)],
+    )
+    .column_spacing(2)
+    .style(Style::default().fg(theme.text));
+
+    let optional = |value: Option<String>| Cell::from(value.unwrap_or("-".to_string()));
+
+    let rates = Table::new(
+        vec![
+            Row::new(vec![
+                key("Bitrate"),
+                Cell::from(format_rate(diagnostic.rx_rate_kbps)),
+                Cell::from(format_rate(diagnostic.tx_rate_kbps)),
+            ]),
+            Row::new(vec![
+                key("MCS"),
+                optional(diagnostic.rx_mcs.map(|mcs| mcs.to_string())),
+                optional(diagnostic.tx_mcs.map(|mcs| mcs.to_string())),
+            ]),
+            Row::new(vec![
+                key("Mode"),
+                optional(diagnostic.rx_mode.as_ref().map(|mode| mode.to_string())),
+                optional(diagnostic.tx_mode.as_ref().map(|mode| mode.to_string())),
+            ]),
+        ],
+        [
+            Constraint::Length(8),
+            Constraint::Length(14),
+            Constraint::Length(14),
+        ],
+    )
+    .header(
+        Row::new(vec!["", "Receive", "Transmit"])
+            .style(Style::default().bold().fg(theme.header))
+            .bottom_margin(1),
+    )
+    .column_spacing(2)
+    .style(Style::default().fg(theme.text));
+
+    let [link_area, rates_area] = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Fill(1), Constraint::Length(40)])
+        .areas(area);
+
+    frame.render_widget(link, link_area);
+    frame.render_widget(rates, rates_area);
+}
+
+/// The title of a graph with the latest, lowest and highest values.
+fn graph_title<T: Copy + Ord>(
+    title: &str,
+    values: &[Option<T>],
+    label: impl Fn(T) -> String,
+) -> String {
+    let known = values.iter().flatten().copied();
+    match (known.clone().min(), known.max()) {
+        (Some(min), Some(max)) => format!(
+            " {}  {}  (min {}, max {}) ",
+            title,
+            values
+                .last()
+                .copied()
+                .flatten()
+                .map(&label)
+                .unwrap_or("-".to_string()),
+            label(min),
+            label(max),
+        ),
+        _ => format!(" {} ", title),
+    }
+}
+
+fn render_graph(
+    frame: &mut Frame,
+    area: Rect,
+    title: String,
+    data: Vec<Option<u64>>,
+    max: Option<u64>,
+    color: Color,
+    theme: &Theme,
+) {
+    let mut sparkline = Sparkline::default()
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(theme.border(false)),
+        )
+        // The latest sample is drawn on the right edge, the older ones scroll to the left
+        .data(data.into_iter().rev().collect::<Vec<Option<u64>>>())
+        .direction(RenderDirection::RightToLeft)
+        .style(Style::default().fg(color))
+        .absent_value_symbol(" ");
+    if let Some(max) = max {
+        sparkline = sparkline.max(max);
+    }
+
+    frame.render_widget(sparkline, area);
+}
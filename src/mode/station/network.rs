@@ -1,19 +1,54 @@
 use anyhow::Result;
+use std::sync::Arc;
+
 use iwdrs::{
     error::{IWDError, network::ConnectError},
-    network::{Network as iwdNetwork, NetworkType},
+    network::NetworkType,
 };
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::{
+    backend::{BssInfo, NetworkInfo, WifiBackend},
     event::Event,
     mode::station::known_network::KnownNetwork,
     notification::{Notification, NotificationLevel},
 };
 
+/// Converts a signal strength reported by iwd (in 100 * dBm) to a percentage.
+pub fn signal_percentage(signal: i16) -> i16 {
+    if signal / 100 >= -50 {
+        100
+    } else {
+        2 * (100 + signal / 100)
+    }
+}
+
+/// Name of the band a frequency belongs to.
+pub fn frequency_band(frequency_mhz: u32) -> &'static str {
+    match frequency_mhz {
+        2400..2500 => "2.4 GHz",
+        5150..5925 => "5 GHz",
+        5925..7125 => "6 GHz",
+        _ => "-",
+    }
+}
+
+/// The channel number of a frequency, as iwd only reports it for some drivers.
+pub fn frequency_channel(frequency_mhz: u32) -> Option<u32> {
+    match frequency_mhz {
+        2484 => Some(14),
+        2412..=2472 => Some((frequency_mhz - 2407) / 5),
+        5935 => Some(2),
+        5000..5925 => Some((frequency_mhz - 5000) / 5),
+        5950..7125 => Some((frequency_mhz - 5950) / 5),
+        _ => None,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Network {
-    pub n: iwdNetwork,
+    backend: Arc<dyn WifiBackend>,
+    pub device_name: String,
     pub name: String,
     pub network_type: NetworkType,
     pub is_connected: bool,
@@ -21,45 +56,42 @@ pub struct Network {
 }
 
 impl Network {
-    pub async fn new(n: iwdNetwork) -> Result<Self> {
-        let name = n.name().await?;
-        let network_type = n.network_type().await?;
-        let is_connected = n.connected().await?;
-        let known_network = {
-            match n.known_network().await {
-                Ok(v) => match v {
-                    Some(net) => Some(KnownNetwork::new(net).await.unwrap()),
-                    None => None,
-                },
-                Err(_) => None,
-            }
-        };
+    pub fn new(backend: Arc<dyn WifiBackend>, device_name: String, network: NetworkInfo) -> Self {
+        let known_network = network
+            .known_network
+            .map(|net| KnownNetwork::new(backend.clone(), net));
 
-        Ok(Self {
-            n,
-            name,
-            network_type,
-            is_connected,
+        Self {
+            backend,
+            device_name,
+            name: network.name,
+            network_type: network.network_type,
+            is_connected: network.is_connected,
             known_network,
-        })
+        }
+    }
+
+    /// The access points of the network.
+    pub async fn basic_service_sets(&self) -> Result<Vec<BssInfo>> {
+        self.backend
+            .basic_service_sets(&self.device_name, &self.name)
+            .await
     }
 
     pub async fn connect(&self, sender: UnboundedSender<Event>) -> Result<()> {
-        match self.n.connect().await {
+        match self.backend.connect(&self.device_name, &self.name).await {
             Ok(()) => Notification::send(
                 format!("Connected to {}", self.name),
                 NotificationLevel::Info,
                 &sender,
             )?,
-            Err(e) => match e {
-                IWDError::OperationError(e) => match e {
-                    ConnectError::Aborted => {
-                        Notification::send(e.to_string(), NotificationLevel::Info, &sender)?;
-                    }
-                    _ => {
-                        Notification::send(e.to_string(), NotificationLevel::Error, &sender)?;
-                    }
-                },
+            Err(e) => match e.downcast_ref::<IWDError<ConnectError>>() {
+                Some(IWDError::OperationError(e @ ConnectError::Aborted)) => {
+                    Notification::send(e.to_string(), NotificationLevel::Info, &sender)?;
+                }
+                Some(IWDError::OperationError(e)) => {
+                    Notification::send(e.to_string(), NotificationLevel::Error, &sender)?;
+                }
                 _ => {
                     Notification::send(e.to_string(), NotificationLevel::Error, &sender)?;
                 }
@@ -6,12 +6,14 @@ use tui_qrcode::{Colors, QrCodeWidget};
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Flex, Layout, Rect},
-    style::{Color, Style, Stylize},
+    style::Stylize,
     text::{Line, Span, Text},
-    widgets::{Block, BorderType, Borders, Clear},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
 };
 
 use crate::iwd_network_name;
+use crate::layout::popup_area;
+use crate::theme::Theme;
 
 #[derive(Clone)]
 pub struct Share {
@@ -56,32 +58,14 @@ impl Share {
         }
     }
 
-    pub fn render(&self, frame: &mut Frame) {
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
         let widget = QrCodeWidget::new(self.qr_code.clone()).colors(Colors::Inverted);
         let sim_area = Rect::new(0, 0, 50, 50);
         let size = widget.size(sim_area);
 
         let block_width = cmp::max(size.width as usize, self.passphrase.len() + 12) + 6;
 
-        let block = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Fill(1),
-                Constraint::Length(size.height + 12),
-                Constraint::Fill(1),
-            ])
-            .flex(Flex::SpaceBetween)
-            .split(frame.area())[1];
-
-        let block = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Fill(1),
-                Constraint::Length(block_width as u16),
-                Constraint::Fill(1),
-            ])
-            .flex(Flex::SpaceBetween)
-            .split(block)[1];
+        let block = popup_area(frame.area(), block_width as u16, size.height + 12);
 
         let (title_block, mut qr_block, passphrase_block) = {
             let chunks = Layout::default()
@@ -103,7 +87,7 @@ impl Share {
             Block::new()
                 .borders(Borders::all())
                 .border_type(BorderType::Thick)
-                .border_style(Style::new().green()),
+                .border_style(theme.border(true)),
             block,
         );
         frame.render_widget(
@@ -123,13 +107,23 @@ impl Share {
                 .split(qr_block)[1];
         }
 
-        frame.render_widget(widget, qr_block);
+        // A truncated QR code can not be scanned
+        if qr_block.width < size.width || qr_block.height < size.height {
+            frame.render_widget(
+                Paragraph::new("Enlarge the terminal to show the QR code")
+                    .centered()
+                    .wrap(Wrap { trim: true }),
+                qr_block,
+            );
+        } else {
+            frame.render_widget(widget, qr_block);
+        }
 
         let passphrase = Text::from(vec![
             Line::from(""),
             Line::from(vec![
                 Span::from("Passphrase: "),
-                Span::from(&self.passphrase).bold().bg(Color::DarkGray),
+                Span::from(&self.passphrase).bold().bg(theme.input),
             ])
             .centered(),
         ]);
@@ -0,0 +1,118 @@
+use std::cmp::Ordering;
+
+use chrono::{DateTime, FixedOffset};
+use iwdrs::{hidden_network::HiddenNetwork, network::NetworkType};
+use serde::Deserialize;
+
+use crate::mode::station::{known_network::KnownNetwork, network::Network};
+
+/// The order of the networks in a table. Ties, and the networks without the value (e.g. the
+/// signal of a network out of reach), are sorted by signal and then by name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortKey {
+    #[default]
+    Signal,
+    Name,
+    LastConnected,
+    Security,
+}
+
+impl SortKey {
+    /// The key selected by the `sort` action after this one.
+    pub fn next(self) -> Self {
+        match self {
+            SortKey::Signal => SortKey::Name,
+            SortKey::Name => SortKey::LastConnected,
+            SortKey::LastConnected => SortKey::Security,
+            SortKey::Security => SortKey::Signal,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortKey::Signal => "signal",
+            SortKey::Name => "name",
+            SortKey::LastConnected => "last connected",
+            SortKey::Security => "security",
+        }
+    }
+}
+
+/// What the networks are compared on.
+struct Entry<'a> {
+    name: &'a str,
+    network_type: NetworkType,
+    signal: Option<i16>,
+    last_connected: Option<DateTime<FixedOffset>>,
+}
+
+// From the least to the most secure
+fn security_rank(network_type: NetworkType) -> u8 {
+    match network_type {
+        NetworkType::Open => 0,
+        NetworkType::Wep => 1,
+        NetworkType::Psk => 2,
+        NetworkType::Eap => 3,
+    }
+}
+
+fn compare(key: SortKey, a: &Entry, b: &Entry) -> Ordering {
+    // The strongest signals and the most recent connections come first, `None` is the lowest
+    let by_signal = || b.signal.cmp(&a.signal);
+    let by_name = || a.name.to_lowercase().cmp(&b.name.to_lowercase());
+
+    let ordering = match key {
+        SortKey::Signal => Ordering::Equal,
+        SortKey::Name => by_name(),
+        SortKey::LastConnected => b.last_connected.cmp(&a.last_connected),
+        SortKey::Security => security_rank(a.network_type).cmp(&security_rank(b.network_type)),
+    };
+
+    ordering.then_with(by_signal).then_with(by_name)
+}
+
+/// Sorts the networks in reach, only the known ones have a last connection.
+pub fn sort_networks(networks: &mut [(Network, i16)], key: SortKey) {
+    fn entry((net, signal): &(Network, i16)) -> Entry<'_> {
+        Entry {
+            name: &net.name,
+            network_type: net.network_type,
+            signal: Some(*signal),
+            last_connected: net
+                .known_network
+                .as_ref()
+                .and_then(|known_network| known_network.last_connected),
+        }
+    }
+
+    networks.sort_by(|a, b| compare(key, &entry(a), &entry(b)));
+}
+
+/// Sorts the known networks out of reach, which have no signal.
+pub fn sort_known_networks(networks: &mut [KnownNetwork], key: SortKey) {
+    fn entry(net: &KnownNetwork) -> Entry<'_> {
+        Entry {
+            name: &net.name,
+            network_type: net.network_type,
+            signal: None,
+            last_connected: net.last_connected,
+        }
+    }
+
+    networks.sort_by(|a, b| compare(key, &entry(a), &entry(b)));
+}
+
+/// Sorts the hidden networks, which are named after their address.
+pub fn sort_hidden_networks(networks: &mut [HiddenNetwork], key: SortKey) {
+    fn entry(net: &HiddenNetwork) -> Entry<'_> {
+        Entry {
+            name: &net.address,
+            network_type: net.network_type,
+            signal: Some(net.signal_strength),
+            last_connected: None,
+        }
+    }
+
+    networks.sort_by(|a, b| compare(key, &entry(a), &entry(b)));
+}
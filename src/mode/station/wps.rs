@@ -0,0 +1,171 @@
+use std::time::Instant;
+
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{
+        Block, BorderType, Borders, Clear, LineGauge, Padding, Paragraph, Row, Table, TableState,
+        Wrap,
+    },
+};
+
+use crate::{
+    backend::{P2pPeerInfo, WPS_WALK_TIME},
+    layout::popup_area,
+    theme::Theme,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WpsMethod {
+    PushButton,
+    Pin,
+}
+
+/// A WPS connection. The method is chosen first, then the connection runs until an access
+/// point answers or the walk time is over.
+#[derive(Debug, Clone)]
+pub struct Wps {
+    pub method: WpsMethod,
+    /// The PIN to enter on the access point
+    pub pin: Option<String>,
+    pub started_at: Option<Instant>,
+    /// The Wi-Fi Direct peer to connect to, rather than any access point
+    pub peer: Option<P2pPeerInfo>,
+}
+
+impl Default for Wps {
+    fn default() -> Self {
+        Self {
+            method: WpsMethod::PushButton,
+            pin: None,
+            started_at: None,
+            peer: None,
+        }
+    }
+}
+
+impl Wps {
+    pub fn with_peer(peer: P2pPeerInfo) -> Self {
+        Self {
+            peer: Some(peer),
+            ..Self::default()
+        }
+    }
+
+    pub fn toggle_method(&mut self) {
+        if self.started_at.is_none() {
+            self.method = match self.method {
+                WpsMethod::PushButton => WpsMethod::Pin,
+                WpsMethod::Pin => WpsMethod::PushButton,
+            };
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let area = popup_area(frame.area(), 64, 9);
+
+        let (title, target) = match &self.peer {
+            Some(peer) => (format!(" Connect to {} ", peer.name), "the peer"),
+            None => (" WPS ".to_string(), "the access point"),
+        };
+
+        let block = Block::default()
+            .title(title)
+            .title_style(Style::default().bold())
+            .title_alignment(Alignment::Center)
+            .padding(Padding::uniform(1))
+            .borders(Borders::ALL)
+            .border_style(theme.border(true))
+            .border_type(BorderType::Thick);
+        let inner = block.inner(area);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let Some(started_at) = self.started_at else {
+            let methods = Table::new(
+                vec![
+                    Row::new(vec![
+                        Line::from("Push button").bold(),
+                        Line::from(match self.peer {
+                            Some(_) => "Accept the connection on the peer".to_string(),
+                            None => format!("Press the WPS button of {}", target),
+                        }),
+                    ]),
+                    Row::new(vec![
+                        Line::from("PIN").bold(),
+                        Line::from(format!("Enter a PIN on {}", target)),
+                    ]),
+                ],
+                [Constraint::Length(12), Constraint::Fill(1)],
+            )
+            .column_spacing(2)
+            .style(Style::default().fg(theme.text))
+            .row_highlight_style(theme.highlight());
+
+            let mut state = TableState::default().with_selected(match self.method {
+                WpsMethod::PushButton => 0,
+                WpsMethod::Pin => 1,
+            });
+            let [_, methods_area] = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Fill(1)])
+                .areas(inner);
+            frame.render_stateful_widget(methods, methods_area, &mut state);
+            return;
+        };
+
+        let [instructions_area, pin_area, _, countdown_area] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .areas(inner);
+
+        let instructions = match (&self.pin, &self.peer) {
+            (Some(_), _) => format!("Enter this PIN on {}", target),
+            (None, Some(_)) => "Accept the connection on the peer".to_string(),
+            (None, None) => format!("Press the WPS button of {}", target),
+        };
+        frame.render_widget(
+            Paragraph::new(instructions)
+                .centered()
+                .wrap(Wrap { trim: true })
+                .fg(theme.text),
+            instructions_area,
+        );
+
+        if let Some(pin) = &self.pin {
+            // Split in two like on the access points, e.g. 1234 5670
+            let (first, last) = pin.split_at(pin.len() / 2);
+            frame.render_widget(
+                Line::from(
+                    Span::from(format!(" {} {} ", first, last))
+                        .bold()
+                        .bg(theme.input),
+                )
+                .centered(),
+                pin_area,
+            );
+        }
+
+        let remaining = WPS_WALK_TIME.saturating_sub(started_at.elapsed());
+        frame.render_widget(
+            LineGauge::default()
+                .ratio(remaining.as_secs_f64() / WPS_WALK_TIME.as_secs_f64())
+                .label(format!(
+                    "{}:{:02} left",
+                    remaining.as_secs() / 60,
+                    remaining.as_secs() % 60
+                ))
+                .filled_style(Style::default().fg(theme.info))
+                .style(Style::default().fg(theme.text)),
+            countdown_area,
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use anyhow::Result;
+use std::time::Duration;
+
+use futures::{StreamExt, stream};
+use tokio::{sync::mpsc::UnboundedSender, time::Instant};
+use zbus::{
+    Connection, MatchRule, MessageStream,
+    fdo::{InterfacesAdded, InterfacesRemoved, PropertiesChanged},
+    message::Type,
+};
+
+use crate::event::Event;
+
+const IWD_SERVICE: &str = "net.connman.iwd";
+
+// Signals tend to come in bursts (e.g. at the end of a scan), wait for things to
+// settle before asking for a refresh.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+// Flush anyway when the signals keep coming, so that the UI does not lag behind.
+const MAX_MERGE: Duration = Duration::from_millis(500);
+
+/// The parts of the iwd state that changed since the last refresh.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Changes {
+    pub adapter: bool,
+    pub device: bool,
+    pub station: bool,
+    pub networks: bool,
+    pub access_point: bool,
+}
+
+impl Changes {
+    pub fn all() -> Self {
+        Self {
+            adapter: true,
+            device: true,
+            station: true,
+            networks: true,
+            access_point: true,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.adapter |= other.adapter;
+        self.device |= other.device;
+        self.station |= other.station;
+        self.networks |= other.networks;
+        self.access_point |= other.access_point;
+    }
+
+    fn from_interface(interface: &str) -> Self {
+        let mut changes = Self::default();
+        match interface {
+            "net.connman.iwd.Adapter" => changes.adapter = true,
+            "net.connman.iwd.Device" => changes.device = true,
+            "net.connman.iwd.Station" | "net.connman.iwd.StationDiagnostic" => {
+                changes.station = true;
+                changes.networks = true;
+            }
+            "net.connman.iwd.Network"
+            | "net.connman.iwd.KnownNetwork"
+            | "net.connman.iwd.BasicServiceSet" => changes.networks = true,
+            "net.connman.iwd.AccessPoint" | "net.connman.iwd.AccessPointDiagnostic" => {
+                changes.access_point = true
+            }
+            _ => {}
+        }
+        changes
+    }
+
+    fn from_message(message: &zbus::Message) -> Self {
+        if let Some(signal) = PropertiesChanged::from_message(message.clone())
+            && let Ok(args) = signal.args()
+        {
+            let mut changes = Self::from_interface(args.interface_name().as_str());
+
+            // The networks are only re-ordered by signal strength at the end of a scan
+            if args.interface_name().as_str() == "net.connman.iwd.Station" {
+                changes.networks = args.changed_properties().contains_key("Scanning");
+            }
+            return changes;
+        }
+
+        let mut changes = Self::default();
+
+        if let Some(signal) = InterfacesAdded::from_message(message.clone())
+            && let Ok(args) = signal.args()
+        {
+            args.interfaces_and_properties()
+                .keys()
+                .for_each(|interface| changes.merge(Self::from_interface(interface.as_str())));
+        } else if let Some(signal) = InterfacesRemoved::from_message(message.clone())
+            && let Ok(args) = signal.args()
+        {
+            args.interfaces()
+                .iter()
+                .for_each(|interface| changes.merge(Self::from_interface(interface.as_str())));
+        }
+
+        changes
+    }
+}
+
+/// Listens to the signals emitted by iwd and sends an `Event::Refresh` with the parts of
+/// the state that need to be fetched again.
+pub async fn spawn(sender: UnboundedSender<Event>) -> Result<()> {
+    let connection = Connection::system().await?;
+
+    let properties_rule = MatchRule::builder()
+        .msg_type(Type::Signal)
+        .sender(IWD_SERVICE)?
+        .interface("org.freedesktop.DBus.Properties")?
+        .member("PropertiesChanged")?
+        .build();
+
+    let object_manager_rule = MatchRule::builder()
+        .msg_type(Type::Signal)
+        .sender(IWD_SERVICE)?
+        .interface("org.freedesktop.DBus.ObjectManager")?
+        .build();
+
+    let mut messages = stream::select(
+        MessageStream::for_match_rule(properties_rule, &connection, None).await?,
+        MessageStream::for_match_rule(object_manager_rule, &connection, None).await?,
+    );
+
+    tokio::spawn(async move {
+        while let Some(Ok(message)) = messages.next().await {
+            let mut changes = Changes::from_message(&message);
+            let flush_at = Instant::now() + MAX_MERGE;
+            let mut is_stopped = false;
+
+            loop {
+                let deadline = (Instant::now() + DEBOUNCE).min(flush_at);
+                match tokio::time::timeout_at(deadline, messages.next()).await {
+                    Ok(Some(Ok(message))) => changes.merge(Changes::from_message(&message)),
+                    Ok(_) => {
+                        is_stopped = true;
+                        break;
+                    }
+                    Err(_) => break,
+                }
+            }
+
+            if !changes.is_empty() && sender.send(Event::Refresh(changes)).is_err() {
+                return;
+            }
+
+            if is_stopped {
+                break;
+            }
+        }
+
+        // The signals are not received anymore, the state has to be polled
+        let _ = sender.send(Event::MonitorStopped);
+    });
+
+    Ok(())
+}
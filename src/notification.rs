@@ -2,13 +2,14 @@ use anyhow::Result;
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Text},
     widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
 };
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::event::Event;
+use crate::theme::Theme;
 
 #[derive(Debug, Clone)]
 pub struct Notification {
@@ -25,11 +26,11 @@ pub enum NotificationLevel {
 }
 
 impl Notification {
-    pub fn render(&self, index: usize, frame: &mut Frame) {
+    pub fn render(&self, index: usize, frame: &mut Frame, theme: &Theme) {
         let (color, title) = match self.level {
-            NotificationLevel::Info => (Color::Green, "Info"),
-            NotificationLevel::Warning => (Color::Yellow, "Warning"),
-            NotificationLevel::Error => (Color::Red, "Error"),
+            NotificationLevel::Info => (theme.info, "Info"),
+            NotificationLevel::Warning => (theme.warning, "Warning"),
+            NotificationLevel::Error => (theme.error, "Error"),
         };
 
         let mut text = Text::from(vec![
@@ -1,48 +1,50 @@
-use iwdrs::modes::Mode;
+use std::sync::Arc;
+
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Style},
+    style::Style,
     text::Text,
     widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph},
 };
 
+use crate::layout::popup_area;
+use crate::{config::Config, keymap::Action, mode::ViewMode};
+
 #[derive(Debug)]
 pub struct Reset {
     pub enable: bool,
-    pub selected_mode: Mode,
-    pub current_mode: Mode,
+    pub selected_mode: ViewMode,
+    pub current_mode: ViewMode,
 }
 
 impl Reset {
-    pub fn new(current_mode: Mode) -> Self {
+    pub fn new(current_mode: ViewMode) -> Self {
         Self {
             enable: false,
-            selected_mode: Mode::Station,
+            selected_mode: ViewMode::Station,
             current_mode,
         }
     }
 
-    pub fn render(&self, frame: &mut Frame) {
-        let popup_layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Fill(1),
-                Constraint::Length(10),
-                Constraint::Fill(1),
-            ])
-            .flex(ratatui::layout::Flex::SpaceBetween)
-            .split(frame.area());
-
-        let area = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Fill(1),
-                Constraint::Length(50),
-                Constraint::Fill(1),
-            ])
-            .flex(ratatui::layout::Flex::SpaceBetween)
-            .split(popup_layout[1])[1];
+    pub fn select_next(&mut self) {
+        let index = ViewMode::ALL.iter().position(|m| *m == self.selected_mode);
+        if let Some(mode) = index.and_then(|index| ViewMode::ALL.get(index + 1)) {
+            self.selected_mode = *mode;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        let index = ViewMode::ALL.iter().position(|m| *m == self.selected_mode);
+        if let Some(index) = index.and_then(|index| index.checked_sub(1)) {
+            self.selected_mode = ViewMode::ALL[index];
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame, config: Arc<Config>) {
+        let theme = config.theme;
+
+        let area = popup_area(frame.area(), 50, 11);
 
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -55,31 +57,13 @@ impl Reset {
                     Constraint::Length(1),
                     Constraint::Length(1),
                     Constraint::Length(1),
+                    Constraint::Length(1),
                 ]
                 .as_ref(),
             )
             .split(area);
 
-        let (message_area, station_choice_area, ap_choice_area, help_area) =
-            (chunks[1], chunks[2], chunks[3], chunks[6]);
-
-        let station_choice_area = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Length(2),
-                Constraint::Fill(1),
-                Constraint::Length(2),
-            ])
-            .split(station_choice_area)[1];
-
-        let ap_choice_area = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Length(2),
-                Constraint::Fill(1),
-                Constraint::Length(2),
-            ])
-            .split(ap_choice_area)[1];
+        let (message_area, choices_area, help_area) = (chunks[1], &chunks[2..5], chunks[7]);
 
         let message_area = Layout::default()
             .direction(Direction::Horizontal)
@@ -93,45 +77,36 @@ impl Reset {
             )
             .split(message_area)[1];
 
-        let (ap_text, station_text) = match self.selected_mode {
-            Mode::Ap => match self.current_mode {
-                Mode::Ap => (
-                    Text::from("  Access Point (current)"),
-                    Text::from("   Station"),
-                ),
-                Mode::Station => (
-                    Text::from("  Access Point"),
-                    Text::from("   Station (current)"),
-                ),
-            },
-            Mode::Station => match self.current_mode {
-                Mode::Ap => (
-                    Text::from("   Access Point (current)"),
-                    Text::from("  Station"),
-                ),
-                Mode::Station => (
-                    Text::from("   Access Point"),
-                    Text::from("  Station (current)"),
-                ),
-            },
-        };
+        let selected = config.icons.selected();
+        let choices = ViewMode::ALL.map(|mode| {
+            let marker = if mode == self.selected_mode {
+                format!("{selected}  ")
+            } else {
+                "   ".to_string()
+            };
+            let current = if mode == self.current_mode {
+                " (current)"
+            } else {
+                ""
+            };
+            Paragraph::new(Text::from(format!("{marker}{}{current}", mode.label())))
+                .style(Style::default().fg(theme.text))
+                .block(Block::new().padding(Padding::horizontal(10)))
+        });
 
         let message = Paragraph::new("Select the desired mode:")
             .alignment(Alignment::Center)
-            .style(Style::default().fg(Color::White))
+            .style(Style::default().fg(theme.text))
             .block(Block::new().padding(Padding::uniform(1)));
 
-        let station_choice = Paragraph::new(station_text)
-            .style(Style::default().fg(Color::White))
-            .block(Block::new().padding(Padding::horizontal(10)));
-
-        let ap_choice = Paragraph::new(ap_text)
-            .style(Style::default().fg(Color::White))
-            .block(Block::new().padding(Padding::horizontal(10)));
-
         let help = Paragraph::new(
-            Text::from(" Scroll down: j | Scroll up: k | Enter: Confirm ")
-                .style(Style::default().blue()),
+            Text::from(format!(
+                " Scroll down: {} | Scroll up: {} | {}: Confirm ",
+                config.keymap.key(Action::Down),
+                config.keymap.key(Action::Up),
+                config.keymap.key(Action::Submit),
+            ))
+            .style(Style::default().fg(theme.help)),
         )
         .alignment(Alignment::Center)
         .style(Style::default())
@@ -143,13 +118,22 @@ impl Reset {
             Block::new()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .style(Style::default().green())
-                .border_style(Style::default().fg(Color::Green)),
+                .style(Style::default().fg(theme.border_focused))
+                .border_style(theme.border(true)),
             area,
         );
         frame.render_widget(message, message_area);
-        frame.render_widget(ap_choice, ap_choice_area);
-        frame.render_widget(station_choice, station_choice_area);
+        for (choice, choice_area) in choices.into_iter().zip(choices_area) {
+            let choice_area = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Length(2),
+                    Constraint::Fill(1),
+                    Constraint::Length(2),
+                ])
+                .split(*choice_area)[1];
+            frame.render_widget(choice, choice_area);
+        }
         frame.render_widget(help, help_area);
     }
 }
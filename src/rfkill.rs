@@ -1,7 +1,43 @@
 use anyhow::Result;
+use serde::Serialize;
 use std::fs;
 
-pub fn check() -> Result<()> {
+/// Whether the wifi radio is turned off by rfkill, e.g. in airplane mode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RfkillState {
+    #[default]
+    Unblocked,
+    SoftBlocked,
+    HardBlocked,
+}
+
+impl RfkillState {
+    pub fn is_blocked(&self) -> bool {
+        *self != RfkillState::Unblocked
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RfkillState::Unblocked => "unblocked",
+            RfkillState::SoftBlocked => "soft blocked",
+            RfkillState::HardBlocked => "hard blocked",
+        }
+    }
+
+    pub fn message(&self) -> &'static str {
+        match self {
+            RfkillState::Unblocked => "The wifi device is not blocked",
+            RfkillState::SoftBlocked => {
+                "The wifi device is soft blocked\nRun the following command to unblock it\n$ sudo rfkill unblock wlan"
+            }
+            RfkillState::HardBlocked => "The wifi device is hard blocked",
+        }
+    }
+}
+
+/// Reads the state of the first wlan rfkill switch.
+pub fn state() -> Result<RfkillState> {
     let entries = fs::read_dir("/sys/class/rfkill/")?;
 
     for entry in entries {
@@ -16,26 +52,23 @@ pub fn check() -> Result<()> {
                 let state = fs::read_to_string(state_path)?.trim().parse::<u8>()?;
 
                 // https://www.kernel.org/doc/Documentation/ABI/stable/sysfs-class-rfkill
-                match state {
-                    0 => {
-                        eprintln!(
-                            r"
-The wifi device is soft blocked
-Run the following command to unblock it
-$ sudo rfkill unblock wlan
-                    "
-                        );
-                        std::process::exit(1);
-                    }
-                    2 => {
-                        eprintln!("The wifi device is hard blocked");
-                        std::process::exit(1);
-                    }
-                    _ => {}
-                }
-                break;
+                return Ok(match state {
+                    0 => RfkillState::SoftBlocked,
+                    2 => RfkillState::HardBlocked,
+                    _ => RfkillState::Unblocked,
+                });
             }
         }
     }
+    Ok(RfkillState::Unblocked)
+}
+
+/// Exits when the wifi device is blocked, the TUI can not do anything with it.
+pub fn check() -> Result<()> {
+    let state = state()?;
+    if state.is_blocked() {
+        eprintln!("{}", state.message());
+        std::process::exit(1);
+    }
     Ok(())
 }
@@ -0,0 +1,209 @@
+use std::str::FromStr;
+
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Deserializer};
+
+/// The built-in themes, selected with `theme.name`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeName {
+    #[default]
+    Default,
+    Light,
+    Solarized,
+    Gruvbox,
+}
+
+/// The `[theme]` section of the config file: a built-in theme and the roles it overrides.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    name: ThemeName,
+    #[serde(deserialize_with = "deserialize_color")]
+    border_focused: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    border_inactive: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    header: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    highlight_fg: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    highlight_bg: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    text: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    dimmed: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    input: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    help: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    info: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    warning: Option<Color>,
+    #[serde(deserialize_with = "deserialize_color")]
+    error: Option<Color>,
+}
+
+fn deserialize_color<'de, D>(deserializer: D) -> Result<Option<Color>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let color = String::deserialize(deserializer)?;
+    Color::from_str(&color).map(Some).map_err(|_| {
+        serde::de::Error::custom(format!(
+            "invalid color `{}`, expected a name like `green`, `#rrggbb` or an index from 0 to 255",
+            color
+        ))
+    })
+}
+
+/// The colors used by every render path, by role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Border of the focused block and of the popups
+    pub border_focused: Color,
+    pub border_inactive: Color,
+    /// Column headers of the tables
+    pub header: Color,
+    /// Selected row
+    pub highlight_fg: Color,
+    pub highlight_bg: Color,
+    /// Text of the popups
+    pub text: Color,
+    /// Networks that are out of reach or hidden
+    pub dimmed: Color,
+    /// Background of the input fields
+    pub input: Color,
+    /// Help bar at the bottom
+    pub help: Color,
+    pub info: Color,
+    pub warning: Color,
+    pub error: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::builtin(ThemeName::Default)
+    }
+}
+
+impl Theme {
+    /// Builds the theme from the config, or a theme without colors when `NO_COLOR` is set.
+    pub fn new(config: &ThemeConfig) -> Self {
+        if std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return Self::monochrome();
+        }
+
+        let theme = Self::builtin(config.name);
+        Self {
+            border_focused: config.border_focused.unwrap_or(theme.border_focused),
+            border_inactive: config.border_inactive.unwrap_or(theme.border_inactive),
+            header: config.header.unwrap_or(theme.header),
+            highlight_fg: config.highlight_fg.unwrap_or(theme.highlight_fg),
+            highlight_bg: config.highlight_bg.unwrap_or(theme.highlight_bg),
+            text: config.text.unwrap_or(theme.text),
+            dimmed: config.dimmed.unwrap_or(theme.dimmed),
+            input: config.input.unwrap_or(theme.input),
+            help: config.help.unwrap_or(theme.help),
+            info: config.info.unwrap_or(theme.info),
+            warning: config.warning.unwrap_or(theme.warning),
+            error: config.error.unwrap_or(theme.error),
+        }
+    }
+
+    pub fn builtin(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Default => Self {
+                border_focused: Color::Green,
+                border_inactive: Color::Reset,
+                header: Color::Yellow,
+                highlight_fg: Color::White,
+                highlight_bg: Color::DarkGray,
+                text: Color::White,
+                dimmed: Color::DarkGray,
+                input: Color::DarkGray,
+                help: Color::Blue,
+                info: Color::Green,
+                warning: Color::Yellow,
+                error: Color::Red,
+            },
+            ThemeName::Light => Self {
+                border_focused: Color::Green,
+                border_inactive: Color::Reset,
+                header: Color::Magenta,
+                highlight_fg: Color::Black,
+                highlight_bg: Color::Gray,
+                text: Color::Black,
+                dimmed: Color::Gray,
+                input: Color::Gray,
+                help: Color::Blue,
+                info: Color::Green,
+                warning: Color::Magenta,
+                error: Color::Red,
+            },
+            ThemeName::Solarized => Self {
+                border_focused: Color::Rgb(0x85, 0x99, 0x00),
+                border_inactive: Color::Rgb(0x58, 0x6e, 0x75),
+                header: Color::Rgb(0xb5, 0x89, 0x00),
+                highlight_fg: Color::Rgb(0x93, 0xa1, 0xa1),
+                highlight_bg: Color::Rgb(0x07, 0x36, 0x42),
+                text: Color::Rgb(0x93, 0xa1, 0xa1),
+                dimmed: Color::Rgb(0x58, 0x6e, 0x75),
+                input: Color::Rgb(0x07, 0x36, 0x42),
+                help: Color::Rgb(0x26, 0x8b, 0xd2),
+                info: Color::Rgb(0x85, 0x99, 0x00),
+                warning: Color::Rgb(0xb5, 0x89, 0x00),
+                error: Color::Rgb(0xdc, 0x32, 0x2f),
+            },
+            ThemeName::Gruvbox => Self {
+                border_focused: Color::Rgb(0xb8, 0xbb, 0x26),
+                border_inactive: Color::Rgb(0x66, 0x5c, 0x54),
+                header: Color::Rgb(0xfa, 0xbd, 0x2f),
+                highlight_fg: Color::Rgb(0xeb, 0xdb, 0xb2),
+                highlight_bg: Color::Rgb(0x50, 0x49, 0x45),
+                text: Color::Rgb(0xeb, 0xdb, 0xb2),
+                dimmed: Color::Rgb(0x92, 0x83, 0x74),
+                input: Color::Rgb(0x3c, 0x38, 0x36),
+                help: Color::Rgb(0x83, 0xa5, 0x98),
+                info: Color::Rgb(0xb8, 0xbb, 0x26),
+                warning: Color::Rgb(0xfa, 0xbd, 0x2f),
+                error: Color::Rgb(0xfb, 0x49, 0x34),
+            },
+        }
+    }
+
+    /// Uses the default colors of the terminal, the selected row is shown in reverse video.
+    pub fn monochrome() -> Self {
+        Self {
+            border_focused: Color::Reset,
+            border_inactive: Color::Reset,
+            header: Color::Reset,
+            highlight_fg: Color::Reset,
+            highlight_bg: Color::Reset,
+            text: Color::Reset,
+            dimmed: Color::Reset,
+            input: Color::Reset,
+            help: Color::Reset,
+            info: Color::Reset,
+            warning: Color::Reset,
+            error: Color::Reset,
+        }
+    }
+
+    pub fn border(&self, focused: bool) -> Style {
+        if focused {
+            Style::default().fg(self.border_focused)
+        } else {
+            Style::default().fg(self.border_inactive)
+        }
+    }
+
+    pub fn highlight(&self) -> Style {
+        if self.highlight_bg == Color::Reset {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default().bg(self.highlight_bg).fg(self.highlight_fg)
+        }
+    }
+}
@@ -25,13 +25,16 @@ impl<B: Backend> Tui<B> {
         Self { terminal, events }
     }
 
-    pub fn init(&mut self) -> Result<()>
+    pub fn init(&mut self, mouse: bool) -> Result<()>
     where
         <B as Backend>::Error: Sync + Send,
         <B as Backend>::Error: 'static,
     {
         terminal::enable_raw_mode()?;
-        ratatui::crossterm::execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        ratatui::crossterm::execute!(io::stdout(), EnterAlternateScreen)?;
+        if mouse {
+            ratatui::crossterm::execute!(io::stdout(), EnableMouseCapture)?;
+        }
 
         let panic_hook = panic::take_hook();
         panic::set_hook(Box::new(move |panic| {
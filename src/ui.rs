@@ -1,52 +1,79 @@
 use std::sync::atomic::Ordering;
 
-use iwdrs::modes::Mode;
 use ratatui::Frame;
 
-use crate::app::{App, FocusedBlock};
+use crate::{
+    app::{App, FocusedBlock},
+    device::sorted_devices,
+    help,
+    layout::{is_too_small, render_too_small},
+    mode::ViewMode,
+};
 
 pub fn render(app: &mut App, frame: &mut Frame) {
+    let theme = app.config.theme;
+
     if app.reset.enable {
-        app.reset.render(frame);
+        app.reset.render(frame, app.config.clone());
     } else {
         if !app.device.is_powered {
             app.device
                 .render(frame, app.focused_block, app.config.clone())
         } else {
             let device = app.device.clone();
-            match app.device.mode {
-                Mode::Station => {
+            match app.device.view_mode() {
+                ViewMode::Station => {
                     if let Some(station) = &mut app.device.station {
                         station.render(frame, app.focused_block, &device, app.config.clone());
                     }
                 }
-                Mode::Ap => {
+                ViewMode::Ap => {
                     if let Some(ap) = &mut app.device.ap {
                         ap.render(frame, app.focused_block, &device, app.config.clone());
                         if app.focused_block == FocusedBlock::AccessPointInput {
-                            ap.render_input(frame);
+                            ap.render_input(frame, &theme);
                         }
                     }
                 }
+                ViewMode::P2p => {
+                    if let Some(p2p) = &mut app.device.p2p {
+                        p2p.render(frame, app.focused_block, &device, app.config.clone());
+                    }
+                }
             }
         };
 
         if app.focused_block == FocusedBlock::WpaEntrepriseAuth
             && let Some(eap) = &mut app.auth.eap
         {
-            eap.render(frame);
+            eap.render(frame, &theme);
         }
 
         if app.focused_block == FocusedBlock::AdapterInfos {
-            app.adapter.render(frame, app.device.address.clone());
+            app.adapter
+                .render(frame, app.device.address.clone(), &theme);
+        }
+
+        if app.focused_block == FocusedBlock::Traffic {
+            app.device.traffic.render(frame, &app.device.name, &theme);
+        }
+
+        if app.focused_block == FocusedBlock::DeviceSelector {
+            let devices = sorted_devices(&app.device, &app.other_devices);
+            if let Some(selector) = &mut app.device_selector {
+                selector.render(frame, &devices, &app.device.name, &theme);
+            }
         }
 
         if app.agent.psk_required.load(Ordering::Relaxed) {
             app.focused_block = FocusedBlock::PskAuthKey;
 
-            app.auth
-                .psk
-                .render(frame, app.network_name_requiring_auth.clone());
+            app.auth.psk.render(
+                frame,
+                app.network_name_requiring_auth.clone(),
+                &theme,
+                app.config.icons,
+            );
         }
 
         if app
@@ -55,13 +82,13 @@ pub fn render(app: &mut App, frame: &mut Frame) {
             .load(Ordering::Relaxed)
             && let Some(req) = &app.auth.request_key_passphrase
         {
-            req.render(frame);
+            req.render(frame, &theme, app.config.icons);
         }
 
         if app.agent.password_required.load(Ordering::Relaxed)
             && let Some(req) = &app.auth.request_password
         {
-            req.render(frame);
+            req.render(frame, &theme, app.config.icons);
         }
 
         if app
@@ -70,18 +97,30 @@ pub fn render(app: &mut App, frame: &mut Frame) {
             .load(Ordering::Relaxed)
             && let Some(req) = &app.auth.request_username_and_password
         {
-            req.render(frame);
+            req.render(frame, &theme, app.config.icons);
         }
 
-        if let Some(station) = &app.device.station
-            && let Some(conn) = &station.connct_hidden_network
+        if let Some(station) = &mut app.device.station
+            && let Some(conn) = &mut station.connct_hidden_network
         {
-            conn.render(frame);
+            conn.render(frame, &theme);
         }
 
         // Notifications
         for (index, notification) in app.notifications.iter().enumerate() {
-            notification.render(index, frame);
+            notification.render(index, frame, &theme);
+        }
+    }
+
+    if app.help.is_some() {
+        let sections = help::sections(app);
+        if let Some(help) = &mut app.help {
+            help.render(frame, &sections, &theme);
         }
     }
+
+    // Drawn over the blocks, which still update the focus when a popup shows up
+    if is_too_small(frame.area()) {
+        render_too_small(frame, &theme);
+    }
 }
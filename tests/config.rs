@@ -0,0 +1,90 @@
+//! Loads config files the way `impala config check` does.
+
+use std::path::PathBuf;
+
+use impala::{
+    config::{Config, ConfigError},
+    keymap::Action,
+};
+
+fn load(name: &str, content: &str) -> Result<Config, ConfigError> {
+    let path: PathBuf =
+        std::env::temp_dir().join(format!("impala-{}-{}.toml", name, std::process::id()));
+    std::fs::write(&path, content).unwrap();
+    let config = Config::load_from(&path);
+    std::fs::remove_file(&path).unwrap();
+    config
+}
+
+#[test]
+fn a_key_of_the_config_takes_over_a_builtin_key() {
+    let config = load("takes-over", "[device]\ninfos = \"t\"\n").unwrap();
+
+    assert_eq!(config.keymap.key(Action::DeviceInfos), "t");
+    assert_eq!(config.keymap.key(Action::ShowTraffic), "");
+}
+
+#[test]
+fn conflicting_keys_of_the_config_are_reported() {
+    let error = load(
+        "conflict",
+        "[device]\ninfos = \"t\"\n\n[keymap.device]\ntraffic = \"t\"\n",
+    )
+    .err()
+    .unwrap();
+
+    assert_eq!(error.key.as_deref(), Some("keymap.device.traffic"));
+    assert_eq!(error.line, Some(5));
+    assert!(error.message.contains("`device.infos`"));
+}
+
+#[test]
+fn connect_hidden_can_keep_the_key_of_next_match() {
+    let config = load(
+        "connect-hidden",
+        "[station.new_network]\nconnect_hidden = \"n\"\n",
+    )
+    .unwrap();
+
+    assert_eq!(config.keymap.key(Action::ConnectHiddenNetwork), "n");
+    assert_eq!(config.keymap.key(Action::NextMatch), "");
+    assert_eq!(config.keymap.key(Action::PreviousMatch), "N");
+}
+
+/// The config examples of the Readme, leaving out the simulation scenario.
+fn readme_examples() -> Vec<String> {
+    let readme = include_str!("../Readme.md");
+    readme
+        .split("```toml\n")
+        .skip(1)
+        .filter_map(|block| block.split("```").next())
+        .filter(|block| !block.contains("[[devices]]"))
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn the_readme_examples_are_valid() {
+    let examples = readme_examples();
+    assert_eq!(examples.len(), 3);
+
+    for (i, example) in examples.iter().enumerate() {
+        match load(&format!("readme-{}", i), example) {
+            Ok(config) => assert!(config.warnings.is_empty(), "{:?}", config.warnings),
+            Err(e) => panic!("{}\n{}", e, example),
+        }
+    }
+}
+
+#[test]
+fn duplicate_signal_thresholds_are_reported() {
+    let error = load(
+        "thresholds",
+        "[station]\nsignal_thresholds = [-70, -60, -70]\n",
+    )
+    .err()
+    .unwrap();
+
+    assert_eq!(error.key.as_deref(), Some("station.signal_thresholds"));
+    assert_eq!(error.message, "duplicate signal threshold -70");
+}
@@ -0,0 +1,213 @@
+//! Drives the TUI against the fake backend the way a user would, and checks what is drawn.
+
+use std::{sync::Arc, time::Duration};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use impala::{
+    app::{App, FocusedBlock},
+    backend::{
+        FakeBackend, KnownNetworkInfo, WifiBackend,
+        fake::{FakeDevice, FakeNetwork, FakeState},
+    },
+    config::Config,
+    event::Event,
+    handler::handle_key_events,
+    mode::ViewMode,
+    monitor::Changes,
+    ui,
+};
+use iwdrs::{network::NetworkType, station::State};
+use ratatui::{Terminal, backend::TestBackend};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+struct Tui {
+    app: App,
+    backend: Arc<FakeBackend>,
+    config: Arc<Config>,
+    sender: UnboundedSender<Event>,
+    events: UnboundedReceiver<Event>,
+    terminal: Terminal<TestBackend>,
+}
+
+impl Tui {
+    /// A station connected to Home, with an open network around.
+    async fn new() -> Self {
+        let mut device = FakeDevice::new("wlan0");
+        device.networks = vec![
+            FakeNetwork::new("Home", NetworkType::Psk, -4200),
+            FakeNetwork::new("Coffee Shop", NetworkType::Open, -6700),
+        ];
+        device.state = State::Connected;
+        device.connected_network = Some("Home".to_string());
+
+        let backend = Arc::new(FakeBackend::new(FakeState {
+            devices: vec![device],
+            known_networks: vec![KnownNetworkInfo {
+                name: "Home".to_string(),
+                network_type: NetworkType::Psk,
+                is_autoconnect: true,
+                is_hidden: false,
+                last_connected: None,
+            }],
+        }));
+
+        let config = Arc::new(Config::default());
+        let (sender, events) = mpsc::unbounded_channel();
+        let app = App::new(
+            backend.clone() as Arc<dyn WifiBackend>,
+            sender.clone(),
+            config.clone(),
+            ViewMode::Station,
+            None,
+        )
+        .await
+        .unwrap();
+
+        Self {
+            app,
+            backend,
+            config,
+            sender,
+            events,
+            terminal: Terminal::new(TestBackend::new(120, 40)).unwrap(),
+        }
+    }
+
+    async fn press(&mut self, code: KeyCode) {
+        handle_key_events(
+            KeyEvent::new(code, KeyModifiers::NONE),
+            &mut self.app,
+            self.sender.clone(),
+            self.config.clone(),
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn refresh(&mut self) {
+        self.app.refresh(Changes::all()).await.unwrap();
+    }
+
+    /// Waits for the connections, which run in the background.
+    async fn wait_for_connection(&self, network: &str) {
+        for _ in 0..100 {
+            let state = self.backend.state();
+            if state.devices[0].connected_network.as_deref() == Some(network) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("Not connected to {}", network);
+    }
+
+    fn notifications(&mut self) -> Vec<String> {
+        let mut messages = Vec::new();
+        while let Ok(event) = self.events.try_recv() {
+            if let Event::Notification(notification) = event {
+                messages.push(notification.message);
+            }
+        }
+        messages
+    }
+
+    fn screen(&mut self) -> String {
+        self.terminal
+            .draw(|frame| ui::render(&mut self.app, frame))
+            .unwrap();
+
+        let buffer = self.terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[tokio::test]
+async fn disconnect_then_connect_to_a_new_network() {
+    let mut tui = Tui::new().await;
+    assert!(tui.screen().contains("Connected"));
+    assert_eq!(tui.app.focused_block, FocusedBlock::KnownNetworks);
+
+    // Home is the connected known network, selected first
+    tui.press(KeyCode::Char(' ')).await;
+    tui.refresh().await;
+    assert_eq!(tui.backend.state().devices[0].connected_network, None);
+    assert!(tui.screen().contains("Disconnected"));
+    assert!(
+        tui.notifications()
+            .contains(&"Disconnected from Home".to_string())
+    );
+
+    tui.press(KeyCode::Tab).await;
+    tui.press(KeyCode::Enter).await;
+    tui.wait_for_connection("Coffee Shop").await;
+    tui.refresh().await;
+    assert!(
+        tui.notifications()
+            .contains(&"Connected to Coffee Shop".to_string())
+    );
+
+    // Once connected, the network is known
+    let screen = tui.screen();
+    let known_networks = screen.split("New Networks").next().unwrap();
+    assert!(known_networks.contains("Coffee Shop"));
+    assert!(screen.contains(" Connected "));
+}
+
+#[tokio::test]
+async fn focus_cycles_through_the_blocks() {
+    let mut tui = Tui::new().await;
+
+    for focused_block in [
+        FocusedBlock::NewNetworks,
+        FocusedBlock::Device,
+        FocusedBlock::KnownNetworks,
+    ] {
+        tui.press(KeyCode::Tab).await;
+        assert_eq!(tui.app.focused_block, focused_block);
+    }
+
+    for focused_block in [
+        FocusedBlock::Device,
+        FocusedBlock::NewNetworks,
+        FocusedBlock::KnownNetworks,
+    ] {
+        tui.press(KeyCode::BackTab).await;
+        assert_eq!(tui.app.focused_block, focused_block);
+    }
+}
+
+#[tokio::test]
+async fn network_details_popup_opens_and_closes() {
+    let mut tui = Tui::new().await;
+    assert!(!tui.screen().contains("Network Details"));
+
+    tui.press(KeyCode::Char('i')).await;
+    assert_eq!(tui.app.focused_block, FocusedBlock::NetworkDetails);
+    let screen = tui.screen();
+    assert!(screen.contains("Network Details"));
+    // The SSID in hex
+    assert!(screen.contains("486f6d65"));
+
+    tui.press(KeyCode::Esc).await;
+    assert_eq!(tui.app.focused_block, FocusedBlock::KnownNetworks);
+    assert!(!tui.screen().contains("Network Details"));
+}
+
+#[tokio::test]
+async fn help_lists_the_keys_of_the_focused_block() {
+    let mut tui = Tui::new().await;
+
+    tui.press(KeyCode::F(1)).await;
+    let screen = tui.screen();
+    assert!(screen.contains("Known networks"));
+
+    // Any other key closes the help
+    tui.press(KeyCode::Char('x')).await;
+    assert!(tui.app.help.is_none());
+}